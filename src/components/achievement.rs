@@ -1,10 +1,11 @@
 use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
+use crate::components::save::Saveable;
 
 /// 成就系统组件
 
 /// 成就类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum AchievementType {
     Combat,       // 战斗成就
     Exploration,  // 探索成就
@@ -17,7 +18,7 @@ pub enum AchievementType {
 }
 
 /// 成就状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum AchievementStatus {
     Hidden,       // 隐藏（未解锁且未显示）
     Revealed,     // 已揭示（未解锁但已显示）
@@ -25,7 +26,7 @@ pub enum AchievementStatus {
 }
 
 /// 成就条件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 pub enum AchievementCondition {
     // 战斗条件
     KillEnemy(String, u32),           // 击杀指定敌人X次
@@ -61,7 +62,7 @@ pub enum AchievementCondition {
 }
 
 /// 成就奖励
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 pub struct AchievementReward {
     pub title: String,               // 称号
     pub experience: u32,            // 经验值
@@ -96,7 +97,9 @@ impl AchievementReward {
 }
 
 /// 成就组件
-#[derive(Debug, Clone, Component, Serialize, Deserialize)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct Achievement {
     pub id: String,
     pub achievement_type: AchievementType,
@@ -172,7 +175,9 @@ impl Achievement {
 }
 
 /// 成就日志组件
-#[derive(Debug, Clone, Component)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct AchievementLog {
     pub unlocked_achievements: Vec<String>,  // 已解锁的成就ID
     pub total_points: u32,                    // 总成就点数
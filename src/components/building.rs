@@ -1,13 +1,47 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use crate::components::building_catalog::BuildingDefinition;
+use crate::components::inventory::Inventory;
+use crate::components::item::{amounts, ItemAmounts, ItemId};
+use crate::components::logistics::LogisticsRole;
+use crate::components::save::Saveable;
+
+/// 建筑之间的库存关联距离（格），生产投入可以从范围内其他建筑的库存中取用
+pub const STORAGE_LINK_RANGE: f32 = 2.0;
+
+/// 玩家走到这个距离（格）内时，建筑的产出直接收进基地库存
+pub const OUTPUT_COLLECT_RANGE: f32 = 1.5;
+
+/// 同时存在的工地上限，材料没运到之前不能无限放置
+pub const MAX_CONSTRUCTION_SITES: usize = 3;
+
+/// 建造机器人每次修理恢复的生命值（每秒一次，乘以机器人效率）
+pub const REPAIR_AMOUNT: f32 = 10.0;
+
+/// 修满一座建筑的花费占建造成本的比例
+pub const REPAIR_COST_RATIO: f32 = 0.5;
+
+/// 修理 `amount` 点生命的花费：按修复比例折算修满的花费，向上取整
+pub fn repair_cost(full_cost: &ItemAmounts, amount: f32, max_health: f32) -> ItemAmounts {
+    let fraction = (amount / max_health).clamp(0.0, 1.0);
+    full_cost
+        .iter()
+        .map(|(&item, &cost)| (item, (cost as f32 * fraction).ceil() as u32))
+        .filter(|&(_, cost)| cost > 0)
+        .collect()
+}
 
 /// 建筑类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum BuildingType {
     // 资源生产建筑
     EnergyCollector,    // 能源收集器
     MetalMine,         // 金属矿
     CrystalExtractor,   // 水晶提取器
     OrganicFarm,       // 有机农场
+
+    // 加工建筑
+    Refinery,          // 精炼厂
     
     // 防御建筑
     Turret,            // 炮塔
@@ -25,332 +59,274 @@ pub enum BuildingType {
 }
 
 impl BuildingType {
-    /// 获取建筑的名称
-    pub fn name(&self) -> &str {
-        match self {
-            BuildingType::EnergyCollector => "能源收集器",
-            BuildingType::MetalMine => "金属矿",
-            BuildingType::CrystalExtractor => "水晶提取器",
-            BuildingType::OrganicFarm => "有机农场",
-            BuildingType::Turret => "炮塔",
-            BuildingType::LaserTower => "激光塔",
-            BuildingType::ShieldGenerator => "护盾发生器",
-            BuildingType::Storage => "仓库",
-            BuildingType::RepairStation => "维修站",
-            BuildingType::Radar => "雷达",
-            BuildingType::Teleporter => "传送器",
-            BuildingType::PowerCore => "能量核心",
-        }
-    }
+    /// 所有建筑类型
+    pub const ALL: [BuildingType; 13] = [
+        BuildingType::EnergyCollector,
+        BuildingType::MetalMine,
+        BuildingType::CrystalExtractor,
+        BuildingType::OrganicFarm,
+        BuildingType::Refinery,
+        BuildingType::Turret,
+        BuildingType::LaserTower,
+        BuildingType::ShieldGenerator,
+        BuildingType::Storage,
+        BuildingType::RepairStation,
+        BuildingType::Radar,
+        BuildingType::Teleporter,
+        BuildingType::PowerCore,
+    ];
 
-    /// 获取建筑的颜色
-    pub fn color(&self) -> Color {
-        match self {
-            BuildingType::EnergyCollector => Color::srgb(1.0, 1.0, 0.0),
-            BuildingType::MetalMine => Color::srgb(0.8, 0.8, 0.8),
-            BuildingType::CrystalExtractor => Color::srgb(0.3, 0.8, 0.9),
-            BuildingType::OrganicFarm => Color::srgb(0.3, 0.9, 0.3),
-            BuildingType::Turret => Color::srgb(0.9, 0.3, 0.3),
-            BuildingType::LaserTower => Color::srgb(0.9, 0.5, 0.5),
-            BuildingType::ShieldGenerator => Color::srgb(0.5, 0.5, 0.9),
-            BuildingType::Storage => Color::srgb(0.6, 0.6, 0.6),
-            BuildingType::RepairStation => Color::srgb(0.9, 0.7, 0.3),
-            BuildingType::Radar => Color::srgb(0.3, 0.7, 0.9),
-            BuildingType::Teleporter => Color::srgb(0.7, 0.3, 0.9),
-            BuildingType::PowerCore => Color::srgb(0.9, 0.9, 0.3),
-        }
-    }
-
-    /// 获取建筑的基础属性
-    pub fn base_stats(&self) -> BuildingStats {
-        match self {
-            BuildingType::EnergyCollector => BuildingStats {
-                production_rate: 1.0,
-                storage_capacity: 100,
-                defense: 10.0,
-                range: 0.0,
-                energy_cost: 50,
-                metal_cost: 20,
-                crystal_cost: 0,
-                organic_cost: 0,
-                build_time: 5.0,
-            },
-            BuildingType::MetalMine => BuildingStats {
-                production_rate: 0.5,
-                storage_capacity: 100,
-                defense: 10.0,
-                range: 0.0,
-                energy_cost: 80,
-                metal_cost: 30,
-                crystal_cost: 0,
-                organic_cost: 0,
-                build_time: 8.0,
-            },
-            BuildingType::CrystalExtractor => BuildingStats {
-                production_rate: 0.3,
-                storage_capacity: 50,
-                defense: 10.0,
-                range: 0.0,
-                energy_cost: 100,
-                metal_cost: 40,
-                crystal_cost: 10,
-                organic_cost: 0,
-                build_time: 10.0,
-            },
-            BuildingType::OrganicFarm => BuildingStats {
-                production_rate: 0.8,
-                storage_capacity: 80,
-                defense: 10.0,
-                range: 0.0,
-                energy_cost: 60,
-                metal_cost: 20,
-                crystal_cost: 0,
-                organic_cost: 10,
-                build_time: 6.0,
-            },
-            BuildingType::Turret => BuildingStats {
-                production_rate: 0.0,
-                storage_capacity: 0,
-                defense: 20.0,
-                range: 5.0,
-                energy_cost: 100,
-                metal_cost: 50,
-                crystal_cost: 0,
-                organic_cost: 0,
-                build_time: 8.0,
-            },
-            BuildingType::LaserTower => BuildingStats {
-                production_rate: 0.0,
-                storage_capacity: 0,
-                defense: 30.0,
-                range: 8.0,
-                energy_cost: 150,
-                metal_cost: 80,
-                crystal_cost: 20,
-                organic_cost: 0,
-                build_time: 12.0,
-            },
-            BuildingType::ShieldGenerator => BuildingStats {
-                production_rate: 0.0,
-                storage_capacity: 0,
-                defense: 40.0,
-                range: 3.0,
-                energy_cost: 200,
-                metal_cost: 100,
-                crystal_cost: 30,
-                organic_cost: 0,
-                build_time: 15.0,
-            },
-            BuildingType::Storage => BuildingStats {
-                production_rate: 0.0,
-                storage_capacity: 500,
-                defense: 15.0,
-                range: 0.0,
-                energy_cost: 80,
-                metal_cost: 40,
-                crystal_cost: 0,
-                organic_cost: 0,
-                build_time: 6.0,
-            },
-            BuildingType::RepairStation => BuildingStats {
-                production_rate: 0.0,
-                storage_capacity: 0,
-                defense: 15.0,
-                range: 3.0,
-                energy_cost: 120,
-                metal_cost: 60,
-                crystal_cost: 10,
-                organic_cost: 0,
-                build_time: 10.0,
-            },
-            BuildingType::Radar => BuildingStats {
-                production_rate: 0.0,
-                storage_capacity: 0,
-                defense: 10.0,
-                range: 10.0,
-                energy_cost: 100,
-                metal_cost: 50,
-                crystal_cost: 20,
-                organic_cost: 0,
-                build_time: 8.0,
-            },
-            BuildingType::Teleporter => BuildingStats {
-                production_rate: 0.0,
-                storage_capacity: 0,
-                defense: 10.0,
-                range: 0.0,
-                energy_cost: 300,
-                metal_cost: 150,
-                crystal_cost: 50,
-                organic_cost: 0,
-                build_time: 20.0,
-            },
-            BuildingType::PowerCore => BuildingStats {
-                production_rate: 2.0,
-                storage_capacity: 200,
-                defense: 50.0,
-                range: 0.0,
-                energy_cost: 500,
-                metal_cost: 200,
-                crystal_cost: 100,
-                organic_cost: 0,
-                build_time: 30.0,
-            },
-        }
+    /// 是否为敌人优先进攻的关键建筑
+    pub fn is_key_target(&self) -> bool {
+        matches!(
+            self,
+            BuildingType::PowerCore | BuildingType::Storage | BuildingType::ShieldGenerator
+        )
     }
 }
 
 /// 建筑属性
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 pub struct BuildingStats {
-    pub production_rate: f32,  // 生产速率
+    pub production_rate: f32,  // 生产速率（每秒完成的生产周期数）
     pub storage_capacity: u32, // 存储容量
     pub defense: f32,          // 防御力
     pub range: f32,            // 范围
-    pub energy_cost: u32,      // 能源成本
-    pub metal_cost: u32,       // 金属成本
-    pub crystal_cost: u32,      // 水晶成本
-    pub organic_cost: u32,      // 有机物成本
+    #[serde(default)]
+    pub costs: ItemAmounts,   // 建造成本
     pub build_time: f32,       // 建造时间
+    #[serde(default)]
+    pub inputs: ItemAmounts,  // 每个生产周期消耗的资源
+    #[serde(default)]
+    pub outputs: ItemAmounts, // 每个生产周期产出的资源
+}
+
+/// 升级曲线
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+pub struct UpgradeCurve {
+    pub stat_growth: f32,         // 每级属性增幅（生产、存储、防御）
+    pub cost: ItemAmounts,    // 基础升级成本
+    pub cost_growth: f32,         // 每级升级成本增幅
+    #[serde(default)]
+    pub max_level: Option<u32>,   // 最高等级（None 表示不限）
+}
+
+impl Default for UpgradeCurve {
+    fn default() -> Self {
+        Self {
+            stat_growth: 0.2,
+            cost: amounts(&[(ItemId::ENERGY, 100)]),
+            cost_growth: 0.5,
+            max_level: None,
+        }
+    }
+}
+
+impl UpgradeCurve {
+    /// 指定等级的属性倍率（1 级为基础属性）
+    pub fn stat_multiplier(&self, level: u32) -> f32 {
+        if level <= 1 {
+            1.0
+        } else {
+            1.0 + level as f32 * self.stat_growth
+        }
+    }
+
+    /// 从当前等级升到下一级的成本
+    pub fn upgrade_cost(&self, current_level: u32) -> ItemAmounts {
+        let multiplier = 1.0 + current_level as f32 * self.cost_growth;
+        self.cost
+            .iter()
+            .map(|(&item, &amount)| (item, (amount as f32 * multiplier) as u32))
+            .collect()
+    }
+
+    /// 是否还能继续升级
+    pub fn can_upgrade(&self, current_level: u32) -> bool {
+        self.max_level.is_none_or(|max_level| current_level < max_level)
+    }
 }
 
 /// 建筑组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct Building {
     pub building_type: BuildingType,
     pub level: u32,
-    pub stats: BuildingStats,
-    pub current_storage: u32,
+    pub base_stats: BuildingStats,  // 1 级属性（来自建筑目录）
+    pub stats: BuildingStats,       // 当前等级属性
+    pub upgrade: UpgradeCurve,
     pub production_progress: f32,
     pub is_operational: bool,
 }
 
 impl Building {
+    /// 使用内置默认定义创建建筑
     pub fn new(building_type: BuildingType) -> Self {
-        let stats = building_type.base_stats();
+        Self::from_definition(&BuildingDefinition::builtin(building_type))
+    }
 
+    /// 按建筑目录中的定义创建建筑
+    pub fn from_definition(definition: &BuildingDefinition) -> Self {
         Self {
-            building_type,
+            building_type: definition.building_type,
             level: 1,
-            stats,
-            current_storage: 0,
+            base_stats: definition.stats.clone(),
+            stats: definition.stats.clone(),
+            upgrade: definition.upgrade.clone(),
             production_progress: 0.0,
             is_operational: false,
         }
     }
 
+    /// 建筑目录热重载后按当前等级重新计算属性
+    pub fn apply_definition(&mut self, definition: &BuildingDefinition, cost_reduction: f32) {
+        self.base_stats = definition.stats.clone();
+        self.upgrade = definition.upgrade.clone();
+        self.recalculate_stats(cost_reduction);
+    }
+
+    /// 升级到下一级所需的资源
+    pub fn upgrade_cost(&self) -> ItemAmounts {
+        self.upgrade.upgrade_cost(self.level)
+    }
+
     /// 升级建筑
-    pub fn upgrade(&mut self) {
+    pub fn upgrade(&mut self, cost_reduction: f32) {
         self.level += 1;
-        let upgrade_multiplier = 1.0 + (self.level as f32 * 0.2);
+        self.recalculate_stats(cost_reduction);
+    }
+
+    /// 按等级和升级曲线计算当前属性
+    ///
+    /// 成本从目录重新取值，工程技能的成本降低需要随之重新应用。
+    fn recalculate_stats(&mut self, cost_reduction: f32) {
+        let upgrade_multiplier = self.upgrade.stat_multiplier(self.level);
+        let base_stats = &self.base_stats;
 
-        let base_stats = self.building_type.base_stats();
         self.stats = BuildingStats {
             production_rate: base_stats.production_rate * upgrade_multiplier,
             storage_capacity: (base_stats.storage_capacity as f32 * upgrade_multiplier) as u32,
             defense: base_stats.defense * upgrade_multiplier,
-            range: base_stats.range,
-            energy_cost: base_stats.energy_cost,
-            metal_cost: base_stats.metal_cost,
-            crystal_cost: base_stats.crystal_cost,
-            organic_cost: base_stats.organic_cost,
-            build_time: base_stats.build_time,
+            ..base_stats.clone()
         };
+        self.apply_cost_reduction(cost_reduction);
     }
 
-    /// 生产资源
-    pub fn produce(&mut self, delta_time: f32) -> Option<ResourceType> {
-        if !self.is_operational {
-            return None;
-        }
+    /// 是否有生产配方
+    pub fn has_recipe(&self) -> bool {
+        self.stats.production_rate > 0.0 && !self.stats.outputs.is_empty()
+    }
 
-        if self.stats.production_rate <= 0.0 {
-            return None;
-        }
+    /// 推进生产周期，返回本周期是否已完成、等待结算投入与产出
+    ///
+    /// 结算失败（投入不足、产出放不下）时停在周期末尾，下一帧重试。
+    pub fn advance_production(&mut self, delta_time: f32) -> bool {
+        self.production_progress = (self.production_progress + self.stats.production_rate * delta_time).min(1.0);
+        self.production_progress >= 1.0
+    }
 
-        self.production_progress += self.stats.production_rate * delta_time;
-
-        if self.production_progress >= 1.0 {
-            self.production_progress = 0.0;
-            
-            // 根据建筑类型确定产出资源
-            match self.building_type {
-                BuildingType::EnergyCollector => Some(ResourceType::Energy),
-                BuildingType::MetalMine => Some(ResourceType::Metal),
-                BuildingType::CrystalExtractor => Some(ResourceType::Crystal),
-                BuildingType::OrganicFarm => Some(ResourceType::Organic),
-                _ => None,
-            }
-        } else {
-            None
-        }
+    /// 结算完成，开始下一个周期
+    pub fn finish_cycle(&mut self) {
+        self.production_progress = 0.0;
     }
 
-    /// 检查是否可以建造
-    pub fn can_build(&self, inventory: &Inventory) -> bool {
-        if inventory.energy < self.stats.energy_cost {
-            return false;
-        }
-        if inventory.metal < self.stats.metal_cost {
-            return false;
-        }
-        if inventory.crystal < self.stats.crystal_cost {
-            return false;
-        }
-        if inventory.organic < self.stats.organic_cost {
-            return false;
+    /// 是否承担某个物流角色
+    pub fn has_logistics_role(&self, role: LogisticsRole) -> bool {
+        match role {
+            LogisticsRole::Provider => !self.stats.outputs.is_empty(),
+            LogisticsRole::Requester => !self.stats.inputs.is_empty(),
+            LogisticsRole::Storage => self.building_type == BuildingType::Storage,
         }
-        true
     }
 
-    /// 消耗建造资源
-    pub fn consume_build_resources(&mut self, inventory: &mut Inventory) -> bool {
-        if !self.can_build(inventory) {
-            return false;
+    /// 按比例降低建造成本（向上取整，至少保留 1 点）
+    pub fn apply_cost_reduction(&mut self, reduction: f32) {
+        if reduction <= 0.0 {
+            return;
         }
+        for amount in self.stats.costs.values_mut() {
+            *amount = ((*amount as f32 * (1.0 - reduction)).ceil() as u32).max(1);
+        }
+    }
 
-        inventory.energy -= self.stats.energy_cost;
-        inventory.metal -= self.stats.metal_cost;
-        inventory.crystal -= self.stats.crystal_cost;
-        inventory.organic -= self.stats.organic_cost;
-
-        true
+    /// 修满所需的花费
+    pub fn full_repair_cost(&self) -> ItemAmounts {
+        self.stats
+            .costs
+            .iter()
+            .map(|(&item, &cost)| (item, (cost as f32 * REPAIR_COST_RATIO).ceil() as u32))
+            .collect()
     }
 }
 
-/// 资源类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum ResourceType {
-    Energy,
-    Metal,
-    Crystal,
-    Organic,
+/// 建造工地
+///
+/// 建造成本作为材料由机器人送到工地库存，材料齐全后建造机器人才能施工。
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct ConstructionSite {
+    pub materials: ItemAmounts,   // 所需材料
 }
 
-/// 建筑库存
-#[derive(Debug, Clone, Default, Resource)]
-pub struct Inventory {
-    pub energy: u32,
-    pub metal: u32,
-    pub crystal: u32,
-    pub organic: u32,
+impl ConstructionSite {
+    /// 材料是否已全部送达
+    pub fn is_supplied(&self, inventory: Option<&Inventory>) -> bool {
+        self.materials.is_empty() || inventory.is_some_and(|inventory| inventory.has_all(&self.materials))
+    }
 }
 
 /// 建筑位置
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
 pub struct BuildingPosition {
     pub tile_x: u32,
     pub tile_y: u32,
 }
 
+/// 生产状态（停滞时记录原因）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ProductionState {
+    #[default]
+    Idle,           // 未运转（没有配方、建造中或已停止）
+    Producing,      // 生产中
+    MissingInputs,  // 缺少原料
+    OutputFull,     // 本地库存放不下产出
+    NoPower,        // 断电
+}
+
+impl ProductionState {
+    /// 获取状态名称
+    pub fn name(&self) -> &str {
+        match self {
+            ProductionState::Idle => "空闲",
+            ProductionState::Producing => "生产中",
+            ProductionState::MissingInputs => "缺少原料",
+            ProductionState::OutputFull => "库存已满",
+            ProductionState::NoPower => "断电",
+        }
+    }
+
+    /// 是否为停滞状态
+    pub fn is_stalled(&self) -> bool {
+        matches!(
+            self,
+            ProductionState::MissingInputs | ProductionState::OutputFull | ProductionState::NoPower
+        )
+    }
+}
+
 /// 建筑状态
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct BuildingStatus {
     pub is_constructing: bool,
     pub construction_progress: f32,
     pub is_damaged: bool,
     pub health: f32,
     pub max_health: f32,
+    pub production: ProductionState,    // 生产状态
 }
 
 impl Default for BuildingStatus {
@@ -361,6 +337,81 @@ impl Default for BuildingStatus {
             is_damaged: false,
             health: 100.0,
             max_health: 100.0,
+            production: ProductionState::Idle,
         }
     }
 }
+
+impl BuildingStatus {
+    /// 承受攻击，防御力按护甲公式减伤；返回建筑是否被摧毁
+    pub fn take_damage(&mut self, damage: f32, defense: f32) -> bool {
+        let damage = damage * 100.0 / (100.0 + defense.max(0.0));
+        self.health = (self.health - damage).max(0.0);
+        self.is_damaged = self.health < self.max_health;
+        self.health <= 0.0
+    }
+}
+
+/// 两次基地遇袭提醒之间的最短间隔（秒）
+pub const BASE_ALERT_INTERVAL: f32 = 15.0;
+
+/// 基地遇袭事件：建筑受到敌人攻击时触发，按 `BASE_ALERT_INTERVAL` 节流
+#[derive(Event, Message, Debug, Clone)]
+pub struct BaseUnderAttackEvent {
+    pub position: Vec2,     // 受击建筑位置（世界坐标）
+}
+
+/// 基地遇袭提醒的节流状态
+#[derive(Resource, Debug, Default)]
+pub struct BaseAlert {
+    pub last_alert: Option<f32>,    // 上次提醒的时间（`Time::elapsed_secs`）
+}
+
+impl BaseAlert {
+    /// 距上次提醒已超过间隔时记录本次提醒并返回 true
+    pub fn try_alert(&mut self, now: f32) -> bool {
+        if self.last_alert.is_some_and(|last| now - last < BASE_ALERT_INTERVAL) {
+            return false;
+        }
+        self.last_alert = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defense_reduces_damage_until_destroyed() {
+        let mut status = BuildingStatus::default();
+        assert!(!status.take_damage(50.0, 100.0));
+        assert_eq!(status.health, 75.0);
+        assert!(status.is_damaged);
+        assert!(status.take_damage(500.0, 0.0));
+        assert_eq!(status.health, 0.0);
+    }
+
+    #[test]
+    fn base_alert_is_throttled() {
+        let mut alert = BaseAlert::default();
+        assert!(alert.try_alert(1.0));
+        assert!(!alert.try_alert(1.0 + BASE_ALERT_INTERVAL - 0.1));
+        assert!(alert.try_alert(1.0 + BASE_ALERT_INTERVAL));
+    }
+
+    #[test]
+    fn cost_reduction_survives_upgrades_and_catalog_reloads() {
+        let definition = BuildingDefinition::builtin(BuildingType::Refinery);
+        let mut building = Building::from_definition(&definition);
+        building.apply_cost_reduction(0.5);
+        let reduced = building.stats.costs.clone();
+        assert_ne!(reduced, definition.stats.costs);
+
+        building.upgrade(0.5);
+        assert_eq!(building.stats.costs, reduced);
+
+        building.apply_definition(&definition, 0.5);
+        assert_eq!(building.stats.costs, reduced);
+    }
+}
@@ -0,0 +1,206 @@
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use crate::components::building::{BuildingStats, BuildingType, UpgradeCurve};
+use crate::components::power::PowerProfile;
+use crate::systems::json_catalog::{parse_catalog, JsonCatalog};
+
+/// 建筑定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildingDefinition {
+    pub building_type: BuildingType,
+    pub name: String,               // 显示名称
+    pub color: [f32; 3],            // sRGB 颜色
+    pub stats: BuildingStats,       // 1 级属性（成本、生产、存储、范围）
+    #[serde(default)]
+    pub upgrade: UpgradeCurve,      // 升级曲线
+    #[serde(default)]
+    pub power: PowerProfile,        // 电力参数（发电、用电、蓄电、连接范围）
+}
+
+impl BuildingDefinition {
+    /// 内置默认定义（编译进程序的 buildings 数据文件）
+    pub fn builtin(building_type: BuildingType) -> Self {
+        BuildingCatalogAsset::builtin()
+            .buildings
+            .iter()
+            .find(|d| d.building_type == building_type)
+            .cloned()
+            .unwrap_or_else(|| panic!("内置建筑目录缺少 {:?}", building_type))
+    }
+
+    /// 显示颜色
+    pub fn color(&self) -> Color {
+        Color::srgb(self.color[0], self.color[1], self.color[2])
+    }
+
+    /// 校验数值合法性
+    pub fn validate(&self) -> Result<(), String> {
+        let stats = &self.stats;
+        let values = [
+            ("production_rate", stats.production_rate),
+            ("defense", stats.defense),
+            ("range", stats.range),
+            ("upgrade.stat_growth", self.upgrade.stat_growth),
+            ("upgrade.cost_growth", self.upgrade.cost_growth),
+        ];
+        for (field, value) in values {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("建筑 {:?} 的 {} 无效: {}", self.building_type, field, value));
+            }
+        }
+
+        if !stats.build_time.is_finite() || stats.build_time <= 0.0 {
+            return Err(format!("建筑 {:?} 的建造时间必须大于 0", self.building_type));
+        }
+
+        if stats.production_rate > 0.0 && stats.outputs.is_empty() && !stats.inputs.is_empty() {
+            return Err(format!("建筑 {:?} 有生产投入但没有产出", self.building_type));
+        }
+
+        self.power
+            .validate()
+            .map_err(|e| format!("建筑 {:?} 的 {}", self.building_type, e))?;
+
+        Ok(())
+    }
+}
+
+/// 建筑目录数据文件（`*.buildings.json`）
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct BuildingCatalogAsset {
+    pub buildings: Vec<BuildingDefinition>,
+    /// 防御塔功率 = 能量消耗 × 该系数（缺省时沿用内置值）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tower_power_per_energy_cost: Option<f32>,
+}
+
+impl BuildingCatalogAsset {
+    /// 内置建筑目录，与 assets 中的 `default.buildings.json` 同源
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: LazyLock<BuildingCatalogAsset> = LazyLock::new(|| {
+            parse_catalog(include_str!("../../assets/data/default.buildings.json").as_bytes())
+                .unwrap_or_else(|e| panic!("内置建筑目录无效: {}", e))
+        });
+        &BUILTIN
+    }
+}
+
+impl JsonCatalog for BuildingCatalogAsset {
+    const LABEL: &'static str = "建筑目录";
+    const EXTENSION: &'static str = "buildings.json";
+    const PATH: &'static str = "data/default.buildings.json";
+
+    type Catalog = BuildingCatalog;
+
+    /// 校验所有定义，并检查建筑类型不重复
+    fn validate(&self) -> Result<(), String> {
+        if let Some(factor) = self.tower_power_per_energy_cost {
+            if !factor.is_finite() || factor < 0.0 {
+                return Err(format!("tower_power_per_energy_cost 无效: {}", factor));
+            }
+        }
+
+        let mut types = HashSet::new();
+        for definition in &self.buildings {
+            definition.validate()?;
+            if !types.insert(definition.building_type) {
+                return Err(format!("建筑类型重复定义: {:?}", definition.building_type));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, catalog: &mut BuildingCatalog) -> usize {
+        catalog.apply(self);
+        catalog.definitions.len()
+    }
+}
+
+/// 当前生效的建筑目录
+#[derive(Resource, Debug, Clone)]
+pub struct BuildingCatalog {
+    pub definitions: HashMap<BuildingType, BuildingDefinition>,
+    pub tower_power_per_energy_cost: f32,   // 防御塔功率 = 能量消耗 × 该系数
+}
+
+impl Default for BuildingCatalog {
+    fn default() -> Self {
+        let builtin = BuildingCatalogAsset::builtin();
+        Self {
+            definitions: builtin
+                .buildings
+                .iter()
+                .map(|d| (d.building_type, d.clone()))
+                .collect(),
+            tower_power_per_energy_cost: builtin
+                .tower_power_per_energy_cost
+                .expect("内置建筑目录缺少 tower_power_per_energy_cost"),
+        }
+    }
+}
+
+impl BuildingCatalog {
+    /// 用数据文件替换建筑目录；文件中缺少的建筑类型与系数保留内置默认值
+    pub fn apply(&mut self, asset: &BuildingCatalogAsset) {
+        self.definitions = BuildingType::ALL
+            .iter()
+            .map(|&t| (t, BuildingDefinition::builtin(t)))
+            .collect();
+        for definition in &asset.buildings {
+            self.definitions.insert(definition.building_type, definition.clone());
+        }
+        self.tower_power_per_energy_cost = asset
+            .tower_power_per_energy_cost
+            .unwrap_or(Self::default().tower_power_per_energy_cost);
+    }
+
+    /// 获取建筑定义
+    pub fn get(&self, building_type: BuildingType) -> Option<&BuildingDefinition> {
+        self.definitions.get(&building_type)
+    }
+
+    /// 建筑的电力参数，目录中没有该建筑时取内置值
+    pub fn power(&self, building_type: BuildingType) -> PowerProfile {
+        self.get(building_type)
+            .map(|definition| definition.power.clone())
+            .unwrap_or_else(|| BuildingDefinition::builtin(building_type).power)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_covers_every_building_type() {
+        let catalog = BuildingCatalog::default();
+        for building_type in BuildingType::ALL {
+            assert!(catalog.get(building_type).is_some(), "{:?}", building_type);
+        }
+    }
+
+    #[test]
+    fn tower_power_factor_falls_back_to_builtin() {
+        let builtin = BuildingCatalog::default().tower_power_per_energy_cost;
+        let mut catalog = BuildingCatalog::default();
+
+        catalog.apply(&BuildingCatalogAsset { buildings: Vec::new(), tower_power_per_energy_cost: Some(0.2) });
+        assert_eq!(catalog.tower_power_per_energy_cost, 0.2);
+
+        catalog.apply(&BuildingCatalogAsset { buildings: Vec::new(), tower_power_per_energy_cost: None });
+        assert_eq!(catalog.tower_power_per_energy_cost, builtin);
+    }
+
+    #[test]
+    fn power_falls_back_to_builtin_definition() {
+        let mut catalog = BuildingCatalog::default();
+        catalog.definitions.remove(&BuildingType::PowerCore);
+
+        assert_eq!(
+            catalog.power(BuildingType::PowerCore),
+            BuildingDefinition::builtin(BuildingType::PowerCore).power
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+/// 镜头控制器（挂在主 Camera2d 上）
+///
+/// 镜头平滑移向目标点：默认跟随 `follow` 实体（缺省为玩家），
+/// 手动平移后进入自由视角，按 Home 回到跟随；事件聚焦期间优先看向事件位置。
+#[derive(Component, Debug, Clone)]
+pub struct CameraController {
+    pub follow: Option<Entity>,         // 跟随目标，为空时自动选择玩家
+    pub free_look: bool,                // 自由视角（手动平移后不再跟随）
+    pub anchor: Vec2,                   // 自由视角下的目标中心（世界坐标）
+    pub focus: Option<CameraFocus>,     // 正在聚焦的事件
+    pub zoom: f32,                      // 当前缩放（正交投影 scale，越大视野越广）
+    pub target_zoom: f32,               // 目标缩放
+    pub min_zoom: f32,                  // 最小缩放（最近）
+    pub max_zoom: f32,                  // 最大缩放（最远）
+    pub zoom_step: f32,                 // 每格滚轮的缩放倍率
+    pub pan_speed: f32,                 // 边缘平移速度（屏幕像素/秒）
+    pub edge_margin: f32,               // 触发边缘平移的窗口边距（像素）
+    pub smoothing: f32,                 // 平滑系数，越大越紧
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self {
+            follow: None,
+            free_look: false,
+            anchor: Vec2::ZERO,
+            focus: None,
+            zoom: 1.0,
+            target_zoom: 1.0,
+            min_zoom: 0.5,
+            max_zoom: 2.5,
+            zoom_step: 1.1,
+            pan_speed: 600.0,
+            edge_margin: 16.0,
+            smoothing: 8.0,
+        }
+    }
+}
+
+impl CameraController {
+    /// 按滚轮格数调整目标缩放（正数为拉近）
+    pub fn zoom_by(&mut self, steps: f32) {
+        self.target_zoom = (self.target_zoom / self.zoom_step.powf(steps))
+            .clamp(self.min_zoom, self.max_zoom);
+    }
+
+    /// 切换到自由视角并平移目标中心
+    pub fn pan(&mut self, current: Vec2, delta: Vec2) {
+        if !self.free_look {
+            self.free_look = true;
+            self.anchor = current;
+        }
+        self.anchor += delta;
+        self.focus = None;
+    }
+
+    /// 回到跟随目标
+    pub fn recenter(&mut self) {
+        self.free_look = false;
+        self.focus = None;
+    }
+}
+
+/// 事件聚焦：镜头在一段时间内看向指定位置，结束后恢复原有行为
+#[derive(Debug, Clone, Copy)]
+pub struct CameraFocus {
+    pub position: Vec2,     // 聚焦位置（世界坐标）
+    pub remaining: f32,     // 剩余时间（秒）
+}
+
+/// 镜头跳转事件：让镜头平移到某个位置停留一段时间（如波次来袭）
+#[derive(Event, Message, Debug, Clone)]
+pub struct CameraJumpEvent {
+    pub position: Vec2,     // 目标位置（世界坐标）
+    pub duration: f32,      // 停留时间（秒）
+}
@@ -48,7 +48,7 @@ impl Default for AttackAttributes {
 }
 
 /// 防御属性
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DefenseAttributes {
     pub physical_resistance: f32, // 物理抗性
     pub energy_resistance: f32,   // 能量抗性
@@ -60,6 +60,31 @@ pub struct DefenseAttributes {
     pub block_reduction: f32,      // 格挡减伤
 }
 
+impl DefenseAttributes {
+    /// 对应伤害类型的抗性
+    pub fn resistance(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Physical => self.physical_resistance,
+            DamageType::Energy => self.energy_resistance,
+            DamageType::Explosive => self.explosive_resistance,
+            DamageType::Laser => self.laser_resistance,
+            DamageType::Corrosive => self.corrosive_resistance,
+        }
+    }
+
+    /// 叠加另一组防御属性：抗性与几率相加，格挡减伤取较高者
+    pub fn stack(&mut self, other: &DefenseAttributes) {
+        self.physical_resistance += other.physical_resistance;
+        self.energy_resistance += other.energy_resistance;
+        self.explosive_resistance += other.explosive_resistance;
+        self.laser_resistance += other.laser_resistance;
+        self.corrosive_resistance += other.corrosive_resistance;
+        self.dodge_chance += other.dodge_chance;
+        self.block_chance += other.block_chance;
+        self.block_reduction = self.block_reduction.max(other.block_reduction);
+    }
+}
+
 impl Default for DefenseAttributes {
     fn default() -> Self {
         Self {
@@ -106,13 +131,7 @@ impl Combat {
     /// 获取实际伤害（考虑抗性）
     pub fn calculate_damage(&self, target_defense: &DefenseAttributes) -> f32 {
         let base_damage = self.attack.damage;
-        let resistance = match self.attack.damage_type {
-            DamageType::Physical => target_defense.physical_resistance,
-            DamageType::Energy => target_defense.energy_resistance,
-            DamageType::Explosive => target_defense.explosive_resistance,
-            DamageType::Laser => target_defense.laser_resistance,
-            DamageType::Corrosive => target_defense.corrosive_resistance,
-        };
+        let resistance = target_defense.resistance(self.attack.damage_type);
 
         // 计算抗性减伤
         let damage_after_resistance = base_damage * (1.0 - resistance.min(0.8));
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 use crate::components::equipment::{EquipmentType, EquipmentRarity};
+use crate::components::inventory::Inventory;
+use crate::components::item::ItemId;
 
 /// 装备制造配方
 #[derive(Debug, Clone, Resource)]
@@ -15,44 +17,10 @@ pub struct CraftingRecipe {
 /// 材料需求
 #[derive(Debug, Clone)]
 pub struct MaterialRequirement {
-    pub material_type: MaterialType,
+    pub item: ItemId,
     pub amount: u32,
 }
 
-/// 材料类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum MaterialType {
-    Energy,   // 能源
-    Metal,    // 金属
-    Soil,     // 土壤
-    Crystal,  // 水晶
-    Organic,  // 有机物
-}
-
-impl MaterialType {
-    /// 获取材料的名称
-    pub fn name(&self) -> &str {
-        match self {
-            MaterialType::Energy => "能源",
-            MaterialType::Metal => "金属",
-            MaterialType::Soil => "土壤",
-            MaterialType::Crystal => "水晶",
-            MaterialType::Organic => "有机物",
-        }
-    }
-
-    /// 获取材料的颜色
-    pub fn color(&self) -> Color {
-        match self {
-            MaterialType::Energy => Color::srgb(1.0, 1.0, 0.0),
-            MaterialType::Metal => Color::srgb(0.8, 0.8, 0.8),
-            MaterialType::Soil => Color::srgb(0.6, 0.4, 0.2),
-            MaterialType::Crystal => Color::srgb(0.3, 0.8, 0.9),
-            MaterialType::Organic => Color::srgb(0.3, 0.9, 0.3),
-        }
-    }
-}
-
 impl CraftingRecipe {
     /// 创建新的制造配方
     pub fn new(
@@ -73,17 +41,17 @@ impl CraftingRecipe {
     }
 
     /// 检查是否可以制造
-    pub fn can_craft(&self, inventory: &CraftingInventory) -> bool {
+    pub fn can_craft(&self, inventory: &Inventory) -> bool {
         if !self.unlocked {
             return false;
         }
 
-        if inventory.energy < self.energy_cost {
+        if inventory.count(ItemId::ENERGY) < self.energy_cost {
             return false;
         }
 
         for material in &self.materials {
-            let amount = inventory.get_material(material.material_type);
+            let amount = inventory.count(material.item);
             if amount < material.amount {
                 return false;
             }
@@ -98,58 +66,6 @@ impl CraftingRecipe {
     }
 }
 
-/// 材料库存
-#[derive(Debug, Clone, Default, Resource)]
-pub struct CraftingInventory {
-    pub energy: u32,
-    pub metal: u32,
-    pub soil: u32,
-    pub crystal: u32,
-    pub organic: u32,
-}
-
-impl CraftingInventory {
-    /// 获取材料数量
-    pub fn get_material(&self, material_type: MaterialType) -> u32 {
-        match material_type {
-            MaterialType::Energy => self.energy,
-            MaterialType::Metal => self.metal,
-            MaterialType::Soil => self.soil,
-            MaterialType::Crystal => self.crystal,
-            MaterialType::Organic => self.organic,
-        }
-    }
-
-    /// 添加材料
-    pub fn add_material(&mut self, material_type: MaterialType, amount: u32) {
-        match material_type {
-            MaterialType::Energy => self.energy += amount,
-            MaterialType::Metal => self.metal += amount,
-            MaterialType::Soil => self.soil += amount,
-            MaterialType::Crystal => self.crystal += amount,
-            MaterialType::Organic => self.organic += amount,
-        }
-    }
-
-    /// 扣除材料
-    pub fn remove_material(&mut self, material_type: MaterialType, amount: u32) -> bool {
-        let current = self.get_material(material_type);
-        if current < amount {
-            return false;
-        }
-
-        match material_type {
-            MaterialType::Energy => self.energy -= amount,
-            MaterialType::Metal => self.metal -= amount,
-            MaterialType::Soil => self.soil -= amount,
-            MaterialType::Crystal => self.crystal -= amount,
-            MaterialType::Organic => self.organic -= amount,
-        }
-
-        true
-    }
-}
-
 /// 配方书
 #[derive(Debug, Clone, Default, Resource)]
 pub struct RecipeBook {
@@ -163,7 +79,7 @@ impl RecipeBook {
     }
 
     /// 获取可制造的配方
-    pub fn get_craftable_recipes(&self, inventory: &CraftingInventory) -> Vec<&CraftingRecipe> {
+    pub fn get_craftable_recipes(&self, inventory: &Inventory) -> Vec<&CraftingRecipe> {
         self.recipes
             .iter()
             .filter(|recipe| recipe.can_craft(inventory))
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use crate::components::enemy_catalog::EnemyDefinition;
+use crate::components::save::Saveable;
 
 /// 敌人类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum EnemyType {
     // 机器人敌人
     ScoutBot,          // 侦察机器人
@@ -26,230 +29,26 @@ pub enum EnemyType {
 }
 
 impl EnemyType {
-    /// 获取敌人的名称
-    pub fn name(&self) -> &str {
-        match self {
-            EnemyType::ScoutBot => "侦察机器人",
-            EnemyType::CombatBot => "战斗机器人",
-            EnemyType::HeavyBot => "重型机器人",
-            EnemyType::EliteBot => "精英机器人",
-            EnemyType::WorkerBug => "工虫",
-            EnemyType::WarriorBug => "战虫",
-            EnemyType::SpitterBug => "喷吐虫",
-            EnemyType::TankBug => "坦克虫",
-            EnemyType::QueenBug => "虫后",
-            EnemyType::ScoutBird => "侦察鸟",
-            EnemyType::AttackBird => "攻击鸟",
-            EnemyType::RobotFortress => "机器人堡垒",
-            EnemyType::AIMotherBase => "AI母巢",
-        }
-    }
-
-    /// 获取敌人的颜色
-    pub fn color(&self) -> Color {
-        match self {
-            EnemyType::ScoutBot => Color::srgb(0.5, 0.5, 0.7),
-            EnemyType::CombatBot => Color::srgb(0.7, 0.5, 0.5),
-            EnemyType::HeavyBot => Color::srgb(0.6, 0.4, 0.4),
-            EnemyType::EliteBot => Color::srgb(0.8, 0.6, 0.3),
-            EnemyType::WorkerBug => Color::srgb(0.4, 0.6, 0.3),
-            EnemyType::WarriorBug => Color::srgb(0.6, 0.4, 0.2),
-            EnemyType::SpitterBug => Color::srgb(0.5, 0.5, 0.2),
-            EnemyType::TankBug => Color::srgb(0.4, 0.3, 0.2),
-            EnemyType::QueenBug => Color::srgb(0.7, 0.4, 0.4),
-            EnemyType::ScoutBird => Color::srgb(0.6, 0.6, 0.4),
-            EnemyType::AttackBird => Color::srgb(0.7, 0.4, 0.3),
-            EnemyType::RobotFortress => Color::srgb(0.5, 0.5, 0.5),
-            EnemyType::AIMotherBase => Color::srgb(0.8, 0.3, 0.3),
-        }
-    }
-
-    /// 获取敌人的基础属性
-    pub fn base_stats(&self) -> EnemyStats {
-        match self {
-            // 机器人敌人属性
-            EnemyType::ScoutBot => EnemyStats {
-                health: 50.0,
-                damage: 10.0,
-                attack_speed: 1.5,
-                movement_speed: 1.2,
-                defense: 5.0,
-                attack_range: 3.0,
-                detection_range: 8.0,
-                xp_reward: 20,
-            },
-            EnemyType::CombatBot => EnemyStats {
-                health: 100.0,
-                damage: 20.0,
-                attack_speed: 1.0,
-                movement_speed: 1.0,
-                defense: 10.0,
-                attack_range: 2.0,
-                detection_range: 6.0,
-                xp_reward: 40,
-            },
-            EnemyType::HeavyBot => EnemyStats {
-                health: 200.0,
-                damage: 30.0,
-                attack_speed: 0.8,
-                movement_speed: 0.6,
-                defense: 20.0,
-                attack_range: 2.0,
-                detection_range: 5.0,
-                xp_reward: 80,
-            },
-            EnemyType::EliteBot => EnemyStats {
-                health: 300.0,
-                damage: 40.0,
-                attack_speed: 1.2,
-                movement_speed: 1.0,
-                defense: 25.0,
-                attack_range: 3.0,
-                detection_range: 10.0,
-                xp_reward: 150,
-            },
-            
-            // 机器虫敌人属性
-            EnemyType::WorkerBug => EnemyStats {
-                health: 30.0,
-                damage: 5.0,
-                attack_speed: 1.0,
-                movement_speed: 1.5,
-                defense: 2.0,
-                attack_range: 1.0,
-                detection_range: 4.0,
-                xp_reward: 10,
-            },
-            EnemyType::WarriorBug => EnemyStats {
-                health: 80.0,
-                damage: 15.0,
-                attack_speed: 1.2,
-                movement_speed: 1.3,
-                defense: 8.0,
-                attack_range: 1.5,
-                detection_range: 6.0,
-                xp_reward: 30,
-            },
-            EnemyType::SpitterBug => EnemyStats {
-                health: 60.0,
-                damage: 20.0,
-                attack_speed: 0.8,
-                movement_speed: 1.0,
-                defense: 5.0,
-                attack_range: 5.0,
-                detection_range: 7.0,
-                xp_reward: 40,
-            },
-            EnemyType::TankBug => EnemyStats {
-                health: 250.0,
-                damage: 25.0,
-                attack_speed: 0.6,
-                movement_speed: 0.5,
-                defense: 30.0,
-                attack_range: 1.5,
-                detection_range: 4.0,
-                xp_reward: 100,
-            },
-            EnemyType::QueenBug => EnemyStats {
-                health: 500.0,
-                damage: 50.0,
-                attack_speed: 0.5,
-                movement_speed: 0.3,
-                defense: 40.0,
-                attack_range: 3.0,
-                detection_range: 12.0,
-                xp_reward: 300,
-            },
-            
-            // 机器鸟敌人属性
-            EnemyType::ScoutBird => EnemyStats {
-                health: 25.0,
-                damage: 8.0,
-                attack_speed: 2.0,
-                movement_speed: 2.0,
-                defense: 3.0,
-                attack_range: 2.0,
-                detection_range: 10.0,
-                xp_reward: 15,
-            },
-            EnemyType::AttackBird => EnemyStats {
-                health: 40.0,
-                damage: 12.0,
-                attack_speed: 1.8,
-                movement_speed: 1.8,
-                defense: 5.0,
-                attack_range: 2.5,
-                detection_range: 8.0,
-                xp_reward: 25,
-            },
-            
-            // 敌人建筑属性
-            EnemyType::RobotFortress => EnemyStats {
-                health: 1000.0,
-                damage: 0.0,
-                attack_speed: 0.0,
-                movement_speed: 0.0,
-                defense: 50.0,
-                attack_range: 0.0,
-                detection_range: 15.0,
-                xp_reward: 200,
-            },
-            EnemyType::AIMotherBase => EnemyStats {
-                health: 2000.0,
-                damage: 0.0,
-                attack_speed: 0.0,
-                movement_speed: 0.0,
-                defense: 80.0,
-                attack_range: 0.0,
-                detection_range: 20.0,
-                xp_reward: 500,
-            },
-        }
-    }
-
-    /// 获取敌人的AI行为类型
-    pub fn ai_behavior(&self) -> AIBehavior {
-        match self {
-            EnemyType::ScoutBot => AIBehavior::Patrol,
-            EnemyType::CombatBot => AIBehavior::Aggressive,
-            EnemyType::HeavyBot => AIBehavior::Guard,
-            EnemyType::EliteBot => AIBehavior::Boss,
-            
-            EnemyType::WorkerBug => AIBehavior::Passive,
-            EnemyType::WarriorBug => AIBehavior::Aggressive,
-            EnemyType::SpitterBug => AIBehavior::Ranged,
-            EnemyType::TankBug => AIBehavior::Guard,
-            EnemyType::QueenBug => AIBehavior::Boss,
-            
-            EnemyType::ScoutBird => AIBehavior::Patrol,
-            EnemyType::AttackBird => AIBehavior::Aggressive,
-            
-            EnemyType::RobotFortress => AIBehavior::Spawn,
-            EnemyType::AIMotherBase => AIBehavior::Spawn,
-        }
-    }
-
-    /// 获取敌人的攻击类型
-    pub fn attack_type(&self) -> AttackType {
-        match self {
-            EnemyType::ScoutBot | EnemyType::CombatBot | 
-            EnemyType::HeavyBot | EnemyType::EliteBot => AttackType::Laser,
-            
-            EnemyType::WorkerBug | EnemyType::WarriorBug | 
-            EnemyType::TankBug => AttackType::Melee,
-            
-            EnemyType::SpitterBug => AttackType::Spit,
-            EnemyType::QueenBug => AttackType::Summon,
-            
-            EnemyType::ScoutBird | EnemyType::AttackBird => AttackType::Melee,
-            
-            EnemyType::RobotFortress | EnemyType::AIMotherBase => AttackType::None,
-        }
-    }
+    /// 所有敌人类型
+    pub const ALL: [EnemyType; 13] = [
+        EnemyType::ScoutBot,
+        EnemyType::CombatBot,
+        EnemyType::HeavyBot,
+        EnemyType::EliteBot,
+        EnemyType::WorkerBug,
+        EnemyType::WarriorBug,
+        EnemyType::SpitterBug,
+        EnemyType::TankBug,
+        EnemyType::QueenBug,
+        EnemyType::ScoutBird,
+        EnemyType::AttackBird,
+        EnemyType::RobotFortress,
+        EnemyType::AIMotherBase,
+    ];
 }
 
 /// 敌人属性
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
 pub struct EnemyStats {
     pub health: f32,           // 生命值
     pub damage: f32,           // 伤害
@@ -262,7 +61,7 @@ pub struct EnemyStats {
 }
 
 /// AI行为类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum AIBehavior {
     Passive,      // 被动（不主动攻击）
     Patrol,       // 巡逻（在区域内巡逻）
@@ -274,7 +73,7 @@ pub enum AIBehavior {
 }
 
 /// 攻击类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum AttackType {
     None,    // 无（建筑类敌人）
     Melee,   // 近战
@@ -284,10 +83,15 @@ pub enum AttackType {
 }
 
 /// 敌人组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct Enemy {
     pub enemy_type: EnemyType,
+    pub archetype: String,          // 敌人原型 ID（对应原型数据中的 id）
     pub stats: EnemyStats,
+    pub behavior: AIBehavior,
+    pub attack_type: AttackType,
     pub current_health: f32,
     pub level: u32,
     pub ai_state: AIState,
@@ -296,23 +100,22 @@ pub struct Enemy {
 }
 
 impl Enemy {
+    /// 使用内置默认原型创建敌人
     pub fn new(enemy_type: EnemyType, level: u32) -> Self {
-        let base_stats = enemy_type.base_stats();
-        let level_multiplier = 1.0 + (level as f32 * 0.2);
+        Self::from_definition(&EnemyDefinition::builtin(enemy_type), level)
+    }
+
+    /// 按原型数据创建敌人
+    pub fn from_definition(definition: &EnemyDefinition, level: u32) -> Self {
+        let stats = Self::scaled_stats(&definition.stats, level);
 
         Self {
-            enemy_type,
-            stats: EnemyStats {
-                health: base_stats.health * level_multiplier,
-                damage: base_stats.damage * level_multiplier,
-                attack_speed: base_stats.attack_speed,
-                movement_speed: base_stats.movement_speed,
-                defense: base_stats.defense * level_multiplier,
-                attack_range: base_stats.attack_range,
-                detection_range: base_stats.detection_range,
-                xp_reward: (base_stats.xp_reward as f32 * level_multiplier) as u32,
-            },
-            current_health: base_stats.health * level_multiplier,
+            enemy_type: definition.enemy_type,
+            archetype: definition.id.clone(),
+            stats,
+            behavior: definition.behavior,
+            attack_type: definition.attack_type,
+            current_health: stats.health,
             level,
             ai_state: AIState::Idle,
             attack_cooldown: 0.0,
@@ -320,6 +123,34 @@ impl Enemy {
         }
     }
 
+    /// 原型数据热重载后重新套用属性，保持当前生命百分比
+    pub fn apply_definition(&mut self, definition: &EnemyDefinition) {
+        let health_percentage = self.health_percentage();
+
+        self.enemy_type = definition.enemy_type;
+        self.archetype = definition.id.clone();
+        self.stats = Self::scaled_stats(&definition.stats, self.level);
+        self.behavior = definition.behavior;
+        self.attack_type = definition.attack_type;
+        self.current_health = self.stats.health * health_percentage;
+    }
+
+    /// 按等级缩放基础属性
+    fn scaled_stats(base_stats: &EnemyStats, level: u32) -> EnemyStats {
+        let level_multiplier = 1.0 + (level as f32 * 0.2);
+
+        EnemyStats {
+            health: base_stats.health * level_multiplier,
+            damage: base_stats.damage * level_multiplier,
+            attack_speed: base_stats.attack_speed,
+            movement_speed: base_stats.movement_speed,
+            defense: base_stats.defense * level_multiplier,
+            attack_range: base_stats.attack_range,
+            detection_range: base_stats.detection_range,
+            xp_reward: (base_stats.xp_reward as f32 * level_multiplier) as u32,
+        }
+    }
+
     /// 检查是否死亡
     pub fn is_dead(&self) -> bool {
         self.current_health <= 0.0
@@ -343,7 +174,7 @@ impl Enemy {
 }
 
 /// AI状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum AIState {
     Idle,          // 闲置
     Patrol,        // 巡逻
@@ -353,67 +184,9 @@ pub enum AIState {
     Dead,          // 死亡
 }
 
-/// 敌人生成配置
-#[derive(Debug, Clone, Resource)]
-pub struct EnemySpawnConfig {
-    pub scout_bot_chance: f32,
-    pub combat_bot_chance: f32,
-    pub heavy_bot_chance: f32,
-    pub elite_bot_chance: f32,
-    pub worker_bug_chance: f32,
-    pub warrior_bug_chance: f32,
-    pub spitter_bug_chance: f32,
-    pub tank_bug_chance: f32,
-    pub queen_bug_chance: f32,
-}
-
-impl Default for EnemySpawnConfig {
-    fn default() -> Self {
-        Self {
-            scout_bot_chance: 0.2,
-            combat_bot_chance: 0.3,
-            heavy_bot_chance: 0.1,
-            elite_bot_chance: 0.05,
-            worker_bug_chance: 0.15,
-            warrior_bug_chance: 0.2,
-            spitter_bug_chance: 0.15,
-            tank_bug_chance: 0.1,
-            queen_bug_chance: 0.02,
-        }
-    }
-}
-
-impl EnemySpawnConfig {
-    /// 随机生成敌人类型
-    pub fn random_enemy_type(&self, rng: &mut impl rand::Rng) -> EnemyType {
-        let roll = rng.gen::<f32>();
-        let mut cumulative = 0.0;
-
-        let types = [
-            (self.scout_bot_chance, EnemyType::ScoutBot),
-            (self.combat_bot_chance, EnemyType::CombatBot),
-            (self.heavy_bot_chance, EnemyType::HeavyBot),
-            (self.elite_bot_chance, EnemyType::EliteBot),
-            (self.worker_bug_chance, EnemyType::WorkerBug),
-            (self.warrior_bug_chance, EnemyType::WarriorBug),
-            (self.spitter_bug_chance, EnemyType::SpitterBug),
-            (self.tank_bug_chance, EnemyType::TankBug),
-            (self.queen_bug_chance, EnemyType::QueenBug),
-        ];
-
-        for (chance, enemy_type) in types {
-            cumulative += chance;
-            if roll < cumulative {
-                return enemy_type;
-            }
-        }
-
-        EnemyType::CombatBot // 默认返回战斗机器人
-    }
-}
-
 /// 敌人波次配置
-#[derive(Debug, Clone, Resource)]
+#[derive(Debug, Clone, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
 pub struct EnemyWaveConfig {
     pub current_wave: u32,
     pub enemies_per_wave: u32,
@@ -432,8 +205,48 @@ impl Default for EnemyWaveConfig {
     }
 }
 
+impl EnemyWaveConfig {
+    /// 本波敌人数量
+    pub fn enemy_count(&self, scan_intensity: f32) -> u32 {
+        let count = self.enemies_per_wave as f32 * self.difficulty_multiplier * (1.0 + scan_intensity);
+        (count.round() as u32).max(1)
+    }
+
+    /// 本波敌人等级
+    pub fn enemy_level(&self, scan_intensity: f32, day: u32) -> u32 {
+        1 + day.saturating_sub(1) / 3 + (scan_intensity * 5.0).round() as u32
+    }
+
+    /// 进入下一波
+    pub fn advance(&mut self) {
+        self.current_wave += 1;
+        self.difficulty_multiplier += 0.1;
+    }
+}
+
+/// 波次开始后最多等待清除的时间（秒），超时后不再等剩余的敌人，照常调度下一波
+pub const WAVE_TIMEOUT: f32 = 120.0;
+
+/// 波次调度状态
+#[derive(Debug, Clone, Default, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct EnemyWaveState {
+    pub active_wave: Option<u32>,   // 正在进行的波次
+    pub wave_timer: f32,            // 距下一波的倒计时（秒）；波次进行中为放弃等待前的剩余时间
+    pub last_day: u32,              // 上次每日结算的日期
+}
+
+/// 波次成员标记
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct WaveMember {
+    pub wave: u32,
+}
+
 /// 敌人大本营组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct EnemyBase {
     pub base_type: EnemyType,
     pub spawn_timer: f32,
@@ -495,7 +308,8 @@ impl EnemyBase {
 }
 
 /// 敌人位置组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
 pub struct EnemyPosition {
     pub tile_x: u32,
     pub tile_y: u32,
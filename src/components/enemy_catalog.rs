@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use crate::components::enemy::{AIBehavior, AttackType, EnemyStats, EnemyType};
+use crate::systems::json_catalog::{parse_catalog, JsonCatalog};
+
+/// 生成威胁等级（决定月相扫描强度对生成权重的影响）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SpawnThreat {
+    Light,        // 轻型（扫描强度越高越少）
+    #[default]
+    Standard,     // 标准（不受扫描强度影响）
+    Heavy,        // 重型（扫描强度越高越多）
+}
+
+impl SpawnThreat {
+    /// 按 AI 扫描强度缩放生成权重
+    pub fn weight_multiplier(&self, scan_intensity: f32) -> f32 {
+        match self {
+            SpawnThreat::Light => (1.0 - scan_intensity).max(0.1),
+            SpawnThreat::Standard => 1.0,
+            SpawnThreat::Heavy => 1.0 + scan_intensity * 2.0,
+        }
+    }
+}
+
+/// 敌人原型定义
+///
+/// 同一 `enemy_type` 可以有多个原型，设计师可以不改代码新增敌人变种。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyDefinition {
+    pub id: String,                 // 唯一标识
+    pub enemy_type: EnemyType,      // 所属类别（决定特殊逻辑，如虫后召唤）
+    pub name: String,               // 显示名称
+    pub color: [f32; 3],            // sRGB 颜色
+    pub stats: EnemyStats,          // 1 级基础属性（含经验奖励）
+    pub behavior: AIBehavior,       // AI 行为
+    pub attack_type: AttackType,    // 攻击类型
+    #[serde(default)]
+    pub spawn_weight: f32,          // 波次生成权重（0 表示不参与随机生成）
+    #[serde(default)]
+    pub threat: SpawnThreat,        // 生成威胁等级
+}
+
+impl EnemyDefinition {
+    /// 内置默认原型（编译进程序的 enemies 数据文件中该类型的首个原型）
+    pub fn builtin(enemy_type: EnemyType) -> Self {
+        EnemyCatalogAsset::builtin()
+            .enemies
+            .iter()
+            .find(|d| d.enemy_type == enemy_type)
+            .cloned()
+            .unwrap_or_else(|| panic!("内置敌人原型数据缺少 {:?}", enemy_type))
+    }
+
+    /// 显示颜色
+    pub fn color(&self) -> Color {
+        Color::srgb(self.color[0], self.color[1], self.color[2])
+    }
+
+    /// 校验数值合法性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("敌人原型 id 不能为空".to_string());
+        }
+
+        let stats = &self.stats;
+        let values = [
+            ("health", stats.health),
+            ("damage", stats.damage),
+            ("attack_speed", stats.attack_speed),
+            ("movement_speed", stats.movement_speed),
+            ("defense", stats.defense),
+            ("attack_range", stats.attack_range),
+            ("detection_range", stats.detection_range),
+            ("spawn_weight", self.spawn_weight),
+        ];
+        for (field, value) in values {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("敌人原型 {} 的 {} 无效: {}", self.id, field, value));
+            }
+        }
+
+        if stats.health <= 0.0 {
+            return Err(format!("敌人原型 {} 的生命值必须大于 0", self.id));
+        }
+
+        Ok(())
+    }
+}
+
+/// 敌人原型数据文件（`*.enemies.json`）
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyCatalogAsset {
+    pub enemies: Vec<EnemyDefinition>,
+}
+
+impl EnemyCatalogAsset {
+    /// 内置敌人原型（`default.enemies.json` 编译进程序）
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: LazyLock<EnemyCatalogAsset> = LazyLock::new(|| {
+            parse_catalog(include_str!("../../assets/data/default.enemies.json").as_bytes())
+                .unwrap_or_else(|e| panic!("内置敌人原型数据无效: {}", e))
+        });
+        &BUILTIN
+    }
+}
+
+impl JsonCatalog for EnemyCatalogAsset {
+    const LABEL: &'static str = "敌人原型数据";
+    const EXTENSION: &'static str = "enemies.json";
+    const PATH: &'static str = "data/default.enemies.json";
+
+    type Catalog = EnemyCatalog;
+
+    /// 校验所有原型，并检查 id 唯一
+    fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for definition in &self.enemies {
+            definition.validate()?;
+            if !ids.insert(definition.id.as_str()) {
+                return Err(format!("敌人原型 id 重复: {}", definition.id));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, catalog: &mut EnemyCatalog) -> usize {
+        catalog.apply(self);
+        catalog.definitions.len()
+    }
+}
+
+/// 当前生效的敌人原型表
+#[derive(Resource, Debug, Clone)]
+pub struct EnemyCatalog {
+    pub definitions: Vec<EnemyDefinition>,
+}
+
+impl Default for EnemyCatalog {
+    fn default() -> Self {
+        Self {
+            definitions: EnemyCatalogAsset::builtin().enemies.clone(),
+        }
+    }
+}
+
+impl EnemyCatalog {
+    /// 用数据文件替换原型表；文件中缺少的敌人类型补上内置默认值
+    pub fn apply(&mut self, asset: &EnemyCatalogAsset) {
+        let mut definitions = asset.enemies.clone();
+        for enemy_type in EnemyType::ALL {
+            if !definitions.iter().any(|d| d.enemy_type == enemy_type) {
+                definitions.push(EnemyDefinition::builtin(enemy_type));
+            }
+        }
+        self.definitions = definitions;
+    }
+
+    /// 按 id 查找原型
+    pub fn get(&self, id: &str) -> Option<&EnemyDefinition> {
+        self.definitions.iter().find(|d| d.id == id)
+    }
+
+    /// 获取某个敌人类型的首个原型
+    pub fn for_type(&self, enemy_type: EnemyType) -> EnemyDefinition {
+        self.definitions
+            .iter()
+            .find(|d| d.enemy_type == enemy_type)
+            .cloned()
+            .unwrap_or_else(|| EnemyDefinition::builtin(enemy_type))
+    }
+
+    /// 按生成权重随机选择波次敌人，权重随扫描强度调整
+    pub fn random_spawn(&self, scan_intensity: f32, rng: &mut impl rand::Rng) -> Option<&EnemyDefinition> {
+        let weights: Vec<f32> = self
+            .definitions
+            .iter()
+            .map(|d| d.spawn_weight * d.threat.weight_multiplier(scan_intensity))
+            .collect();
+
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = rng.gen::<f32>() * total;
+        for (definition, weight) in self.definitions.iter().zip(weights) {
+            if weight <= 0.0 {
+                continue;
+            }
+            if roll < weight {
+                return Some(definition);
+            }
+            roll -= weight;
+        }
+
+        self.definitions.iter().rev().find(|d| d.spawn_weight > 0.0)
+    }
+
+    /// 从指定类型中等概率选择一个原型（基地出兵使用）
+    pub fn random_of_types(&self, types: &[EnemyType], rng: &mut impl rand::Rng) -> Option<&EnemyDefinition> {
+        let candidates: Vec<&EnemyDefinition> = self
+            .definitions
+            .iter()
+            .filter(|d| types.contains(&d.enemy_type))
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        Some(candidates[rng.gen_range(0..candidates.len())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_covers_every_enemy_type() {
+        for enemy_type in EnemyType::ALL {
+            assert_eq!(EnemyDefinition::builtin(enemy_type).enemy_type, enemy_type);
+        }
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let mut asset = EnemyCatalogAsset::builtin().clone();
+        asset.enemies.push(asset.enemies[0].clone());
+        let json = serde_json::to_vec(&asset).unwrap();
+
+        let error = parse_catalog::<EnemyCatalogAsset>(&json).unwrap_err();
+        assert!(error.contains("重复"), "{}", error);
+    }
+}
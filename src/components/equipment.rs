@@ -1,7 +1,10 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use crate::components::combat::DefenseAttributes;
+use crate::components::save::Saveable;
 
 /// 装备槽位类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum EquipmentSlot {
     Weapon,     // 武器
     Armor,      // 护甲
@@ -9,7 +12,7 @@ pub enum EquipmentSlot {
 }
 
 /// 装备类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum EquipmentType {
     // 武器
     LaserGun,       // 激光枪
@@ -57,6 +60,46 @@ impl EquipmentType {
         }
     }
 
+    /// 移动速度修正（比例，负数为减速），重型装备拖慢移动
+    pub fn move_speed_modifier(&self) -> f32 {
+        match self {
+            EquipmentType::PlasmaCannon => -0.05,
+            EquipmentType::Railgun => -0.1,
+            EquipmentType::HeavyArmor => -0.15,
+            EquipmentType::EnergyShield => -0.05,
+            EquipmentType::LaserGun | EquipmentType::EMPBlaster | EquipmentType::LightArmor |
+            EquipmentType::SolarPanel | EquipmentType::BatteryPack |
+            EquipmentType::TargetingSystem => 0.0,
+        }
+    }
+
+    /// 护甲提供的抗性、闪避与格挡（武器和饰品没有）
+    pub fn defense_attributes(&self) -> DefenseAttributes {
+        match self {
+            EquipmentType::LightArmor => DefenseAttributes {
+                physical_resistance: 0.1,
+                dodge_chance: 0.05,
+                ..default()
+            },
+            EquipmentType::HeavyArmor => DefenseAttributes {
+                physical_resistance: 0.25,
+                explosive_resistance: 0.15,
+                block_chance: 0.1,
+                ..default()
+            },
+            EquipmentType::EnergyShield => DefenseAttributes {
+                energy_resistance: 0.2,
+                laser_resistance: 0.25,
+                block_chance: 0.15,
+                block_reduction: 0.6,
+                ..default()
+            },
+            EquipmentType::LaserGun | EquipmentType::PlasmaCannon | EquipmentType::EMPBlaster |
+            EquipmentType::Railgun | EquipmentType::SolarPanel | EquipmentType::BatteryPack |
+            EquipmentType::TargetingSystem => DefenseAttributes::default(),
+        }
+    }
+
     /// 获取装备的基础属性
     pub fn base_stats(&self) -> EquipmentStats {
         match self {
@@ -145,7 +188,7 @@ impl EquipmentType {
 }
 
 /// 装备属性
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
 pub struct EquipmentStats {
     pub damage: f32,          // 伤害
     pub attack_speed: f32,    // 攻击速度
@@ -169,7 +212,9 @@ impl Default for EquipmentStats {
 }
 
 /// 装备组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct Equipment {
     pub equipment_type: EquipmentType,
     pub level: u32,
@@ -217,7 +262,7 @@ impl Equipment {
 }
 
 /// 装备稀有度
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum EquipmentRarity {
     Common,      // 普通（白色）
     Uncommon,    // 稀有（绿色）
@@ -251,10 +296,14 @@ impl EquipmentRarity {
 }
 
 /// 装备栏组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
 pub struct EquipmentBar {
+    #[entities]
     pub weapon: Option<Entity>,
+    #[entities]
     pub armor: Option<Entity>,
+    #[entities]
     pub accessory: Option<Entity>,
 }
 
@@ -306,4 +355,27 @@ impl EquipmentBar {
 
         total
     }
+
+    /// 在基础防御属性上叠加所有已装备物品的防御属性
+    pub fn defense_attributes(&self, equipment_query: &Query<&Equipment>) -> DefenseAttributes {
+        let mut defense = DefenseAttributes::default();
+        for equipment in [self.weapon, self.armor, self.accessory]
+            .into_iter()
+            .flatten()
+            .filter_map(|entity| equipment_query.get(entity).ok())
+        {
+            defense.stack(&equipment.equipment_type.defense_attributes());
+        }
+        defense
+    }
+
+    /// 已装备物品的移动速度修正之和
+    pub fn move_speed_modifier(&self, equipment_query: &Query<&Equipment>) -> f32 {
+        [self.weapon, self.armor, self.accessory]
+            .into_iter()
+            .flatten()
+            .filter_map(|entity| equipment_query.get(entity).ok())
+            .map(|equipment| equipment.equipment_type.move_speed_modifier())
+            .sum()
+    }
 }
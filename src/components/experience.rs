@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+
+/// 升级经验曲线
+///
+/// 从 `level` 升到下一级需要 `base_xp * growth^(level - 1)` 点经验。
+/// 插件用 `init_resource` 注册，提前 `insert_resource` 即可替换曲线。
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct LevelCurve {
+    pub base_xp: u32,       // 1 级升到 2 级所需经验
+    pub growth: f32,        // 每级所需经验的增长倍率
+    pub max_level: u32,     // 等级上限
+}
+
+impl Default for LevelCurve {
+    fn default() -> Self {
+        Self {
+            base_xp: 100,
+            growth: 1.5,
+            max_level: 30,
+        }
+    }
+}
+
+impl LevelCurve {
+    /// 当前等级升到下一级所需经验，已满级时返回 None
+    pub fn xp_to_next(&self, level: u32) -> Option<u32> {
+        if level >= self.max_level {
+            return None;
+        }
+        let required = self.base_xp as f32 * self.growth.powi(level.saturating_sub(1) as i32);
+        Some((required.round() as u32).max(1))
+    }
+}
+
+/// 玩家经验（挂在玩家实体上，随存档保存）
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct PlayerExperience {
+    pub current: u32,   // 当前等级内已积累的经验
+    pub total: u32,     // 累计获得的经验
+}
+
+impl PlayerExperience {
+    /// 增加经验并按曲线结算升级，返回提升的等级数
+    pub fn gain(&mut self, amount: u32, level: &mut u32, curve: &LevelCurve) -> u32 {
+        self.total = self.total.saturating_add(amount);
+        self.current = self.current.saturating_add(amount);
+
+        let mut levels_gained = 0;
+        while let Some(required) = curve.xp_to_next(*level) {
+            if self.current < required {
+                break;
+            }
+            self.current -= required;
+            *level += 1;
+            levels_gained += 1;
+        }
+
+        // 满级后不再积累等级内经验
+        if curve.xp_to_next(*level).is_none() {
+            self.current = 0;
+        }
+
+        levels_gained
+    }
+
+    /// 当前等级的进度（0~1），满级时为 1
+    pub fn progress(&self, level: u32, curve: &LevelCurve) -> f32 {
+        match curve.xp_to_next(level) {
+            Some(required) => self.current as f32 / required as f32,
+            None => 1.0,
+        }
+    }
+}
+
+/// 经验来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExperienceSource {
+    Enemy,          // 击杀敌人
+    Quest,          // 完成任务
+    Achievement,    // 解锁成就
+}
+
+/// 玩家获得经验
+#[derive(Event, Message, Debug, Clone)]
+pub struct ExperienceGainEvent {
+    pub amount: u32,
+    pub source: ExperienceSource,
+}
+
+/// 玩家升级（每提升一级触发一次）
+#[derive(Event, Message, Debug, Clone)]
+pub struct LevelUpEvent {
+    pub level: u32,     // 升级后的等级
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve() -> LevelCurve {
+        LevelCurve { base_xp: 100, growth: 1.5, max_level: 4 }
+    }
+
+    #[test]
+    fn level_curve_grows_and_stops_at_max_level() {
+        let curve = curve();
+        assert_eq!(curve.xp_to_next(1), Some(100));
+        assert_eq!(curve.xp_to_next(2), Some(150));
+        assert_eq!(curve.xp_to_next(3), Some(225));
+        assert_eq!(curve.xp_to_next(4), None);
+    }
+
+    #[test]
+    fn gain_carries_over_surplus_across_levels() {
+        let curve = curve();
+        let mut experience = PlayerExperience::default();
+        let mut level = 1;
+
+        assert_eq!(experience.gain(90, &mut level, &curve), 0);
+        assert_eq!((level, experience.current), (1, 90));
+
+        // 90 + 180 = 270：升到 2 级剩 170，再升到 3 级剩 20
+        assert_eq!(experience.gain(180, &mut level, &curve), 2);
+        assert_eq!((level, experience.current, experience.total), (3, 20, 270));
+        assert!((experience.progress(level, &curve) - 20.0 / 225.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn gain_clears_level_experience_at_max_level() {
+        let curve = curve();
+        let mut experience = PlayerExperience::default();
+        let mut level = 1;
+
+        assert_eq!(experience.gain(10_000, &mut level, &curve), 3);
+        assert_eq!((level, experience.current, experience.total), (4, 0, 10_000));
+        assert_eq!(experience.progress(level, &curve), 1.0);
+    }
+}
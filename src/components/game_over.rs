@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+
+/// 本局统计（随存档保存）
+#[derive(Debug, Clone, Default, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct RunStats {
+    pub enemies_killed: u32,    // 击杀敌人数
+}
+
+/// 结算信息（玩家死亡时生成，供结算界面显示）
+#[derive(Debug, Clone, Resource)]
+pub struct GameOverSummary {
+    pub days_survived: u32,     // 存活天数
+    pub enemies_killed: u32,    // 击杀敌人数
+    pub buildings: usize,       // 建筑数量
+}
+
+/// 重新开局请求
+#[derive(Event, Message, Debug, Clone)]
+pub struct RestartGameEvent;
+
+/// 重新开局标记：存在时进入 `InGame` 的各插件把本局状态恢复为初始值，进入后移除
+#[derive(Debug, Clone, Resource)]
+pub struct Restarting;
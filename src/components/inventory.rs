@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use crate::components::item::{ItemAmounts, ItemId, ItemRegistry};
+
+/// 库存
+///
+/// 挂在玩家、仓库建筑、机器人和基地实体上。
+/// 每种物品按注册表中的堆叠上限占用格子，`slots` 与 `capacity` 同时生效。
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Inventory {
+    pub items: ItemAmounts,         // 各物品的数量
+    pub slots: Option<u32>,         // 格子上限（None 表示不限）
+    pub capacity: Option<u32>,      // 物品总量上限（None 表示不限）
+}
+
+/// 一格中的物品堆
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u32,
+}
+
+impl Inventory {
+    /// 按格子数限制的库存（玩家背包）
+    pub fn with_slots(slots: u32) -> Self {
+        Self {
+            slots: Some(slots),
+            ..default()
+        }
+    }
+
+    /// 按总量限制的库存（机器人货舱、仓库建筑）
+    pub fn with_capacity(capacity: u32) -> Self {
+        Self {
+            capacity: Some(capacity),
+            ..default()
+        }
+    }
+
+    /// 获取某种物品的数量
+    pub fn count(&self, item: ItemId) -> u32 {
+        self.items.get(&item).copied().unwrap_or(0)
+    }
+
+    /// 所有物品的总数量
+    pub fn total(&self) -> u32 {
+        self.items.values().sum()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 按堆叠上限拆分后的物品堆
+    pub fn stacks(&self, registry: &ItemRegistry) -> Vec<ItemStack> {
+        let mut stacks = Vec::new();
+        for (&item, &count) in &self.items {
+            let stack_size = registry.stack_size(item);
+            let mut remaining = count;
+            while remaining > 0 {
+                let stack = remaining.min(stack_size);
+                stacks.push(ItemStack { item, count: stack });
+                remaining -= stack;
+            }
+        }
+        stacks
+    }
+
+    /// 已占用的格子数
+    pub fn used_slots(&self, registry: &ItemRegistry) -> u32 {
+        self.items
+            .iter()
+            .map(|(&item, &count)| count.div_ceil(registry.stack_size(item)))
+            .sum()
+    }
+
+    /// 还能放入的某种物品数量
+    pub fn space_for(&self, item: ItemId, registry: &ItemRegistry) -> u32 {
+        let by_capacity = self
+            .capacity
+            .map_or(u32::MAX, |capacity| capacity.saturating_sub(self.total()));
+
+        let by_slots = self.slots.map_or(u32::MAX, |slots| {
+            let stack_size = registry.stack_size(item);
+            let count = self.count(item);
+            // 未满的那一堆还能补齐的数量
+            let partial = count.div_ceil(stack_size) * stack_size - count;
+            let free_slots = slots.saturating_sub(self.used_slots(registry));
+            partial.saturating_add(free_slots.saturating_mul(stack_size))
+        });
+
+        by_capacity.min(by_slots)
+    }
+
+    /// 是否已放不下任何新物品
+    pub fn is_full(&self, registry: &ItemRegistry) -> bool {
+        self.capacity.is_some_and(|capacity| self.total() >= capacity)
+            || self.slots.is_some_and(|slots| self.used_slots(registry) >= slots)
+    }
+
+    /// 放入物品，放不下的部分不放入，返回实际放入的数量
+    pub fn add(&mut self, item: ItemId, amount: u32, registry: &ItemRegistry) -> u32 {
+        let added = amount.min(self.space_for(item, registry));
+        if added > 0 {
+            *self.items.entry(item).or_insert(0) += added;
+        }
+        added
+    }
+
+    /// 检查物品是否足够
+    pub fn has_all(&self, amounts: &ItemAmounts) -> bool {
+        amounts
+            .iter()
+            .all(|(&item, &amount)| self.count(item) >= amount)
+    }
+
+    /// 取出至多 `amount` 个物品，返回实际取出的数量
+    pub fn take(&mut self, item: ItemId, amount: u32) -> u32 {
+        let Some(count) = self.items.get_mut(&item) else { return 0; };
+        let taken = amount.min(*count);
+        *count -= taken;
+        if *count == 0 {
+            self.items.remove(&item);
+        }
+        taken
+    }
+
+    /// 扣除物品，不足时不做任何扣除并返回 false
+    pub fn remove(&mut self, item: ItemId, amount: u32) -> bool {
+        if self.count(item) < amount {
+            return false;
+        }
+        self.take(item, amount);
+        true
+    }
+
+    /// 扣除一组物品，任一不足时不做任何扣除并返回 false
+    pub fn remove_all(&mut self, amounts: &ItemAmounts) -> bool {
+        if !self.has_all(amounts) {
+            return false;
+        }
+
+        for (&item, &amount) in amounts {
+            self.take(item, amount);
+        }
+
+        true
+    }
+
+    /// 把物品转移到另一个库存，受对方空间限制，返回实际转移的数量
+    pub fn transfer_to(&mut self, target: &mut Inventory, item: ItemId, amount: u32, registry: &ItemRegistry) -> u32 {
+        let amount = amount.min(self.count(item)).min(target.space_for(item, registry));
+        let moved = target.add(item, amount, registry);
+        self.take(item, moved);
+        moved
+    }
+
+    /// 把全部物品转移到另一个库存，返回实际转移的物品
+    pub fn transfer_all_to(&mut self, target: &mut Inventory, registry: &ItemRegistry) -> ItemAmounts {
+        let items: Vec<_> = self.items.iter().map(|(&item, &count)| (item, count)).collect();
+
+        items
+            .into_iter()
+            .map(|(item, count)| (item, self.transfer_to(target, item, count, registry)))
+            .filter(|&(_, moved)| moved > 0)
+            .collect()
+    }
+}
+
+/// 基地
+///
+/// 基地实体上的 `Inventory` 承担建造、制造、升级的消耗与建筑产出；
+/// 机器人把它当作停靠在玩家身边的卸货点。
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Base;
+
+/// 库存所在处
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryOwner {
+    Base,               // 基地实体的库存
+    Entity(Entity),     // 挂有 `Inventory` 组件的实体
+}
+
+/// 在两个库存之间转移物品
+///
+/// 按对方剩余空间尽量转移，放不下或不足的部分留在原处。
+#[derive(Event, Message, Debug, Clone)]
+pub struct TransferItemsEvent {
+    pub from: InventoryOwner,
+    pub to: InventoryOwner,
+    pub items: ItemAmounts,
+}
@@ -0,0 +1,282 @@
+use bevy::prelude::*;
+use serde::de::{self, Visitor};
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::sync::{LazyLock, Mutex};
+use crate::systems::json_catalog::{parse_catalog, JsonCatalog};
+
+/// 物品 ID
+///
+/// 取自物品数据文件中的字符串 ID，驻留为 `'static` 以便像枚举一样复制和比较。
+/// 存档中按 RON 标识符写出（如 `Energy: 90`），因此 ID 只能由字母、数字和下划线组成。
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Reflect)]
+#[reflect(opaque)]
+#[reflect(Debug, Hash, PartialEq, Serialize, Deserialize)]
+pub struct ItemId(&'static str);
+
+impl ItemId {
+    // 代码中直接引用的物品，定义仍以数据文件为准
+    pub const ENERGY: ItemId = ItemId("Energy");       // 能源
+    pub const METAL: ItemId = ItemId("Metal");         // 金属
+    pub const CRYSTAL: ItemId = ItemId("Crystal");     // 水晶
+    pub const ORGANIC: ItemId = ItemId("Organic");     // 有机物
+    pub const SOIL: ItemId = ItemId("Soil");           // 土壤
+
+    /// 由字符串 ID 构造，同一 ID 只驻留一份
+    pub fn new(id: &str) -> Self {
+        static INTERNED: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(default);
+
+        let mut interned = INTERNED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&id) = interned.get(id) {
+            return ItemId(id);
+        }
+        let id: &'static str = Box::leak(id.to_owned().into_boxed_str());
+        interned.insert(id);
+        ItemId(id)
+    }
+
+    /// 字符串 ID
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+
+    /// 检查 ID 能否作为存档中的标识符
+    pub fn validate(&self) -> Result<(), String> {
+        let mut chars = self.0.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("物品 ID \"{}\" 只能由字母、数字和下划线组成，且不能以数字开头", self.0));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl fmt::Display for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for ItemId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // 与旧版枚举写法一致：RON 中是标识符，JSON 中是字符串
+        serializer.serialize_unit_variant("ItemId", 0, self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IdVisitor;
+
+        impl Visitor<'_> for IdVisitor {
+            type Value = ItemId;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("物品 ID")
+            }
+
+            fn visit_str<E: de::Error>(self, id: &str) -> Result<ItemId, E> {
+                Ok(ItemId::new(id))
+            }
+        }
+
+        deserializer.deserialize_identifier(IdVisitor)
+    }
+}
+
+/// 物品分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemCategory {
+    Energy,     // 能源
+    Mineral,    // 矿物
+    Organic,    // 有机
+}
+
+impl ItemCategory {
+    /// 获取分类名称
+    pub fn name(&self) -> &str {
+        match self {
+            ItemCategory::Energy => "能源",
+            ItemCategory::Mineral => "矿物",
+            ItemCategory::Organic => "有机",
+        }
+    }
+}
+
+/// 按物品计的数量（成本、生产投入与产出、库存内容）
+pub type ItemAmounts = BTreeMap<ItemId, u32>;
+
+/// 由 (物品, 数量) 列表构造数量表，忽略数量为 0 的项
+pub fn amounts(pairs: &[(ItemId, u32)]) -> ItemAmounts {
+    pairs.iter().copied().filter(|&(_, amount)| amount > 0).collect()
+}
+
+/// 数量表的可读描述（物品名称取自注册表）
+pub fn describe_amounts(amounts: &ItemAmounts, registry: &ItemRegistry) -> String {
+    amounts
+        .iter()
+        .map(|(&item, amount)| format!("{} {}", registry.name(item), amount))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 物品定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemDefinition {
+    pub id: ItemId,
+    pub name: String,               // 显示名称
+    pub category: ItemCategory,
+    pub stack_size: u32,            // 单格堆叠上限
+    pub color: [f32; 3],            // sRGB 颜色
+}
+
+impl ItemDefinition {
+    /// 显示颜色
+    pub fn color(&self) -> Color {
+        Color::srgb(self.color[0], self.color[1], self.color[2])
+    }
+
+    /// 校验数值合法性
+    pub fn validate(&self) -> Result<(), String> {
+        self.id.validate()?;
+        if self.stack_size == 0 {
+            return Err(format!("物品 {:?} 的堆叠上限必须大于 0", self.id));
+        }
+        Ok(())
+    }
+}
+
+/// 物品数据文件（`*.items.json`）
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct ItemCatalogAsset {
+    pub items: Vec<ItemDefinition>,
+}
+
+impl ItemCatalogAsset {
+    /// 内置物品数据（`default.items.json`）
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: LazyLock<ItemCatalogAsset> = LazyLock::new(|| {
+            parse_catalog(include_str!("../../assets/data/default.items.json").as_bytes())
+                .unwrap_or_else(|e| panic!("内置物品数据无效: {}", e))
+        });
+        &BUILTIN
+    }
+}
+
+impl JsonCatalog for ItemCatalogAsset {
+    const LABEL: &'static str = "物品数据";
+    const EXTENSION: &'static str = "items.json";
+    const PATH: &'static str = "data/default.items.json";
+
+    type Catalog = ItemRegistry;
+
+    /// 校验所有定义，并检查物品 ID 不重复
+    fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for definition in &self.items {
+            definition.validate()?;
+            if !ids.insert(definition.id) {
+                return Err(format!("物品重复定义: {:?}", definition.id));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, registry: &mut ItemRegistry) -> usize {
+        registry.apply(self);
+        registry.definitions.len()
+    }
+}
+
+/// 当前生效的物品注册表
+///
+/// 在数据文件加载前就填入内置物品数据，代码中引用的物品因此总有定义；
+/// 其他数据文件引用了未定义的物品时，按 ID 显示且不可堆叠。
+#[derive(Resource, Debug, Clone)]
+pub struct ItemRegistry {
+    pub definitions: HashMap<ItemId, ItemDefinition>,
+}
+
+impl Default for ItemRegistry {
+    fn default() -> Self {
+        Self {
+            definitions: ItemCatalogAsset::builtin()
+                .items
+                .iter()
+                .map(|d| (d.id, d.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl ItemRegistry {
+    /// 用数据文件替换物品定义；文件中缺少的物品保留内置默认值
+    pub fn apply(&mut self, asset: &ItemCatalogAsset) {
+        *self = Self::default();
+        for definition in &asset.items {
+            self.definitions.insert(definition.id, definition.clone());
+        }
+    }
+
+    /// 获取物品定义
+    pub fn get(&self, id: ItemId) -> Option<&ItemDefinition> {
+        self.definitions.get(&id)
+    }
+
+    /// 物品显示名称
+    pub fn name(&self, id: ItemId) -> &str {
+        self.get(id).map_or(id.as_str(), |definition| &definition.name)
+    }
+
+    /// 单格堆叠上限
+    pub fn stack_size(&self, id: ItemId) -> u32 {
+        self.get(id).map_or(1, |definition| definition.stack_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_defines_items_used_in_code() {
+        let registry = ItemRegistry::default();
+        for id in [ItemId::ENERGY, ItemId::METAL, ItemId::CRYSTAL, ItemId::ORGANIC, ItemId::SOIL] {
+            assert_eq!(registry.get(id).map(|definition| definition.id), Some(id));
+        }
+    }
+
+    #[test]
+    fn ids_are_interned_by_value() {
+        let id = ItemId::new("Metal");
+        assert_eq!(id, ItemId::METAL);
+        assert!(std::ptr::eq(id.as_str(), ItemId::new(&String::from("Metal")).as_str()));
+    }
+
+    #[test]
+    fn ids_keep_their_legacy_save_format() {
+        let amounts = amounts(&[(ItemId::new("Alloy"), 3)]);
+        let text = ron::to_string(&amounts).unwrap();
+        assert_eq!(text, "{Alloy:3}");
+        assert_eq!(ron::from_str::<ItemAmounts>(&text).unwrap(), amounts);
+
+        let json: ItemAmounts = serde_json::from_str(r#"{"Alloy": 3}"#).unwrap();
+        assert_eq!(json, amounts);
+    }
+
+    #[test]
+    fn rejects_ids_that_are_not_identifiers() {
+        assert!(ItemId::new("iron_ore").validate().is_ok());
+        assert!(ItemId::new("iron-ore").validate().is_err());
+        assert!(ItemId::new("2x").validate().is_err());
+        assert!(ItemId::new("").validate().is_err());
+    }
+}
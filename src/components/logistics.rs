@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use crate::components::inventory::InventoryOwner;
+use crate::components::item::{ItemAmounts, ItemId};
+
+/// 物流规划间隔（秒）
+pub const LOGISTICS_PLAN_INTERVAL: f32 = 1.0;
+
+/// 需求方囤积的投入量（按生产周期计）
+pub const REQUEST_BUFFER_CYCLES: u32 = 3;
+
+/// 单个搬运任务的最大数量
+pub const HAUL_BATCH: u32 = 20;
+
+/// 供应方攒够这么多产出才派车（库存已满时不受限）
+pub const MIN_HAUL: u32 = 5;
+
+/// 机器人取货、送货时与建筑的距离
+pub const DOCK_RADIUS: f32 = 32.0;
+
+/// 机器人向基地（玩家身边）交货的距离
+pub const BASE_DOCK_RADIUS: f32 = 50.0;
+
+/// 建筑在物流网络中的角色（一个建筑可以同时承担多个角色）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogisticsRole {
+    Provider,   // 供应方：产出等待运走
+    Requester,  // 需求方：配方投入等待送达
+    Storage,    // 仓储：中转站，接收多余产出并补给需求方
+}
+
+/// 搬运任务：从供应方、仓库或基地取货，送到需求方、工地、仓库或基地
+#[derive(Debug, Clone)]
+pub struct HaulJob {
+    pub id: u64,
+    pub from: InventoryOwner,       // 取货处
+    pub pickup: Vec2,               // 取货位置（发布时）
+    pub to: InventoryOwner,         // 送货处
+    pub item: ItemId,
+    pub amount: u32,
+    pub carrier: Option<Entity>,    // 承接的机器人
+}
+
+/// 仓库的空余容量（规划时的快照，机器人卸货时据此选择仓库）
+#[derive(Debug, Clone)]
+pub struct HubSlot {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub free: u32,
+}
+
+/// 物流任务板
+///
+/// 规划系统定期按供需重新发布未承接的任务，已承接的任务一直保留到送达或放弃。
+#[derive(Resource, Debug, Clone, Default)]
+pub struct JobBoard {
+    pub jobs: Vec<HaulJob>,
+    pub hubs: Vec<HubSlot>,
+    next_id: u64,
+}
+
+impl JobBoard {
+    /// 发布任务
+    pub fn post(&mut self, from: InventoryOwner, pickup: Vec2, to: InventoryOwner, item: ItemId, amount: u32) {
+        self.next_id += 1;
+        self.jobs.push(HaulJob {
+            id: self.next_id,
+            from,
+            pickup,
+            to,
+            item,
+            amount,
+            carrier: None,
+        });
+    }
+
+    /// 撤下所有未承接的任务
+    pub fn clear_open(&mut self) {
+        self.jobs.retain(|job| job.carrier.is_some());
+    }
+
+    /// 机器人承接离它最近的任务，数量不超过它的空余容量
+    pub fn claim(&mut self, carrier: Entity, position: Vec2, space: u32) -> Option<HaulJob> {
+        if space == 0 {
+            return None;
+        }
+
+        let job = self
+            .jobs
+            .iter_mut()
+            .filter(|job| job.carrier.is_none())
+            .min_by(|a, b| a.pickup.distance(position).total_cmp(&b.pickup.distance(position)))?;
+        job.carrier = Some(carrier);
+        job.amount = job.amount.min(space);
+        Some(job.clone())
+    }
+
+    /// 任务完成或放弃
+    pub fn finish(&mut self, id: u64) {
+        self.jobs.retain(|job| job.id != id);
+    }
+
+    /// 正在送往某处的某种物品数量
+    pub fn incoming(&self, to: InventoryOwner, item: ItemId) -> u32 {
+        self.jobs
+            .iter()
+            .filter(|job| job.to == to && job.item == item)
+            .map(|job| job.amount)
+            .sum()
+    }
+
+    /// 正在送往某处的物品总量
+    pub fn incoming_total(&self, to: InventoryOwner) -> u32 {
+        self.jobs.iter().filter(|job| job.to == to).map(|job| job.amount).sum()
+    }
+
+    /// 已预定从某处取走的某种物品数量
+    pub fn outgoing(&self, from: InventoryOwner, item: ItemId) -> u32 {
+        self.jobs
+            .iter()
+            .filter(|job| job.from == from && job.item == item)
+            .map(|job| job.amount)
+            .sum()
+    }
+
+    /// 最近的有空余容量的仓库
+    pub fn nearest_hub(&self, position: Vec2) -> Option<&HubSlot> {
+        self.hubs
+            .iter()
+            .filter(|hub| hub.free > 0)
+            .min_by(|a, b| a.position.distance(position).total_cmp(&b.position.distance(position)))
+    }
+}
+
+/// 运送阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStage {
+    Pickup,     // 前往取货
+    Dropoff,    // 前往送货
+}
+
+/// 一次运送
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub job: Option<u64>,               // 对应的任务（卸下自身货物时为 None）
+    pub from: Option<InventoryOwner>,   // 取货处（货物已在机器人身上时为 None）
+    pub to: InventoryOwner,
+    pub items: ItemAmounts,
+    pub stage: DeliveryStage,
+}
+
+impl Delivery {
+    /// 承接任务板上的任务
+    pub fn from_job(job: &HaulJob) -> Self {
+        Self {
+            job: Some(job.id),
+            from: Some(job.from),
+            to: job.to,
+            items: [(job.item, job.amount)].into_iter().collect(),
+            stage: DeliveryStage::Pickup,
+        }
+    }
+
+    /// 把机器人身上的货物卸到某处
+    pub fn unload(items: ItemAmounts, to: InventoryOwner) -> Self {
+        Self {
+            job: None,
+            from: None,
+            to,
+            items,
+            stage: DeliveryStage::Dropoff,
+        }
+    }
+}
+
+/// 机器人的运送状态（不写入存档，读档后重新接单）
+#[derive(Component, Debug, Clone, Default)]
+pub struct Courier {
+    pub delivery: Option<Delivery>,
+}
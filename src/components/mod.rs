@@ -12,3 +12,14 @@ pub mod defense;
 pub mod quest;
 pub mod achievement;
 pub mod save;
+pub mod pathfinding;
+pub mod enemy_catalog;
+pub mod building_catalog;
+pub mod camera;
+pub mod game_over;
+pub mod experience;
+pub mod skill;
+pub mod item;
+pub mod inventory;
+pub mod power;
+pub mod logistics;
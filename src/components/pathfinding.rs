@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// 路径缓存组件（每个寻路实体一份）
+#[derive(Component, Clone, Debug)]
+pub struct PathCache {
+    pub goal: Option<UVec2>,          // 当前路径的目标地块
+    pub waypoints: VecDeque<UVec2>,   // 剩余路径点（不含起点）
+    pub dirty: bool,                  // 需要重新计算
+    pub unreachable: bool,            // 上次计算未找到路径
+}
+
+impl Default for PathCache {
+    fn default() -> Self {
+        Self {
+            goal: None,
+            waypoints: VecDeque::new(),
+            dirty: true,
+            unreachable: false,
+        }
+    }
+}
+
+impl PathCache {
+    /// 标记路径失效，下次移动时重新计算
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// 清空路径
+    pub fn clear(&mut self) {
+        self.goal = None;
+        self.waypoints.clear();
+        self.dirty = true;
+        self.unreachable = false;
+    }
+}
+
+/// 寻路障碍物（防御墙、建筑占据的地块）
+#[derive(Resource, Clone, Debug, Default)]
+pub struct NavObstacles {
+    pub blocked: HashSet<UVec2>,
+}
+
+impl NavObstacles {
+    pub fn is_blocked(&self, tile: UVec2) -> bool {
+        self.blocked.contains(&tile)
+    }
+}
+
+/// 流场（积分场 + 方向场）：所有追同一目标的敌人共享
+#[derive(Clone, Debug, Default)]
+pub struct FlowField {
+    pub size: UVec2,
+    pub goal: UVec2,                // 目标地块
+    pub blocked: HashSet<UVec2>,    // 构建时的障碍快照，障碍变化时据此增量修补
+    pub costs: Vec<f32>,            // 积分场：到目标的累计代价
+    pub directions: Vec<IVec2>,     // 方向场：指向代价最低的相邻地块
+}
+
+/// 各追击目标（玩家、关键建筑）的流场
+#[derive(Resource, Clone, Debug, Default)]
+pub struct FlowFields {
+    pub fields: HashMap<Entity, FlowField>,
+}
+
+impl FlowFields {
+    /// 目标实体的流场，没有时（如防御机器人）由调用方改用 A* 路径
+    pub fn get(&self, target: Entity) -> Option<&FlowField> {
+        self.fields.get(&target)
+    }
+}
+
+impl FlowField {
+    fn index(&self, tile: UVec2) -> Option<usize> {
+        if tile.x < self.size.x && tile.y < self.size.y {
+            Some((tile.y * self.size.x + tile.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// 到目标的累计代价，不可达时为无穷大
+    pub fn cost_at(&self, tile: UVec2) -> f32 {
+        self.index(tile)
+            .and_then(|i| self.costs.get(i).copied())
+            .unwrap_or(f32::INFINITY)
+    }
+
+    /// 地块的流动方向，目标地块为零向量，不可达时返回 None
+    pub fn direction_at(&self, tile: UVec2) -> Option<IVec2> {
+        if self.cost_at(tile).is_infinite() {
+            return None;
+        }
+        self.index(tile).and_then(|i| self.directions.get(i).copied())
+    }
+}
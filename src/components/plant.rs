@@ -1,7 +1,9 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use crate::components::save::Saveable;
 
 /// 植物类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum PlantType {
     Grass,      // 草
     Bush,       // 灌木
@@ -46,7 +48,9 @@ impl PlantType {
 }
 
 /// 植物组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct Plant {
     pub plant_type: PlantType,
     pub growth_stage: u8,      // 生长阶段 (0-5)
@@ -105,11 +109,13 @@ impl Plant {
 pub struct Plantable;
 
 /// 可收获标记组件
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Harvestable;
 
 /// 植物生长系统
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Growable {
     pub base_growth_rate: f32,
     pub current_stage: u8,
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
 use crate::components::plant::PlantType;
 
 /// 植物等级
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum PlantLevel {
     Level1, // 基础等级
     Level2, // 进阶等级
@@ -58,7 +59,8 @@ impl PlantLevel {
 }
 
 /// 植物升级属性
-#[derive(Debug, Clone, Component)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct PlantUpgrade {
     pub level: PlantLevel,
     pub growth_speed_bonus: f32,    // 生长速度加成
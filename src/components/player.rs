@@ -1,7 +1,14 @@
 use bevy::prelude::*;
+use rand::Rng;
+use crate::components::combat::{DamageType, DefenseAttributes};
+use crate::components::experience::PlayerExperience;
+use crate::components::skill::PlayerSkills;
+use crate::components::save::Saveable;
 
 #[allow(dead_code)]
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[require(Saveable, PlayerStats, PlayerVitals, PlayerExperience, PlayerSkills)]
 pub struct Player {
     pub id: u64,
     pub name: String,
@@ -13,3 +20,126 @@ impl Player {
         Self { id, name, level: 1 }
     }
 }
+
+/// 玩家属性（由等级、装备等推导，不写入存档）
+#[derive(Component, Debug, Clone)]
+pub struct PlayerStats {
+    pub move_speed: f32,            // 移动速度（像素/秒）
+    pub armor: f32,                 // 护甲值（来自装备防御力）
+    pub defense: DefenseAttributes, // 抗性、闪避与格挡
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            move_speed: BASE_MOVE_SPEED,
+            armor: 0.0,
+            defense: DefenseAttributes::default(),
+        }
+    }
+}
+
+impl PlayerStats {
+    /// 计算玩家实际承受的伤害
+    ///
+    /// 依次判定闪避、抗性、护甲与格挡；护甲按 `armor / (armor + 100)` 减伤。
+    pub fn mitigate_damage(&self, damage: f32, damage_type: DamageType) -> f32 {
+        let mut rng = rand::thread_rng();
+
+        if rng.gen::<f32>() < self.defense.dodge_chance {
+            return 0.0;
+        }
+
+        let mut damage = damage * (1.0 - self.defense.resistance(damage_type).min(0.8));
+        damage *= 100.0 / (100.0 + self.armor.max(0.0));
+
+        if rng.gen::<f32>() < self.defense.block_chance {
+            damage *= 1.0 - self.defense.block_reduction;
+        }
+
+        damage.max(0.0)
+    }
+}
+
+/// 玩家基础生命上限
+pub const BASE_MAX_HEALTH: f32 = 100.0;
+/// 玩家基础能量上限
+pub const BASE_MAX_ENERGY: f32 = 100.0;
+/// 每级增加的生命上限
+pub const HEALTH_PER_LEVEL: f32 = 10.0;
+/// 每级增加的能量上限
+pub const ENERGY_PER_LEVEL: f32 = 5.0;
+/// 每级增加的基础护甲
+pub const ARMOR_PER_LEVEL: f32 = 1.0;
+/// 玩家基础移动速度（像素/秒）
+pub const BASE_MOVE_SPEED: f32 = 160.0;
+/// 每级增加的移动速度（像素/秒）
+pub const MOVE_SPEED_PER_LEVEL: f32 = 2.0;
+/// 玩家背包格子数
+pub const PLAYER_INVENTORY_SLOTS: u32 = 20;
+/// 新开局时背包中的能源
+pub const STARTING_ENERGY: u32 = 100;
+
+/// 玩家生命与能量
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct PlayerVitals {
+    pub health: f32,        // 当前生命
+    pub max_health: f32,    // 生命上限
+    pub energy: f32,        // 当前能量（技能消耗，区别于背包中的能量资源）
+    pub max_energy: f32,    // 能量上限
+    pub health_regen: f32,  // 生命恢复（每秒）
+    pub energy_regen: f32,  // 能量恢复（每秒）
+}
+
+impl Default for PlayerVitals {
+    fn default() -> Self {
+        Self {
+            health: BASE_MAX_HEALTH,
+            max_health: BASE_MAX_HEALTH,
+            energy: BASE_MAX_ENERGY,
+            max_energy: BASE_MAX_ENERGY,
+            health_regen: 1.0,
+            energy_regen: 5.0,
+        }
+    }
+}
+
+impl PlayerVitals {
+    /// 受到伤害
+    pub fn take_damage(&mut self, amount: f32) {
+        self.health = (self.health - amount).max(0.0);
+    }
+
+    /// 恢复生命
+    pub fn heal(&mut self, amount: f32) {
+        self.health = (self.health + amount).min(self.max_health);
+    }
+
+    /// 按时间恢复生命与能量
+    pub fn regenerate(&mut self, delta: f32) {
+        if self.is_dead() {
+            return;
+        }
+        self.health = (self.health + self.health_regen * delta).min(self.max_health);
+        self.energy = (self.energy + self.energy_regen * delta).min(self.max_energy);
+    }
+
+    /// 更新上限，当前值随之截断
+    pub fn set_limits(&mut self, max_health: f32, max_energy: f32) {
+        self.max_health = max_health;
+        self.max_energy = max_energy;
+        self.health = self.health.min(max_health);
+        self.energy = self.energy.min(max_energy);
+    }
+
+    /// 完全恢复生命与能量
+    pub fn restore(&mut self) {
+        self.health = self.max_health;
+        self.energy = self.max_energy;
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health <= 0.0
+    }
+}
@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use crate::components::item::{amounts, ItemAmounts, ItemId};
+use crate::components::save::Saveable;
+
+/// 电缆的连接范围（格），只连通相邻的电缆与建筑
+pub const CABLE_CONNECTION_RANGE: f32 = 1.5;
+
+/// 铺设一段电缆的成本
+pub fn cable_cost() -> ItemAmounts {
+    amounts(&[(ItemId::METAL, 2)])
+}
+
+/// 没有可用充电桩时，机器人在玩家身边的充电速率（每秒）
+pub const FALLBACK_CHARGE_RATE: f32 = 2.0;
+
+/// 用电优先级
+///
+/// 供电不足时从低优先级开始断电（同优先级按实体顺序）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, Serialize, Deserialize)]
+pub enum PowerPriority {
+    Low,        // 低（传送等非必要设施）
+    #[default]
+    Normal,     // 普通
+    High,       // 高（防御设施）
+    Critical,   // 关键（最后断电）
+}
+
+/// 建筑的电力参数（来自建筑目录，发电量与蓄电量随建筑等级增长）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerProfile {
+    pub generation: f32,        // 发电功率（每秒电量）
+    pub consumption: f32,       // 用电功率（每秒电量）
+    pub battery_capacity: f32,  // 蓄电池容量
+    pub connection_range: f32,  // 连接范围（格）
+    pub charge_rate: f32,       // 为机器人充电的速率（大于 0 即为充电桩）
+    pub priority: PowerPriority,
+}
+
+impl PowerProfile {
+    /// 校验数值合法性
+    pub fn validate(&self) -> Result<(), String> {
+        let values = [
+            ("generation", self.generation),
+            ("consumption", self.consumption),
+            ("battery_capacity", self.battery_capacity),
+            ("connection_range", self.connection_range),
+            ("charge_rate", self.charge_rate),
+        ];
+        for (field, value) in values {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("power.{} 无效: {}", field, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 电网节点
+///
+/// 挂在建筑、防御塔与电缆上。参数由建筑目录或塔属性换算而来，不写入存档；
+/// `network` 与 `powered` 每帧由电网结算更新。
+#[derive(Component, Debug, Clone, Default)]
+pub struct PowerNode {
+    pub generation: f32,        // 当前发电功率
+    pub consumption: f32,       // 基础用电功率
+    pub load: f32,              // 本帧附加负载（充电桩为机器人充电），结算后清零
+    pub connection_range: f32,  // 连接范围（格）
+    pub charge_rate: f32,       // 为机器人充电的速率
+    pub priority: PowerPriority,
+    pub active: bool,           // 是否接入电网（建造完成且已启动）
+    pub network: Option<usize>, // 所属电网（`PowerGrid::networks` 下标）
+    pub powered: bool,          // 本帧是否得到供电
+}
+
+impl PowerNode {
+    /// 按建筑电力参数创建节点
+    pub fn from_profile(profile: &PowerProfile, multiplier: f32) -> Self {
+        Self {
+            generation: profile.generation * multiplier,
+            consumption: profile.consumption,
+            connection_range: profile.connection_range,
+            charge_rate: profile.charge_rate,
+            priority: profile.priority,
+            ..default()
+        }
+    }
+
+    /// 只有用电的节点（防御塔）
+    pub fn consumer(consumption: f32, connection_range: f32, priority: PowerPriority) -> Self {
+        Self {
+            consumption,
+            connection_range,
+            priority,
+            active: true,
+            ..default()
+        }
+    }
+
+    /// 电缆节点
+    pub fn cable() -> Self {
+        Self {
+            connection_range: CABLE_CONNECTION_RANGE,
+            active: true,
+            ..default()
+        }
+    }
+
+    /// 本帧的用电需求
+    pub fn demand(&self) -> f32 {
+        if self.active {
+            self.consumption + self.load
+        } else {
+            0.0
+        }
+    }
+
+    /// 本帧的发电量
+    pub fn supply(&self) -> f32 {
+        if self.active {
+            self.generation
+        } else {
+            0.0
+        }
+    }
+
+    /// 是否为可用的机器人充电桩
+    pub fn can_charge(&self) -> bool {
+        self.charge_rate > 0.0 && self.powered
+    }
+}
+
+/// 蓄电池（随存档保存电量）
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Battery {
+    pub charge: f32,    // 当前电量
+    pub capacity: f32,  // 容量
+}
+
+impl Battery {
+    /// 充电，返回实际充入的电量
+    pub fn fill(&mut self, amount: f32) -> f32 {
+        let filled = amount.min(self.capacity - self.charge).max(0.0);
+        self.charge += filled;
+        filled
+    }
+
+    /// 放电，返回实际放出的电量
+    pub fn drain(&mut self, amount: f32) -> f32 {
+        let drained = amount.min(self.charge).max(0.0);
+        self.charge -= drained;
+        drained
+    }
+}
+
+/// 电缆
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Saveable)]
+pub struct PowerCable {
+    pub tile_x: u32,
+    pub tile_y: u32,
+}
+
+/// 一个连通电网的本帧统计
+#[derive(Debug, Clone, Default)]
+pub struct PowerNetwork {
+    pub supply: f32,            // 发电功率
+    pub demand: f32,            // 用电需求（含被断电的设备）
+    pub stored: f32,            // 蓄电池总电量
+    pub capacity: f32,          // 蓄电池总容量
+    pub shed: usize,            // 被断电的设备数
+}
+
+/// 当前所有电网（每帧重新划分与结算）
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PowerGrid {
+    pub networks: Vec<PowerNetwork>,
+}
+
+impl PowerGrid {
+    /// 全部电网的发电功率
+    pub fn total_supply(&self) -> f32 {
+        self.networks.iter().map(|network| network.supply).sum()
+    }
+
+    /// 全部电网的用电需求
+    pub fn total_demand(&self) -> f32 {
+        self.networks.iter().map(|network| network.demand).sum()
+    }
+
+    /// 全部蓄电池的电量与容量
+    pub fn total_storage(&self) -> (f32, f32) {
+        self.networks
+            .iter()
+            .fold((0.0, 0.0), |(stored, capacity), network| {
+                (stored + network.stored, capacity + network.capacity)
+            })
+    }
+
+    /// 全部被断电的设备数
+    pub fn total_shed(&self) -> usize {
+        self.networks.iter().map(|network| network.shed).sum()
+    }
+}
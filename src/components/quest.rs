@@ -1,10 +1,11 @@
 use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
+use crate::components::save::Saveable;
 
 /// 任务系统组件
 
 /// 任务类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum QuestType {
     Main,      // 主线任务
     Side,      // 支线任务
@@ -13,7 +14,7 @@ pub enum QuestType {
 }
 
 /// 任务状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum QuestStatus {
     NotStarted,    // 未开始
     InProgress,    // 进行中
@@ -22,7 +23,7 @@ pub enum QuestStatus {
 }
 
 /// 任务目标类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum QuestObjectiveType {
     Kill,          // 击杀敌人
     Collect,       // 收集物品
@@ -36,7 +37,7 @@ pub enum QuestObjectiveType {
 }
 
 /// 任务目标
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 pub struct QuestObjective {
     pub objective_type: QuestObjectiveType,
     pub target_id: Option<String>,      // 目标ID
@@ -82,7 +83,7 @@ impl QuestObjective {
 }
 
 /// 任务奖励
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
 pub struct QuestReward {
     pub experience: u32,
     pub gold: u32,
@@ -112,7 +113,9 @@ impl QuestReward {
 }
 
 /// 任务组件
-#[derive(Debug, Clone, Component, Serialize, Deserialize)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct Quest {
     pub id: String,
     pub quest_type: QuestType,
@@ -229,6 +232,11 @@ impl Quest {
         self.objectives.iter().all(|obj| obj.is_completed())
     }
 
+    /// 目标已全部达成、等待玩家交付
+    pub fn can_turn_in(&self) -> bool {
+        self.status == QuestStatus::InProgress && !self.auto_complete && self.is_completed()
+    }
+
     /// 更新任务进度
     pub fn update_progress(&mut self, objective_type: QuestObjectiveType, target_id: Option<&str>, amount: u32) {
         if self.status != QuestStatus::InProgress {
@@ -242,11 +250,6 @@ impl Quest {
                 }
             }
         }
-
-        // 检查是否自动完成
-        if self.auto_complete && self.is_completed() {
-            self.complete();
-        }
     }
 
     /// 更新时间限制
@@ -260,8 +263,34 @@ impl Quest {
     }
 }
 
+/// 下一个可接受的任务（主线优先，其次按 ID）
+pub fn next_available_quest<'a>(
+    quests: impl IntoIterator<Item = &'a Quest>,
+    player_level: u32,
+    completed_quests: &[String],
+) -> Option<&'a Quest> {
+    quests
+        .into_iter()
+        .filter(|quest| quest.can_accept(player_level, completed_quests))
+        .min_by_key(|quest| (quest.quest_type != QuestType::Main, quest.id.as_str()))
+}
+
+/// 玩家接受任务
+#[derive(Event, Message, Debug, Clone)]
+pub struct AcceptQuestEvent {
+    pub quest_id: String,
+}
+
+/// 玩家交付目标已达成的任务
+#[derive(Event, Message, Debug, Clone)]
+pub struct TurnInQuestEvent {
+    pub quest_id: String,
+}
+
 /// 任务日志组件
-#[derive(Debug, Clone, Component)]
+#[derive(Debug, Clone, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
+#[require(Saveable)]
 pub struct QuestLog {
     pub active_quests: Vec<String>,      // 进行中的任务ID
     pub completed_quests: Vec<String>,   // 已完成的任务ID
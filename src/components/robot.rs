@@ -1,7 +1,22 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use crate::components::logistics::Courier;
+use crate::components::save::Saveable;
+
+/// 防御机器人能量低于上限的这个比例时撤回充电
+pub const DEFENDER_RETREAT_ENERGY: f32 = 0.25;
+
+/// 防御机器人追击敌人时离守卫点（或玩家）的最远距离（格）
+pub const DEFENDER_LEASH_RADIUS: f32 = 6.0;
+
+/// 防御机器人在守卫点（或玩家）附近待命的距离（格）
+pub const DEFENDER_STATION_RADIUS: f32 = 1.25;
+
+/// 防御机器人的机体生命
+pub const DEFENDER_MAX_HEALTH: f32 = 150.0;
 
 /// 机器人类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum RobotType {
     Harvester,  // 采集机器人
     Builder,    // 建造机器人
@@ -20,7 +35,7 @@ impl RobotType {
         }
     }
 
-    /// 获取机器人的移动速度
+    /// 获取机器人的移动速度（格/秒）
     pub fn movement_speed(&self) -> f32 {
         match self {
             RobotType::Harvester => 2.0,
@@ -42,18 +57,22 @@ impl RobotType {
 }
 
 /// 机器人任务类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum RobotTask {
     Idle,           // 空闲
     Harvest,        // 采集
     Build,          // 建造
     Patrol,         // 巡逻
     Repair,         // 维修
-    ReturnToBase,   // 返回基地
+    ReturnToBase,   // 返回充电
+    Haul,           // 搬运
+    Engage,         // 交战
 }
 
 /// 机器人组件
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Saveable, Courier)]
 pub struct Robot {
     pub robot_type: RobotType,
     pub current_task: RobotTask,
@@ -103,8 +122,8 @@ impl Robot {
 /// 机器人 AI 组件
 #[derive(Component, Clone, Debug)]
 pub struct RobotAI {
-    pub patrol_radius: f32,
-    pub detection_radius: f32,
+    pub patrol_radius: f32,     // 闲逛范围（格）
+    pub detection_radius: f32,  // 搜寻植物、敌人的范围（格）
     pub current_patrol_index: usize,
     pub patrol_points: Vec<Vec2>,
 }
@@ -112,54 +131,46 @@ pub struct RobotAI {
 impl Default for RobotAI {
     fn default() -> Self {
         Self {
-            patrol_radius: 200.0,
-            detection_radius: 100.0,
+            patrol_radius: 6.0,
+            detection_radius: 3.0,
             current_patrol_index: 0,
             patrol_points: Vec::new(),
         }
     }
 }
 
-/// 机器人资源携带组件
-#[derive(Component, Clone, Debug)]
-pub struct RobotInventory {
-    pub capacity: u32,
-    pub current: u32,
-    pub resource_type: Option<crate::components::resource::ResourceType>,
+/// 防御机器人的指令
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub enum DefenderOrder {
+    Guard(Vec2),    // 守卫指定地点
+    #[default]
+    Escort,         // 护送玩家
 }
 
-impl RobotInventory {
-    pub fn new(capacity: u32) -> Self {
-        Self {
-            capacity,
-            current: 0,
-            resource_type: None,
-        }
-    }
+/// 机器人机体（防御机器人会被敌人攻击，生命归零即被摧毁）
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct RobotHealth {
+    pub health: f32,
+    pub max_health: f32,
+}
 
-    /// 添加资源
-    pub fn add(&mut self, amount: u32, resource_type: crate::components::resource::ResourceType) -> bool {
-        if self.current + amount <= self.capacity {
-            if self.resource_type.is_none() {
-                self.resource_type = Some(resource_type);
-            }
-            self.current += amount;
-            true
-        } else {
-            false
+impl RobotHealth {
+    pub fn new(max_health: f32) -> Self {
+        Self {
+            health: max_health,
+            max_health,
         }
     }
 
-    /// 清空资源
-    pub fn clear(&mut self) -> u32 {
-        let amount = self.current;
-        self.current = 0;
-        self.resource_type = None;
-        amount
+    /// 受到伤害
+    pub fn take_damage(&mut self, damage: f32) {
+        self.health = (self.health - damage).max(0.0);
     }
 
-    /// 检查是否已满
-    pub fn is_full(&self) -> bool {
-        self.current >= self.capacity
+    /// 是否已被摧毁
+    pub fn is_destroyed(&self) -> bool {
+        self.health <= 0.0
     }
 }
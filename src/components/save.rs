@@ -1,5 +1,8 @@
 use bevy::prelude::*;
+use bevy::scene::SceneFilter;
 use serde::{Serialize, Deserialize};
+use std::path::Path;
+use crate::systems::save_file::{LEGACY_SAVE_EXTENSION, SAVE_EXTENSION};
 
 /// 存档系统组件
 
@@ -12,6 +15,27 @@ pub enum SaveType {
     Checkpoint, // 检查点存档
 }
 
+impl SaveType {
+    /// 存档ID前缀
+    pub fn id_prefix(&self) -> &'static str {
+        match self {
+            SaveType::Auto => "auto",
+            SaveType::Manual => "manual",
+            SaveType::Quick => "quick",
+            SaveType::Checkpoint => "checkpoint",
+        }
+    }
+
+    /// 从存档ID解析存档类型与时间戳
+    pub fn parse_save_id(save_id: &str) -> Option<(SaveType, u64)> {
+        let (prefix, timestamp) = save_id.rsplit_once('_')?;
+        let save_type = [SaveType::Auto, SaveType::Manual, SaveType::Quick, SaveType::Checkpoint]
+            .into_iter()
+            .find(|save_type| save_type.id_prefix() == prefix)?;
+        Some((save_type, timestamp.parse().ok()?))
+    }
+}
+
 /// 存档状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SaveStatus {
@@ -21,6 +45,74 @@ pub enum SaveStatus {
     Incompatible, // 不兼容
 }
 
+/// 存档错误
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),                                 // 文件读写失败
+    BadMagic,                                           // 不是存档文件
+    UnsupportedFormat(u16),                             // 容器格式版本不支持
+    Truncated { expected: usize, actual: usize },       // 文件被截断（字节数）
+    ChecksumMismatch { expected: u32, actual: u32 },    // 校验和不符（文件损坏）
+    Decompress(String),                                 // 解压失败
+    Encode(String),                                     // 序列化失败
+    Decode(String),                                     // 反序列化失败
+    Incompatible { found: String, expected: String },   // 存档版本不兼容
+    World(String),                                      // 读取或还原世界状态失败
+}
+
+impl SaveError {
+    /// 对应的存档状态
+    pub fn status(&self) -> SaveStatus {
+        match self {
+            SaveError::BadMagic
+            | SaveError::Truncated { .. }
+            | SaveError::ChecksumMismatch { .. }
+            | SaveError::Decompress(_)
+            | SaveError::Decode(_) => SaveStatus::Corrupted,
+            SaveError::UnsupportedFormat(_) | SaveError::Incompatible { .. } => SaveStatus::Incompatible,
+            SaveError::Io(_) | SaveError::Encode(_) | SaveError::World(_) => SaveStatus::Failed,
+        }
+    }
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "存档文件读写失败: {}", e),
+            SaveError::BadMagic => write!(f, "不是有效的存档文件"),
+            SaveError::UnsupportedFormat(version) => write!(f, "不支持的存档格式版本: {}", version),
+            SaveError::Truncated { expected, actual } => {
+                write!(f, "存档文件不完整: 需要 {} 字节，实际 {} 字节", expected, actual)
+            }
+            SaveError::ChecksumMismatch { expected, actual } => {
+                write!(f, "存档校验失败: 期望 {:08x}，实际 {:08x}", expected, actual)
+            }
+            SaveError::Decompress(e) => write!(f, "存档解压失败: {}", e),
+            SaveError::Encode(e) => write!(f, "序列化失败: {}", e),
+            SaveError::Decode(e) => write!(f, "反序列化失败: {}", e),
+            SaveError::Incompatible { found, expected } => {
+                write!(f, "存档版本({})不兼容当前版本({})", found, expected)
+            }
+            SaveError::World(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(e: std::io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
 /// 存档信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveInfo {
@@ -76,83 +168,49 @@ impl SaveInfo {
     }
 }
 
+/// 存档格式版本
+pub const SAVE_VERSION: &str = "0.13.0";
+
 /// 存档数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveData {
     pub info: SaveInfo,
-    pub player_data: PlayerData,
-    pub world_data: WorldData,
-    pub quest_data: QuestData,
-    pub achievement_data: AchievementData,
+    pub scene: String,    // 世界快照（Bevy 动态场景，RON 格式）
 }
 
-/// 玩家数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlayerData {
-    pub level: u32,
-    pub experience: u32,
-    pub health: f32,
-    pub max_health: f32,
-    pub position: (f32, f32),
-    pub inventory: Vec<ItemData>,
-    pub equipment: EquipmentData,
-}
+/// 可存档标记
+///
+/// 存档只收集带此标记的实体；实体上的组件还需通过 `SaveAppExt::register_saveable` 注册才会写入。
+#[derive(Component, Reflect, Default, Debug, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct Saveable;
 
-/// 物品数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ItemData {
-    pub item_id: String,
-    pub quantity: u32,
+/// 存档白名单：允许写入存档的组件与资源类型
+#[derive(Resource, Debug, Clone)]
+pub struct SaveRegistry {
+    pub components: SceneFilter,
+    pub resources: SceneFilter,
 }
 
-/// 装备数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EquipmentData {
-    pub weapon: Option<String>,
-    pub armor: Option<String>,
-    pub accessory: Option<String>,
-}
-
-/// 世界数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WorldData {
-    pub time: f32,
-    pub day: u32,
-    pub explored_areas: Vec<String>,
-    pub buildings: Vec<BuildingData>,
-    pub resources: Vec<ResourceData>,
-}
-
-/// 建筑数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BuildingData {
-    pub building_id: String,
-    pub position: (f32, f32),
-    pub level: u32,
-    pub health: f32,
-}
-
-/// 资源数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResourceData {
-    pub resource_id: String,
-    pub amount: u32,
+impl Default for SaveRegistry {
+    fn default() -> Self {
+        Self {
+            components: SceneFilter::deny_all(),
+            resources: SceneFilter::deny_all(),
+        }
+    }
 }
 
-/// 任务数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct QuestData {
-    pub active_quests: Vec<String>,
-    pub completed_quests: Vec<String>,
-    pub failed_quests: Vec<String>,
-    pub current_quest: Option<String>,
-}
+impl SaveRegistry {
+    /// 允许组件写入存档
+    pub fn allow_component<T: Component>(&mut self) {
+        self.components = std::mem::take(&mut self.components).allow::<T>();
+    }
 
-/// 成就数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AchievementData {
-    pub unlocked_achievements: Vec<String>,
-    pub total_points: u32,
+    /// 允许资源写入存档
+    pub fn allow_resource<T: Resource>(&mut self) {
+        self.resources = std::mem::take(&mut self.resources).allow::<T>();
+    }
 }
 
 /// 存档设置
@@ -166,6 +224,18 @@ pub struct SaveSettings {
     pub compress_saves: bool,             // 压缩存档
 }
 
+impl SaveSettings {
+    /// 某类存档最多保留的数量（`None` 表示不限）
+    pub fn max_saves(&self, save_type: SaveType) -> Option<usize> {
+        match save_type {
+            SaveType::Auto => Some(self.max_auto_saves),
+            SaveType::Manual => Some(self.max_manual_saves),
+            SaveType::Quick => Some(1),   // 快速存档只保留最新一个
+            SaveType::Checkpoint => None,
+        }
+    }
+}
+
 impl Default for SaveSettings {
     fn default() -> Self {
         Self {
@@ -184,7 +254,9 @@ impl Default for SaveSettings {
 pub struct SaveManager {
     pub settings: SaveSettings,
     pub last_auto_save: Option<f32>,
+    pub last_checkpoint_day: Option<u32>,   // 上次检查点存档对应的天数
     pub current_save_id: Option<String>,
+    pub last_save_timestamp: u64,           // 上次生成存档ID所用的时间戳
 }
 
 impl Default for SaveManager {
@@ -192,7 +264,9 @@ impl Default for SaveManager {
         Self {
             settings: SaveSettings::default(),
             last_auto_save: None,
+            last_checkpoint_day: None,
             current_save_id: None,
+            last_save_timestamp: 0,
         }
     }
 }
@@ -214,9 +288,10 @@ impl SaveManager {
                     false
                 }
             }
+            // 首次调用只开始计时，避免一进入游戏就自动存档
             None => {
                 self.last_auto_save = Some(current_time);
-                true
+                false
             }
         }
     }
@@ -232,29 +307,66 @@ impl SaveManager {
                 let elapsed = current_time - last;
                 elapsed >= self.settings.auto_save_interval
             }
-            None => true,
+            None => false,
         }
     }
 
     /// 生成存档ID
-    pub fn generate_save_id(&self, save_type: SaveType) -> String {
-        let timestamp = std::time::SystemTime::now()
+    ///
+    /// 时间戳精确到秒：同一秒内的多次存档顺延到下一秒，与已有存档文件同名时同样顺延。
+    pub fn generate_save_id(&mut self, save_type: SaveType) -> String {
+        let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs();
 
-        let prefix = match save_type {
-            SaveType::Auto => "auto",
-            SaveType::Manual => "manual",
-            SaveType::Quick => "quick",
-            SaveType::Checkpoint => "checkpoint",
-        };
+        let mut timestamp = now.max(self.last_save_timestamp + 1);
+        let mut save_id = format!("{}_{}", save_type.id_prefix(), timestamp);
+        while Path::new(&self.get_save_path(&save_id)).exists()
+            || Path::new(&self.get_legacy_save_path(&save_id)).exists()
+        {
+            timestamp += 1;
+            save_id = format!("{}_{}", save_type.id_prefix(), timestamp);
+        }
 
-        format!("{}_{}", prefix, timestamp)
+        self.last_save_timestamp = timestamp;
+        save_id
     }
 
     /// 获取存档路径
     pub fn get_save_path(&self, save_id: &str) -> String {
-        format!("{}/{}.json", self.settings.save_location, save_id)
+        format!("{}/{}.{}", self.settings.save_location, save_id, SAVE_EXTENSION)
+    }
+
+    /// 获取容器格式之前的旧存档路径
+    pub fn get_legacy_save_path(&self, save_id: &str) -> String {
+        format!("{}/{}.{}", self.settings.save_location, save_id, LEGACY_SAVE_EXTENSION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_ids_in_the_same_second_do_not_collide() {
+        let dir = std::env::temp_dir().join(format!("dark-forest-save-ids-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut save_manager = SaveManager::default();
+        save_manager.settings.save_location = dir.to_string_lossy().into_owned();
+
+        let first = save_manager.generate_save_id(SaveType::Quick);
+        let second = save_manager.generate_save_id(SaveType::Quick);
+        let (_, first_stamp) = SaveType::parse_save_id(&first).unwrap();
+        let (_, second_stamp) = SaveType::parse_save_id(&second).unwrap();
+        assert!(second_stamp > first_stamp);
+
+        // 新会话从头计时，但跳过磁盘上已有的同名存档
+        let existing = SaveManager { last_save_timestamp: 0, ..save_manager.clone() }.generate_save_id(SaveType::Manual);
+        std::fs::write(save_manager.get_save_path(&existing), b"").unwrap();
+        let next = SaveManager { last_save_timestamp: 0, ..save_manager.clone() }.generate_save_id(SaveType::Manual);
+        assert_ne!(next, existing);
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
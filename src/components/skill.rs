@@ -0,0 +1,376 @@
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use crate::systems::json_catalog::{parse_catalog, JsonCatalog};
+
+/// 每升一级获得的技能点
+pub const SKILL_POINTS_PER_LEVEL: u32 = 1;
+
+/// 技能分支
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SkillBranch {
+    Combat,         // 战斗
+    Farming,        // 种植
+    Engineering,    // 工程
+    Robotics,       // 机器人
+}
+
+impl SkillBranch {
+    pub const ALL: [SkillBranch; 4] = [
+        SkillBranch::Combat,
+        SkillBranch::Farming,
+        SkillBranch::Engineering,
+        SkillBranch::Robotics,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            SkillBranch::Combat => "战斗",
+            SkillBranch::Farming => "种植",
+            SkillBranch::Engineering => "工程",
+            SkillBranch::Robotics => "机器人",
+        }
+    }
+}
+
+/// 技能影响的属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SkillStat {
+    SpecialDamage,          // 特殊攻击（空格）伤害倍率，加法叠加
+    SpecialCooldown,        // 特殊攻击冷却缩减（比例）
+    UltimateDamage,         // 终极攻击（E）伤害倍率，加法叠加
+    UltimateCooldown,       // 终极攻击冷却缩减（比例）
+    PlantGrowth,            // 植物生长速度加成（比例）
+    RobotEnergyConsumption, // 机器人能耗降低（比例）
+    BuildingCost,           // 建造成本降低（比例）
+}
+
+impl SkillStat {
+    /// 该属性是否为比例缩减（叠加后需要封顶）
+    pub fn is_reduction(&self) -> bool {
+        matches!(
+            self,
+            SkillStat::SpecialCooldown
+                | SkillStat::UltimateCooldown
+                | SkillStat::RobotEnergyConsumption
+                | SkillStat::BuildingCost
+        )
+    }
+
+    /// 效果描述
+    pub fn describe(&self, value: f32) -> String {
+        let percent = value * 100.0;
+        match self {
+            SkillStat::SpecialDamage => format!("特殊攻击伤害 +{:.1} 倍", value),
+            SkillStat::SpecialCooldown => format!("特殊攻击冷却 -{:.0}%", percent),
+            SkillStat::UltimateDamage => format!("终极攻击伤害 +{:.1} 倍", value),
+            SkillStat::UltimateCooldown => format!("终极攻击冷却 -{:.0}%", percent),
+            SkillStat::PlantGrowth => format!("植物生长速度 +{:.0}%", percent),
+            SkillStat::RobotEnergyConsumption => format!("机器人能耗 -{:.0}%", percent),
+            SkillStat::BuildingCost => format!("建造成本 -{:.0}%", percent),
+        }
+    }
+}
+
+/// 技能效果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SkillEffect {
+    pub stat: SkillStat,
+    pub value: f32,
+}
+
+/// 技能节点定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillDefinition {
+    pub id: String,
+    pub name: String,                   // 显示名称
+    pub branch: SkillBranch,
+    pub cost: u32,                      // 消耗技能点
+    #[serde(default = "default_required_level")]
+    pub required_level: u32,            // 等级要求
+    #[serde(default)]
+    pub prerequisites: Vec<String>,     // 前置技能ID
+    pub effects: Vec<SkillEffect>,
+}
+
+fn default_required_level() -> u32 {
+    1
+}
+
+impl SkillDefinition {
+    /// 效果描述（多个效果以逗号分隔）
+    pub fn describe_effects(&self) -> String {
+        self.effects
+            .iter()
+            .map(|effect| effect.stat.describe(effect.value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// 校验数值合法性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() {
+            return Err("技能ID不能为空".to_string());
+        }
+        if self.cost == 0 {
+            return Err(format!("技能 {} 的消耗必须大于 0", self.id));
+        }
+        if self.effects.is_empty() {
+            return Err(format!("技能 {} 没有效果", self.id));
+        }
+        for effect in &self.effects {
+            if !effect.value.is_finite() || effect.value < 0.0 {
+                return Err(format!("技能 {} 的 {:?} 无效: {}", self.id, effect.stat, effect.value));
+            }
+            if effect.stat.is_reduction() && effect.value >= 1.0 {
+                return Err(format!("技能 {} 的 {:?} 必须小于 1", self.id, effect.stat));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 技能树数据文件（`*.skills.json`）
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
+pub struct SkillTreeAsset {
+    pub skills: Vec<SkillDefinition>,
+}
+
+impl SkillTreeAsset {
+    /// 内置技能树（`default.skills.json`）
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: LazyLock<SkillTreeAsset> = LazyLock::new(|| {
+            parse_catalog(include_str!("../../assets/data/default.skills.json").as_bytes())
+                .unwrap_or_else(|e| panic!("内置技能树无效: {}", e))
+        });
+        &BUILTIN
+    }
+}
+
+impl JsonCatalog for SkillTreeAsset {
+    const LABEL: &'static str = "技能树";
+    const EXTENSION: &'static str = "skills.json";
+    const PATH: &'static str = "data/default.skills.json";
+
+    type Catalog = SkillTree;
+
+    /// 校验所有定义，并检查ID不重复、前置技能存在
+    fn validate(&self) -> Result<(), String> {
+        let mut ids = HashSet::new();
+        for definition in &self.skills {
+            definition.validate()?;
+            if !ids.insert(definition.id.as_str()) {
+                return Err(format!("技能ID重复定义: {}", definition.id));
+            }
+        }
+
+        // 前置技能必须在自身之前定义，保证不存在环
+        let mut defined = HashSet::new();
+        for definition in &self.skills {
+            for prerequisite in &definition.prerequisites {
+                if !defined.contains(prerequisite.as_str()) {
+                    return Err(format!("技能 {} 的前置技能 {} 未在之前定义", definition.id, prerequisite));
+                }
+            }
+            defined.insert(definition.id.as_str());
+        }
+        Ok(())
+    }
+
+    fn apply(&self, tree: &mut SkillTree) -> usize {
+        tree.apply(self);
+        tree.skills.len()
+    }
+}
+
+/// 当前生效的技能树
+#[derive(Resource, Debug, Clone)]
+pub struct SkillTree {
+    pub skills: Vec<SkillDefinition>,  // 按数据文件中的顺序
+}
+
+impl Default for SkillTree {
+    fn default() -> Self {
+        Self {
+            skills: SkillTreeAsset::builtin().skills.clone(),
+        }
+    }
+}
+
+impl SkillTree {
+    /// 用数据文件替换技能树
+    pub fn apply(&mut self, asset: &SkillTreeAsset) {
+        self.skills = asset.skills.clone();
+    }
+
+    /// 获取技能定义
+    pub fn get(&self, skill_id: &str) -> Option<&SkillDefinition> {
+        self.skills.iter().find(|definition| definition.id == skill_id)
+    }
+
+    /// 某个分支下的技能
+    pub fn branch(&self, branch: SkillBranch) -> impl Iterator<Item = &SkillDefinition> {
+        self.skills.iter().filter(move |definition| definition.branch == branch)
+    }
+}
+
+/// 玩家已学技能（挂在玩家实体上，随存档保存）
+///
+/// 可用技能点由等级推导，只记录已花费的点数，
+/// 这样旧存档与调整过消耗的技能树都不会多给或少给技能点。
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct PlayerSkills {
+    pub unlocked: Vec<String>,  // 已解锁技能ID
+    pub spent_points: u32,      // 已花费技能点
+}
+
+impl PlayerSkills {
+    /// 当前等级累计获得的技能点
+    pub fn earned_points(level: u32) -> u32 {
+        level.saturating_sub(1) * SKILL_POINTS_PER_LEVEL
+    }
+
+    /// 可用技能点
+    pub fn available_points(&self, level: u32) -> u32 {
+        Self::earned_points(level).saturating_sub(self.spent_points)
+    }
+
+    pub fn is_unlocked(&self, skill_id: &str) -> bool {
+        self.unlocked.iter().any(|id| id == skill_id)
+    }
+
+    /// 检查是否可以解锁
+    pub fn can_unlock(&self, definition: &SkillDefinition, level: u32) -> Result<(), String> {
+        if self.is_unlocked(&definition.id) {
+            return Err(format!("技能 {} 已解锁", definition.name));
+        }
+        if level < definition.required_level {
+            return Err(format!("需要等级 {}", definition.required_level));
+        }
+        if let Some(missing) = definition.prerequisites.iter().find(|id| !self.is_unlocked(id)) {
+            return Err(format!("需要先解锁前置技能 {}", missing));
+        }
+        if self.available_points(level) < definition.cost {
+            return Err(format!("技能点不足，需要 {}", definition.cost));
+        }
+        Ok(())
+    }
+
+    /// 解锁技能并扣除技能点
+    pub fn unlock(&mut self, definition: &SkillDefinition, level: u32) -> Result<(), String> {
+        self.can_unlock(definition, level)?;
+        self.unlocked.push(definition.id.clone());
+        self.spent_points += definition.cost;
+        Ok(())
+    }
+}
+
+/// 已学技能汇总出的加成（每次技能或技能树变化后重算）
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct SkillBonuses {
+    pub special_damage: f32,            // 特殊攻击伤害倍率加成
+    pub special_cooldown: f32,          // 特殊攻击冷却缩减
+    pub ultimate_damage: f32,           // 终极攻击伤害倍率加成
+    pub ultimate_cooldown: f32,         // 终极攻击冷却缩减
+    pub plant_growth: f32,              // 植物生长速度加成
+    pub robot_energy_consumption: f32,  // 机器人能耗降低
+    pub building_cost: f32,             // 建造成本降低
+}
+
+/// 比例缩减的上限，避免叠加到免费或零冷却
+const MAX_REDUCTION: f32 = 0.75;
+
+impl SkillBonuses {
+    /// 汇总已解锁技能的效果；技能树中已不存在的技能忽略
+    pub fn from_skills(skills: &PlayerSkills, tree: &SkillTree) -> Self {
+        let mut bonuses = Self::default();
+        for definition in skills.unlocked.iter().filter_map(|id| tree.get(id)) {
+            for effect in &definition.effects {
+                let value = match effect.stat {
+                    SkillStat::SpecialDamage => &mut bonuses.special_damage,
+                    SkillStat::SpecialCooldown => &mut bonuses.special_cooldown,
+                    SkillStat::UltimateDamage => &mut bonuses.ultimate_damage,
+                    SkillStat::UltimateCooldown => &mut bonuses.ultimate_cooldown,
+                    SkillStat::PlantGrowth => &mut bonuses.plant_growth,
+                    SkillStat::RobotEnergyConsumption => &mut bonuses.robot_energy_consumption,
+                    SkillStat::BuildingCost => &mut bonuses.building_cost,
+                };
+                *value += effect.value;
+            }
+        }
+
+        for value in [
+            &mut bonuses.special_cooldown,
+            &mut bonuses.ultimate_cooldown,
+            &mut bonuses.robot_energy_consumption,
+            &mut bonuses.building_cost,
+        ] {
+            *value = value.min(MAX_REDUCTION);
+        }
+        bonuses
+    }
+}
+
+/// 请求解锁技能（来自技能面板）
+#[derive(Event, Message, Debug, Clone)]
+pub struct UnlockSkillEvent {
+    pub skill_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_tree_is_valid() {
+        assert!(SkillTreeAsset::builtin().validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let mut asset = SkillTreeAsset::builtin().clone();
+        asset.skills[1].id = asset.skills[0].id.clone();
+        assert!(asset.validate().unwrap_err().contains("重复"));
+    }
+
+    #[test]
+    fn rejects_prerequisite_defined_later() {
+        let mut asset = SkillTreeAsset::builtin().clone();
+        assert_eq!(asset.skills[1].prerequisites, [asset.skills[0].id.clone()]);
+        asset.skills.swap(0, 1);
+        assert!(asset.validate().unwrap_err().contains("前置技能"));
+    }
+
+    #[test]
+    fn rejects_full_reduction() {
+        let mut asset = SkillTreeAsset::builtin().clone();
+        let effect = &mut asset.skills[1].effects[0];
+        assert!(effect.stat.is_reduction());
+        effect.value = 1.0;
+        assert!(asset.validate().is_err());
+    }
+
+    #[test]
+    fn can_unlock_checks_level_prerequisites_and_points() {
+        let tree = SkillTree::default();
+        let first = tree.skills[0].clone();
+        let second = tree.skills[1].clone();
+        let mut skills = PlayerSkills::default();
+
+        assert!(skills.can_unlock(&first, first.required_level - 1).is_err());
+        assert!(skills.can_unlock(&second, second.required_level).is_err());
+
+        skills.unlock(&first, first.required_level).unwrap();
+        assert_eq!(skills.spent_points, first.cost);
+        assert!(skills.can_unlock(&first, first.required_level).is_err());
+
+        // 等级够但技能点已经花完
+        let mut broke = skills.clone();
+        broke.spent_points = PlayerSkills::earned_points(second.required_level);
+        assert!(broke.can_unlock(&second, second.required_level).is_err());
+
+        assert!(skills.can_unlock(&second, second.required_level).is_ok());
+    }
+}
@@ -13,44 +13,70 @@ use ui::hud::HUDPlugin;
 use ui::plant_upgrade::PlantUpgradeUIPlugin;
 use ui::crafting::CraftingUIPlugin;
 use ui::building::BuildingUIPlugin;
+use ui::skill_tree::SkillTreeUIPlugin;
+use ui::save::SaveBrowserUIPlugin;
+use ui::game_over::GameOverUIPlugin;
 use systems::map::{init_map_assets, MapRenderAssets};
 use systems::player::{init_player_assets, PlayerRenderAssets};
 use systems::enemy_spawn::{EnemySpawnPlugin, init_enemy_assets, EnemyRenderAssets};
+use systems::headless::{HeadlessPlugin, HeadlessConfig, headless_requested};
 
 fn main() {
     std::panic::set_hook(Box::new(|info| {
         eprintln!("PANIC: {}", info);
     }));
 
+    let headless = headless_requested();
+
+    // 无头模式默认运行完整玩法层
     let layer = std::env::var("LAYER")
         .unwrap_or_default()
         .parse::<u32>()
-        .unwrap_or(6);
+        .unwrap_or(if headless { 999 } else { 6 });
 
     let mut app = App::new();
 
-    app.add_plugins(DefaultPlugins.set(WindowPlugin {
-        primary_window: Some(Window {
-            title: "Dark Forest".into(),
-            resolution: (1280, 720).into(),
+    if headless {
+        app.add_plugins(HeadlessPlugin {
+            config: HeadlessConfig::from_env(),
+        });
+    } else {
+        app.add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Dark Forest".into(),
+                resolution: (1280, 720).into(),
+                ..default()
+            }),
             ..default()
-        }),
-        ..default()
-    }))
-    .add_plugins((MenuPlugin, HUDPlugin))
-    .init_state::<GameState>()
+        }))
+        .add_plugins((MenuPlugin, HUDPlugin, GameOverUIPlugin))
+        .add_plugins((systems::player::PlayerControlPlugin, systems::camera::CameraControlPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Startup, init_render_assets);
+    }
+
+    app.init_state::<GameState>()
     .add_systems(Startup, |mut next_state: ResMut<NextState<GameState>>| {
         next_state.set(GameState::InGame);
     })
-    .add_systems(Startup, setup)
-    .add_systems(Startup, init_render_assets)
     .add_systems(Startup, systems::time::init_game_time)
     .add_systems(Startup, systems::lighting::init_lighting);
 
+    // 所有层都包含 Sys0 地图和敌人、建筑、物品数据目录
+    app.add_plugins(systems::map::MapPlugin);
+    app.add_plugins(systems::enemy_catalog::EnemyCatalogPlugin);
+    app.add_plugins(systems::building_catalog::BuildingCatalogPlugin);
+    app.add_plugins(systems::item::ItemPlugin);
+    app.add_plugins((
+        systems::vitals::PlayerVitalsPlugin,
+        systems::experience::ExperiencePlugin,
+        systems::skill::SkillTreePlugin,
+        systems::game_over::GameOverPlugin,
+    ));
+
     match layer {
         0 => {
             info!("Running Layer 0: Pure Map");
-            app.add_plugins(systems::map::MapPlugin);
         }
         1 => {
             info!("Running Layer 1: Entity Spawning");
@@ -65,6 +91,9 @@ fn main() {
             app.add_plugins(systems::plant::PlantPlugin);
             app.add_plugins(systems::enemy::EnemyPlugin);
             app.add_plugins(systems::robot::RobotPlugin);
+            app.add_plugins(systems::logistics::LogisticsPlugin);
+            app.add_plugins(systems::pathfinding::PathfindingPlugin);
+            app.add_plugins(systems::flow_field::FlowFieldPlugin);
             app.add_plugins(systems::equipment::EquipmentPlugin);
         }
         3 => {
@@ -74,11 +103,13 @@ fn main() {
             app.add_plugins(systems::plant::PlantPlugin);
             app.add_plugins(systems::enemy::EnemyPlugin);
             app.add_plugins(systems::robot::RobotPlugin);
+            app.add_plugins(systems::logistics::LogisticsPlugin);
+            app.add_plugins(systems::pathfinding::PathfindingPlugin);
+            app.add_plugins(systems::flow_field::FlowFieldPlugin);
             app.add_plugins(systems::equipment::EquipmentPlugin);
             app.add_plugins(systems::enemy_attack::EnemyAttackPlugin);
             app.add_plugins(systems::player_combat::PlayerCombatPlugin);
             app.add_plugins(systems::combat::CombatPlugin);
-            app.add_plugins(systems::combat_effects::CombatEffectsPlugin);
             app.add_plugins(systems::defense_tower::DefenseTowerPlugin);
             app.add_plugins(systems::defense_wall::DefenseWallPlugin);
             app.add_plugins(systems::defense_range::DefenseRangePlugin);
@@ -90,20 +121,20 @@ fn main() {
             app.add_plugins(systems::plant::PlantPlugin);
             app.add_plugins(systems::enemy::EnemyPlugin);
             app.add_plugins(systems::robot::RobotPlugin);
+            app.add_plugins(systems::pathfinding::PathfindingPlugin);
+            app.add_plugins(systems::flow_field::FlowFieldPlugin);
             app.add_plugins(systems::equipment::EquipmentPlugin);
             app.add_plugins(systems::enemy_attack::EnemyAttackPlugin);
             app.add_plugins(systems::player_combat::PlayerCombatPlugin);
             app.add_plugins(systems::combat::CombatPlugin);
-            app.add_plugins(systems::combat_effects::CombatEffectsPlugin);
             app.add_plugins(systems::defense_tower::DefenseTowerPlugin);
             app.add_plugins(systems::defense_wall::DefenseWallPlugin);
             app.add_plugins(systems::defense_range::DefenseRangePlugin);
             app.add_plugins(systems::plant_upgrade::PlantUpgradePlugin);
-            app.add_plugins(PlantUpgradeUIPlugin);
             app.add_plugins(systems::crafting::CraftingPlugin);
-            app.add_plugins(CraftingUIPlugin);
             app.add_plugins(systems::building::BuildingPlugin);
-            app.add_plugins(BuildingUIPlugin);
+            app.add_plugins(systems::power::PowerGridPlugin);
+            app.add_plugins(systems::logistics::LogisticsPlugin);
         }
         50 => {
             info!("Running Layer 50: Quest & Achievement");
@@ -112,20 +143,20 @@ fn main() {
             app.add_plugins(systems::plant::PlantPlugin);
             app.add_plugins(systems::enemy::EnemyPlugin);
             app.add_plugins(systems::robot::RobotPlugin);
+            app.add_plugins(systems::pathfinding::PathfindingPlugin);
+            app.add_plugins(systems::flow_field::FlowFieldPlugin);
             app.add_plugins(systems::equipment::EquipmentPlugin);
             app.add_plugins(systems::enemy_attack::EnemyAttackPlugin);
             app.add_plugins(systems::player_combat::PlayerCombatPlugin);
             app.add_plugins(systems::combat::CombatPlugin);
-            app.add_plugins(systems::combat_effects::CombatEffectsPlugin);
             app.add_plugins(systems::defense_tower::DefenseTowerPlugin);
             app.add_plugins(systems::defense_wall::DefenseWallPlugin);
             app.add_plugins(systems::defense_range::DefenseRangePlugin);
             app.add_plugins(systems::plant_upgrade::PlantUpgradePlugin);
-            app.add_plugins(PlantUpgradeUIPlugin);
             app.add_plugins(systems::crafting::CraftingPlugin);
-            app.add_plugins(CraftingUIPlugin);
             app.add_plugins(systems::building::BuildingPlugin);
-            app.add_plugins(BuildingUIPlugin);
+            app.add_plugins(systems::power::PowerGridPlugin);
+            app.add_plugins(systems::logistics::LogisticsPlugin);
             app.add_plugins(systems::quest_manager::QuestManagerPlugin);
             app.add_plugins(systems::quest_events::QuestEventsPlugin);
             app.add_plugins(systems::quest_generator::QuestGeneratorPlugin);
@@ -140,20 +171,20 @@ fn main() {
             app.add_plugins(systems::plant::PlantPlugin);
             app.add_plugins(systems::enemy::EnemyPlugin);
             app.add_plugins(systems::robot::RobotPlugin);
+            app.add_plugins(systems::pathfinding::PathfindingPlugin);
+            app.add_plugins(systems::flow_field::FlowFieldPlugin);
             app.add_plugins(systems::equipment::EquipmentPlugin);
             app.add_plugins(systems::enemy_attack::EnemyAttackPlugin);
             app.add_plugins(systems::player_combat::PlayerCombatPlugin);
             app.add_plugins(systems::combat::CombatPlugin);
-            app.add_plugins(systems::combat_effects::CombatEffectsPlugin);
             app.add_plugins(systems::defense_tower::DefenseTowerPlugin);
             app.add_plugins(systems::defense_wall::DefenseWallPlugin);
             app.add_plugins(systems::defense_range::DefenseRangePlugin);
             app.add_plugins(systems::plant_upgrade::PlantUpgradePlugin);
-            app.add_plugins(PlantUpgradeUIPlugin);
             app.add_plugins(systems::crafting::CraftingPlugin);
-            app.add_plugins(CraftingUIPlugin);
             app.add_plugins(systems::building::BuildingPlugin);
-            app.add_plugins(BuildingUIPlugin);
+            app.add_plugins(systems::power::PowerGridPlugin);
+            app.add_plugins(systems::logistics::LogisticsPlugin);
             app.add_plugins(systems::quest_manager::QuestManagerPlugin);
             app.add_plugins(systems::quest_events::QuestEventsPlugin);
             app.add_plugins(systems::quest_generator::QuestGeneratorPlugin);
@@ -161,14 +192,33 @@ fn main() {
             app.add_plugins(systems::achievement_events::AchievementEventsPlugin);
             app.add_plugins(systems::achievement_generator::AchievementGeneratorPlugin);
             app.add_plugins(systems::save_manager::SaveManagerPlugin);
-            app.add_plugins(systems::save_ui::SaveUIPlugin);
         }
         _ => {
             info!("Running Layer {}: Unknown layer, using default (Sys0)", layer);
         }
     }
 
-    app.add_systems(OnEnter(GameState::InGame), systems::player::spawn_player)
+    // UI 与纯视觉插件（无头模式下跳过）
+    if !headless {
+        if matches!(layer, 3 | 4 | 50 | 999) {
+            app.add_plugins(systems::combat_effects::CombatEffectsPlugin);
+        }
+        if matches!(layer, 4 | 50 | 999) {
+            app.add_plugins(PlantUpgradeUIPlugin);
+            app.add_plugins(CraftingUIPlugin);
+            app.add_plugins(BuildingUIPlugin);
+            app.add_plugins(SkillTreeUIPlugin);
+        }
+        if layer == 999 {
+            app.add_plugins(systems::save_ui::SaveUIPlugin);
+            app.add_plugins(SaveBrowserUIPlugin);
+        }
+    }
+
+    app.add_systems(OnEnter(GameState::InGame), systems::player::spawn_player.after(systems::map::setup_map))
+       .add_systems(OnEnter(GameState::InGame), systems::game_over::reset_resource::<systems::time::GameTime>
+           .run_if(systems::game_over::restarting))
+       .add_systems(Update, systems::time::update_time.run_if(in_state(GameState::InGame)))
        .add_systems(Update, systems::lighting::update_lighting)
        .run();
 }
@@ -191,19 +241,12 @@ fn init_render_assets(
 }
 
 fn setup(mut commands: Commands) {
-    let map_width = 20;
-    let map_height = 20;
-    let tile_size = 32.0;
-
-    let offset_x = -(map_width as f32 * tile_size) / 2.0 + tile_size / 2.0;
-    let offset_y = -(map_height as f32 * tile_size) / 2.0 + tile_size / 2.0;
-    let center_x = offset_x + (map_width / 2) as f32 * tile_size;
-    let center_y = offset_y + (map_height / 2) as f32 * tile_size;
-
+    // 地图生成后由 CameraControlPlugin 移到地图中心
     commands.spawn((
         Camera2d,
-        Transform::from_xyz(center_x, center_y, 100.0),
+        components::camera::CameraController::default(),
+        Transform::from_xyz(0.0, 0.0, 100.0),
         GlobalTransform::default(),
     ));
-    println!("Dark Forest Initialized! Camera at ({}, {})", center_x, center_y);
-}
\ No newline at end of file
+    info!("Dark Forest Initialized!");
+}
@@ -1,11 +1,12 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
 use bevy::reflect::Reflect;
 use std::ops::{Index, IndexMut};
 
 pub const TILE_SIZE: f32 = 32.0;
 pub const CHUNK_SIZE: u32 = 16;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect, Serialize, Deserialize)]
 pub enum TileType {
     #[default]
     Grass,
@@ -35,6 +36,22 @@ impl TileType {
         )
     }
 
+    /// 寻路通行代价，不可通行地形为无穷大
+    pub fn movement_cost(&self) -> f32 {
+        match self {
+            TileType::Grass => 1.0,
+            TileType::Desert => 1.5,
+            TileType::Forest => 2.0,
+            TileType::DarkForest => 3.0,
+            TileType::Mountain | TileType::Water => f32::INFINITY,
+        }
+    }
+
+    /// 玩家在该地形上的移动速度倍率：通行代价的倒数，不可通行地形为 0
+    pub fn move_speed_multiplier(&self) -> f32 {
+        1.0 / self.movement_cost()
+    }
+
     pub fn energy_multiplier(&self) -> f32 {
         match self {
             TileType::Grass => 1.0,
@@ -66,6 +83,7 @@ impl TileData {
 
 pub type MapTile = TileData;
 
+/// 世界地图（唯一的地块资源，由 MapPlugin 在进入游戏时生成）
 #[derive(Resource, Reflect, Default)]
 #[reflect(Resource)]
 pub struct MapGrid {
@@ -85,6 +103,14 @@ impl MapGrid {
         }
     }
 
+    pub fn width(&self) -> u32 {
+        self.size.x
+    }
+
+    pub fn height(&self) -> u32 {
+        self.size.y
+    }
+
     pub fn index(&self, x: u32, y: u32) -> Option<usize> {
         if x < self.size.x && y < self.size.y {
             Some((y * self.size.x + x) as usize)
@@ -110,18 +136,74 @@ impl MapGrid {
         }
     }
 
-    pub fn world_to_grid(world_pos: Vec2) -> UVec2 {
-        UVec2::new(
-            (world_pos.x / TILE_SIZE).floor() as u32,
-            (world_pos.y / TILE_SIZE).floor() as u32,
+    /// 地块 (0, 0) 中心的世界坐标（地图以世界原点为中心）
+    pub fn origin(&self) -> Vec2 {
+        Vec2::new(
+            -(self.size.x as f32 * TILE_SIZE) / 2.0 + TILE_SIZE / 2.0,
+            -(self.size.y as f32 * TILE_SIZE) / 2.0 + TILE_SIZE / 2.0,
         )
     }
 
-    pub fn grid_to_world(grid_pos: UVec2) -> Vec2 {
-        Vec2::new(
-            grid_pos.x as f32 * TILE_SIZE,
-            grid_pos.y as f32 * TILE_SIZE,
-        )
+    /// 地图中心地块中心的世界坐标
+    pub fn center(&self) -> Vec2 {
+        self.grid_to_world(self.size / 2)
+    }
+
+    /// 地块是否在地图范围内（允许传入负坐标）
+    pub fn contains(&self, tile: IVec2) -> bool {
+        tile.x >= 0 && tile.y >= 0 && (tile.x as u32) < self.size.x && (tile.y as u32) < self.size.y
+    }
+
+    /// 地块坐标 → 地块中心的世界坐标（可超出地图范围）
+    pub fn tile_to_world(&self, tile: IVec2) -> Vec2 {
+        self.origin() + tile.as_vec2() * TILE_SIZE
+    }
+
+    /// 世界坐标 → 所在地块坐标（可能为负或超出地图范围）
+    pub fn world_to_tile(&self, world_pos: Vec2) -> IVec2 {
+        ((world_pos - self.origin()) / TILE_SIZE + Vec2::splat(0.5))
+            .floor()
+            .as_ivec2()
+    }
+
+    /// 网格坐标 → 地块中心的世界坐标
+    pub fn grid_to_world(&self, grid_pos: UVec2) -> Vec2 {
+        self.tile_to_world(grid_pos.as_ivec2())
+    }
+
+    /// 世界坐标 → 网格坐标，超出地图时返回 None
+    pub fn world_to_grid(&self, world_pos: Vec2) -> Option<UVec2> {
+        let tile = self.world_to_tile(world_pos);
+        self.contains(tile).then(|| tile.as_uvec2())
+    }
+
+    /// 光标拾取：屏幕坐标 → 网格坐标
+    pub fn cursor_to_grid(
+        &self,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        cursor_position: Vec2,
+    ) -> Option<UVec2> {
+        let world_pos = camera.viewport_to_world_2d(camera_transform, cursor_position).ok()?;
+        self.world_to_grid(world_pos)
+    }
+
+    /// 地块坐标所在的区块（向下取整，负坐标落在负区块）
+    pub fn chunk_of(&self, tile: IVec2) -> IVec2 {
+        tile.div_euclid(IVec2::splat(CHUNK_SIZE as i32))
+    }
+
+    /// 世界坐标所在的区块，超出地图时返回 None
+    pub fn chunk_at_world(&self, world_pos: Vec2) -> Option<UVec2> {
+        let tile = self.world_to_tile(world_pos);
+        self.contains(tile).then(|| self.chunk_of(tile).as_uvec2())
+    }
+
+    /// 区块覆盖的网格范围 [min, max)，已裁剪到地图边界
+    pub fn chunk_bounds(&self, chunk: UVec2) -> (UVec2, UVec2) {
+        let min = (chunk * CHUNK_SIZE).min(self.size);
+        let max = ((chunk + UVec2::ONE) * CHUNK_SIZE).min(self.size);
+        (min, max)
     }
 
     pub fn explore_area(&mut self, center_x: u32, center_y: u32, radius: u32) {
@@ -169,14 +251,6 @@ impl IndexMut<UVec2> for MapGrid {
     }
 }
 
-#[derive(Event)]
-pub struct TileChangedEvent {
-    pub x: u32,
-    pub y: u32,
-    pub old_type: TileType,
-    pub new_type: TileType,
-}
-
 #[derive(Event)]
 pub struct MapReadyEvent {
     pub width: u32,
@@ -203,73 +277,66 @@ impl MapGenerationConfig {
     }
 }
 
-#[derive(Resource, Clone)]
-pub struct WorldMap {
-    pub width: u32,
-    pub height: u32,
-    pub tiles: Vec<Vec<MapTile>>,
-    pub fog_of_war: bool,
-    pub seed: u64,
-}
-
-impl Default for WorldMap {
-    fn default() -> Self {
-        Self {
-            width: 20,
-            height: 20,
-            tiles: Vec::new(),
-            fog_of_war: true,
-            seed: 0,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_world_round_trip_covers_negative_and_edge_tiles() {
+        let map = MapGrid::new(20, 10, 0);
+        for tile in [
+            IVec2::new(0, 0),
+            IVec2::new(19, 9),
+            IVec2::new(20, 10),
+            IVec2::new(-1, -1),
+            IVec2::new(-17, 4),
+        ] {
+            assert_eq!(map.world_to_tile(map.tile_to_world(tile)), tile);
         }
+        assert_eq!(map.grid_to_world(UVec2::ZERO), map.origin());
     }
-}
 
-impl WorldMap {
-    pub fn new(width: u32, height: u32, tiles: Vec<Vec<MapTile>>, fog_of_war: bool, seed: u64) -> Self {
-        Self {
-            width,
-            height,
-            tiles,
-            fog_of_war,
-            seed,
-        }
-    }
+    #[test]
+    fn tile_boundaries_belong_to_the_upper_tile() {
+        let map = MapGrid::new(20, 10, 0);
+        let half = TILE_SIZE / 2.0;
+        let origin = map.origin();
 
-    pub fn get_tile(&self, x: u32, y: u32) -> Option<&MapTile> {
-        if x < self.width && y < self.height {
-            self.tiles.get(y as usize).and_then(|row| row.get(x as usize))
-        } else {
-            None
-        }
+        assert_eq!(map.world_to_tile(origin + Vec2::splat(half - 0.01)), IVec2::ZERO);
+        assert_eq!(map.world_to_tile(origin + Vec2::splat(half)), IVec2::ONE);
+        assert_eq!(map.world_to_tile(origin - Vec2::splat(half)), IVec2::ZERO);
+        assert_eq!(map.world_to_tile(origin - Vec2::splat(half + 0.01)), IVec2::NEG_ONE);
     }
 
-    pub fn get_tile_mut(&mut self, x: u32, y: u32) -> Option<&mut MapTile> {
-        if x < self.width && y < self.height {
-            self.tiles.get_mut(y as usize).and_then(|row| row.get_mut(x as usize))
-        } else {
-            None
-        }
+    #[test]
+    fn world_to_grid_rejects_tiles_outside_the_map() {
+        let map = MapGrid::new(20, 10, 0);
+
+        assert_eq!(map.world_to_grid(map.tile_to_world(IVec2::new(19, 9))), Some(UVec2::new(19, 9)));
+        assert_eq!(map.world_to_grid(map.tile_to_world(IVec2::new(-1, 0))), None);
+        assert_eq!(map.world_to_grid(map.tile_to_world(IVec2::new(20, 0))), None);
+        assert_eq!(map.world_to_grid(map.tile_to_world(IVec2::new(0, 10))), None);
     }
 
-    pub fn explore_area(&mut self, center_x: u32, center_y: u32, radius: u32) {
-        for dy in -(radius as i32)..=(radius as i32) {
-            for dx in -(radius as i32)..=(radius as i32) {
-                let nx = center_x as i32 + dx;
-                let ny = center_y as i32 + dy;
+    #[test]
+    fn chunks_floor_negative_tiles() {
+        let map = MapGrid::new(40, 20, 0);
+        let chunk = CHUNK_SIZE as i32;
+
+        assert_eq!(map.chunk_of(IVec2::new(0, chunk - 1)), IVec2::ZERO);
+        assert_eq!(map.chunk_of(IVec2::new(chunk, 2 * chunk - 1)), IVec2::new(1, 1));
+        assert_eq!(map.chunk_of(IVec2::new(-1, -chunk)), IVec2::new(-1, -1));
+        assert_eq!(map.chunk_of(IVec2::new(-chunk - 1, 0)), IVec2::new(-2, 0));
+        assert_eq!(map.chunk_at_world(map.tile_to_world(IVec2::new(-1, 0))), None);
+        assert_eq!(map.chunk_at_world(map.tile_to_world(IVec2::new(39, 19))), Some(UVec2::new(2, 1)));
+    }
 
-                if nx >= 0 && ny >= 0 {
-                    let tile_x = nx as u32;
-                    let tile_y = ny as u32;
+    #[test]
+    fn chunk_bounds_are_clipped_to_the_map() {
+        let map = MapGrid::new(40, 20, 0);
 
-                    if let Some(tile) = self.get_tile_mut(tile_x, tile_y) {
-                        let distance = ((dx * dx + dy * dy) as f32).sqrt();
-                        if distance <= radius as f32 {
-                            tile.explored = true;
-                            tile.visible = true;
-                        }
-                    }
-                }
-            }
-        }
+        assert_eq!(map.chunk_bounds(UVec2::ZERO), (UVec2::ZERO, UVec2::splat(CHUNK_SIZE)));
+        assert_eq!(map.chunk_bounds(UVec2::new(2, 1)), (UVec2::new(32, 16), UVec2::new(40, 20)));
+        assert_eq!(map.chunk_count(), UVec2::new(3, 2));
     }
-}
\ No newline at end of file
+}
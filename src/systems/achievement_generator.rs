@@ -6,12 +6,16 @@ pub struct AchievementGeneratorPlugin;
 
 impl Plugin for AchievementGeneratorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, initialize_achievements);
+        app.add_systems(OnEnter(crate::states::GameState::InGame), initialize_achievements);
     }
 }
 
 /// 初始化所有成就
-fn initialize_achievements(mut commands: Commands) {
+fn initialize_achievements(mut commands: Commands, achievement_query: Query<(), With<Achievement>>) {
+    if !achievement_query.is_empty() {
+        return;
+    }
+
     // === 战斗成就 ===
 
     // 初次杀敌
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use crate::components::achievement::{
     Achievement, AchievementLog, AchievementCondition
 };
+use crate::components::experience::{ExperienceGainEvent, ExperienceSource};
 
 /// 成就管理系统插件
 pub struct AchievementManagerPlugin;
@@ -17,7 +18,7 @@ impl Plugin for AchievementManagerPlugin {
 
 /// 检查成就解锁条件
 fn check_achievements(
-    _commands: Commands,
+    mut commands: Commands,
     mut achievement_query: Query<&mut Achievement>,
     mut achievement_log_query: Query<&mut AchievementLog>,
     player_query: Query<&crate::components::player::Player>,
@@ -50,6 +51,10 @@ fn check_achievements(
         if should_unlock {
             // 解锁成就
             achievement.unlock();
+            commands.trigger(ExperienceGainEvent {
+                amount: achievement.reward.experience,
+                source: ExperienceSource::Achievement,
+            });
 
             // 更新成就日志
             let Ok(mut log) = achievement_log_query.single_mut() else { continue; };
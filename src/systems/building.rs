@@ -1,77 +1,270 @@
 use bevy::prelude::*;
 use crate::components::building::{
-    Building, BuildingType, BuildingStatus, BuildingPosition, Inventory, ResourceType
+    Building, BuildingType, BuildingStatus, BuildingPosition, ConstructionSite, ProductionState,
+    MAX_CONSTRUCTION_SITES, OUTPUT_COLLECT_RANGE, STORAGE_LINK_RANGE,
 };
-use crate::resources::world::WorldMap;
+use crate::components::building_catalog::BuildingCatalog;
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::{describe_amounts, ItemId, ItemRegistry};
+use crate::components::logistics::LogisticsRole;
+use crate::components::player::Player;
+use crate::components::power::PowerNode;
+use crate::resources::world::{MapGrid, TILE_SIZE};
 
 /// 建筑建造系统插件
 pub struct BuildingPlugin;
 
 impl Plugin for BuildingPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Inventory>()
-            .add_systems(Update, (
+        app.add_systems(Update, (
                 update_construction.run_if(in_state(crate::states::GameState::InGame)),
                 update_building_production.run_if(in_state(crate::states::GameState::InGame)),
+                collect_outputs_near_player.run_if(in_state(crate::states::GameState::InGame)),
                 update_building_function.run_if(in_state(crate::states::GameState::InGame)),
+                update_building_inventories.run_if(in_state(crate::states::GameState::InGame)),
             ));
     }
 }
 
-/// 更新建造进度
+/// 工地完工
+///
+/// 施工进度由建造机器人推进（见 `builder_robot_system`）。进度满后扣除工地材料，
+/// 多送的材料退回基地库存，工地库存换成建筑自身的本地库存。
 fn update_construction(
-    time: Res<Time>,
-    mut building_query: Query<(&mut BuildingStatus, &Building)>,
+    mut commands: Commands,
+    registry: Res<ItemRegistry>,
+    mut base_query: Query<&mut Inventory, With<Base>>,
+    mut building_query: Query<(Entity, &Building, &mut BuildingStatus, Option<&ConstructionSite>, Option<&mut Inventory>), Without<Base>>,
 ) {
-    for (mut status, building) in building_query.iter_mut() {
-        if status.is_constructing {
-            status.construction_progress += time.delta_secs() / building.stats.build_time;
-
-            if status.construction_progress >= 1.0 {
-                status.is_constructing = false;
-                status.construction_progress = 0.0;
-                info!("建造完成: {:?}", building.building_type);
+    let Ok(mut base_inventory) = base_query.single_mut() else { return; };
+
+    for (entity, building, mut status, site, inventory) in building_query.iter_mut() {
+        if !status.is_constructing || status.construction_progress < 1.0 {
+            continue;
+        }
+
+        status.is_constructing = false;
+        status.construction_progress = 0.0;
+
+        if let Some(site) = site {
+            if let Some(mut inventory) = inventory {
+                inventory.remove_all(&site.materials);
+                inventory.transfer_all_to(&mut base_inventory, &registry);
+
+                let capacity = building.stats.storage_capacity;
+                if capacity > 0 {
+                    inventory.capacity = Some(capacity);
+                } else {
+                    commands.entity(entity).remove::<Inventory>();
+                }
             }
+            commands.entity(entity).remove::<ConstructionSite>();
         }
+
+        info!("建造完成: {:?}", building.building_type);
     }
 }
 
+/// 可供其他建筑取用投入的建筑库存
+struct LinkedStorage {
+    entity: Entity,
+    tile: Vec2,
+    reserved: Vec<ItemId>,  // 该建筑自身配方的投入，不借给其他建筑
+}
+
 /// 更新建筑生产
+///
+/// 周期完成时先从本地库存、再从关联范围内其他建筑的库存（由近到远）扣除投入，
+/// 产出放入本地库存。投入不足、产出放不下或断电时停在周期末尾，
+/// 并在 `BuildingStatus::production` 中记录原因。
 fn update_building_production(
     time: Res<Time>,
-    mut building_query: Query<(&mut Building, &BuildingStatus)>,
-    mut inventory: ResMut<Inventory>,
+    registry: Res<ItemRegistry>,
+    mut building_query: Query<(Entity, &mut Building, &mut BuildingStatus, &BuildingPosition, Option<&PowerNode>)>,
+    mut inventory_query: Query<&mut Inventory, With<Building>>,
+) {
+    let storages: Vec<LinkedStorage> = building_query
+        .iter()
+        .filter(|(entity, _, status, _, _)| !status.is_constructing && inventory_query.contains(*entity))
+        .map(|(entity, building, _, position, _)| LinkedStorage {
+            entity,
+            tile: Vec2::new(position.tile_x as f32, position.tile_y as f32),
+            reserved: building.stats.inputs.keys().copied().collect(),
+        })
+        .collect();
+
+    for (entity, mut building, mut status, position, power) in building_query.iter_mut() {
+        let state = if status.is_constructing || !building.is_operational || !building.has_recipe() {
+            ProductionState::Idle
+        } else if power.is_some_and(|node| node.consumption > 0.0 && !node.powered) {
+            ProductionState::NoPower
+        } else if !building.advance_production(time.delta_secs()) {
+            ProductionState::Producing
+        } else {
+            let tile = Vec2::new(position.tile_x as f32, position.tile_y as f32);
+            let state = complete_production_cycle(entity, tile, &building, &storages, &mut inventory_query, &registry);
+            if state == ProductionState::Producing {
+                building.finish_cycle();
+            }
+            state
+        };
+
+        if status.production != state {
+            if state.is_stalled() {
+                debug!("{:?} 生产停滞: {}", building.building_type, state.name());
+            }
+            status.production = state;
+        }
+    }
+}
+
+/// 结算一个生产周期，投入与产出都满足时才修改库存，返回结算后的生产状态
+fn complete_production_cycle(
+    entity: Entity,
+    tile: Vec2,
+    building: &Building,
+    storages: &[LinkedStorage],
+    inventory_query: &mut Query<&mut Inventory, With<Building>>,
+    registry: &ItemRegistry,
+) -> ProductionState {
+    // 没有本地库存的建筑无处存放产出
+    let Ok(local) = inventory_query.get(entity) else {
+        return ProductionState::OutputFull;
+    };
+
+    let mut linked: Vec<&LinkedStorage> = storages
+        .iter()
+        .filter(|storage| storage.entity != entity && storage.tile.distance(tile) <= STORAGE_LINK_RANGE)
+        .collect();
+    linked.sort_by(|a, b| a.tile.distance(tile).total_cmp(&b.tile.distance(tile)));
+
+    // 本地库存不足的投入依次从关联库存中补齐
+    let mut updated = local.clone();
+    let mut draws = Vec::new();
+    for (&item, &amount) in &building.stats.inputs {
+        let mut needed = amount - updated.take(item, amount);
+        for storage in &linked {
+            if needed == 0 {
+                break;
+            }
+            if storage.reserved.contains(&item) {
+                continue;
+            }
+            let Ok(inventory) = inventory_query.get(storage.entity) else { continue; };
+            let taken = inventory.count(item).min(needed);
+            if taken > 0 {
+                draws.push((storage.entity, item, taken));
+                needed -= taken;
+            }
+        }
+        if needed > 0 {
+            return ProductionState::MissingInputs;
+        }
+    }
+
+    let fits = building
+        .stats
+        .outputs
+        .iter()
+        .all(|(&item, &amount)| updated.add(item, amount, registry) == amount);
+    if !fits {
+        return ProductionState::OutputFull;
+    }
+
+    for (source, item, amount) in draws {
+        if let Ok(mut inventory) = inventory_query.get_mut(source) {
+            inventory.take(item, amount);
+        }
+    }
+    if let Ok(mut local) = inventory_query.get_mut(entity) {
+        *local = updated;
+    }
+
+    ProductionState::Producing
+}
+
+/// 玩家走到建筑旁时把产出收进基地库存
+///
+/// 物流机器人之外的另一条路：没有搬运机器人时产出也能回到基地。
+/// 仓库里的物品由物流统一调度，不在这里收取。
+fn collect_outputs_near_player(
+    registry: Res<ItemRegistry>,
+    player_query: Query<&Transform, With<Player>>,
+    mut base_query: Query<&mut Inventory, With<Base>>,
+    mut building_query: Query<(&Building, &BuildingStatus, &Transform, &mut Inventory), Without<Base>>,
+) {
+    let Ok(player_transform) = player_query.single() else { return; };
+    let Ok(mut base_inventory) = base_query.single_mut() else { return; };
+    let player_position = player_transform.translation.truncate();
+
+    for (building, status, transform, mut inventory) in building_query.iter_mut() {
+        if status.is_constructing
+            || building.has_logistics_role(LogisticsRole::Storage)
+            || transform.translation.truncate().distance(player_position) > OUTPUT_COLLECT_RANGE * TILE_SIZE
+        {
+            continue;
+        }
+
+        for &item in building.stats.outputs.keys() {
+            let count = inventory.count(item);
+            if count == 0 {
+                continue;
+            }
+            let moved = inventory.transfer_to(&mut base_inventory, item, count, &registry);
+            if moved > 0 {
+                debug!("从 {:?} 收取 {} {}", building.building_type, registry.name(item), moved);
+            }
+        }
+    }
+}
+
+/// 有存储容量的建筑带有本地库存，容量跟随存储容量（升级、目录热重载），缺少库存时补上
+///
+/// 工地库存用来收材料，不受存储容量限制，完工时再换成本地库存。
+fn update_building_inventories(
+    mut commands: Commands,
+    mut building_query: Query<(Entity, &Building, &BuildingStatus, Option<&mut Inventory>), Changed<Building>>,
 ) {
-    for (mut building, status) in building_query.iter_mut() {
-        if !status.is_constructing && building.is_operational {
-            if let Some(resource_type) = building.produce(time.delta_secs()) {
-                match resource_type {
-                    ResourceType::Energy => inventory.energy += 1,
-                    ResourceType::Metal => inventory.metal += 1,
-                    ResourceType::Crystal => inventory.crystal += 1,
-                    ResourceType::Organic => inventory.organic += 1,
+    for (entity, building, status, inventory) in building_query.iter_mut() {
+        let capacity = building.stats.storage_capacity;
+        if capacity == 0 || status.is_constructing {
+            continue;
+        }
+
+        match inventory {
+            Some(mut inventory) => {
+                if inventory.capacity != Some(capacity) {
+                    inventory.capacity = Some(capacity);
                 }
             }
+            None => {
+                commands.entity(entity).insert(Inventory::with_capacity(capacity));
+            }
         }
     }
 }
 
 /// 更新建筑功能
 fn update_building_function(
-    building_query: Query<(&Building, &BuildingPosition)>,
-    world_map: Option<Res<WorldMap>>,
+    building_query: Query<(&Building, &BuildingPosition, Option<&PowerNode>)>,
+    map_grid: Option<Res<MapGrid>>,
 ) {
-    let world_map = match world_map {
-        Some(wm) => wm,
+    let map_grid = match map_grid {
+        Some(grid) => grid,
         None => return,
     };
 
-    for (building, position) in building_query.iter() {
+    for (building, position, power) in building_query.iter() {
+        // 用电建筑断电时停止工作
+        if power.is_some_and(|node| node.consumption > 0.0 && !node.powered) {
+            continue;
+        }
+
         // 根据建筑类型执行不同功能
         match building.building_type {
             BuildingType::Radar => {
                 // 雷达：探索周围区域
-                if let Some(_tile) = world_map.get_tile(position.tile_x, position.tile_y) {
+                if let Some(_tile) = map_grid.get(position.tile_x, position.tile_y) {
                     // TODO: 实现雷达探索功能
                 }
             }
@@ -88,22 +281,29 @@ fn update_building_function(
     }
 }
 
-/// 放置建筑
+/// 放置建筑工地
+///
+/// 建造成本不在放置时扣除，而是作为工地材料由机器人运来，所以同时存在的工地数量有上限。
+/// 已有建筑（含工地）的地块不能再放置。`cost_reduction` 为工程技能带来的建造成本降低比例。
 pub fn place_building(
     commands: &mut Commands,
     building_type: BuildingType,
     tile_x: u32,
     tile_y: u32,
-    world_map: &WorldMap,
-    inventory: &mut Inventory,
+    map_grid: &MapGrid,
+    catalog: &BuildingCatalog,
+    registry: &ItemRegistry,
+    cost_reduction: f32,
+    building_query: &Query<(&BuildingPosition, &BuildingStatus)>,
+    rendering: bool,
 ) -> Result<Entity, String> {
     // 检查位置是否有效
-    if tile_x >= world_map.width || tile_y >= world_map.height {
+    if tile_x >= map_grid.width() || tile_y >= map_grid.height() {
         return Err("位置超出地图范围".to_string());
     }
 
     // 检查地形是否可建造
-    if let Some(tile) = world_map.get_tile(tile_x, tile_y) {
+    if let Some(tile) = map_grid.get(tile_x, tile_y) {
         if !tile.tile_type.is_walkable() {
             return Err("地形不可建造".to_string());
         }
@@ -111,73 +311,96 @@ pub fn place_building(
         return Err("无效的位置".to_string());
     }
 
-    // 创建建筑
-    let mut building = Building::new(building_type);
-
-    // 检查资源是否足够
-    if !building.can_build(inventory) {
-        return Err("资源不足".to_string());
+    if building_query.iter().any(|(position, _)| position.tile_x == tile_x && position.tile_y == tile_y) {
+        return Err("该位置已有建筑".to_string());
     }
-
-    // 消耗资源
-    if !building.consume_build_resources(inventory) {
-        return Err("消耗资源失败".to_string());
+    let open_sites = building_query.iter().filter(|(_, status)| status.is_constructing).count();
+    if open_sites >= MAX_CONSTRUCTION_SITES {
+        return Err(format!("同时最多只能有 {} 个工地", MAX_CONSTRUCTION_SITES));
     }
 
-    // 计算世界坐标
-    let tile_size = 32.0;
-    let offset_x = -(world_map.width as f32 * tile_size) / 2.0 + tile_size / 2.0;
-    let offset_y = -(world_map.height as f32 * tile_size) / 2.0 + tile_size / 2.0;
+    // 按建筑目录创建建筑
+    let Some(definition) = catalog.get(building_type) else {
+        return Err(format!("建筑目录中没有 {:?}", building_type));
+    };
+    let mut building = Building::from_definition(definition);
+    building.apply_cost_reduction(cost_reduction);
+    let materials = building.stats.costs.clone();
 
-    // 生成建筑实体
-    let entity = commands.spawn((
-        Sprite {
-            color: building_type.color(),
-            custom_size: Some(Vec2::splat(tile_size * 0.8)),
-            ..default()
-        },
-        Transform::from_xyz(
-            offset_x + tile_x as f32 * tile_size,
-            offset_y + tile_y as f32 * tile_size,
-            2.0
-        ),
+    let entity = spawn_building(commands, building, definition.color(), tile_x, tile_y, map_grid, rendering);
+
+    info!("建造 {:?} 在 ({}, {})，需要材料 {}", building_type, tile_x, tile_y, describe_amounts(&materials, registry));
+
+    Ok(entity)
+}
+
+/// 在指定地块生成建筑工地（`rendering` 为 false 时不带外观）
+pub fn spawn_building(
+    commands: &mut Commands,
+    building: Building,
+    color: Color,
+    tile_x: u32,
+    tile_y: u32,
+    map_grid: &MapGrid,
+    rendering: bool,
+) -> Entity {
+    let world_pos = map_grid.grid_to_world(UVec2::new(tile_x, tile_y));
+    let site = ConstructionSite {
+        materials: building.stats.costs.clone(),
+    };
+
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 2.0),
         building,
+        site,
+        Inventory::default(),
         BuildingPosition {
             tile_x,
             tile_y,
         },
         BuildingStatus::default(),
-    )).id();
-
-    info!("建造 {:?} 在 ({}, {})", building_type, tile_x, tile_y);
+    ));
+    if rendering {
+        entity.insert(building_sprite(color));
+    }
+    entity.id()
+}
 
-    Ok(entity)
+/// 建筑外观
+pub fn building_sprite(color: Color) -> Sprite {
+    Sprite {
+        color,
+        custom_size: Some(Vec2::splat(TILE_SIZE * 0.8)),
+        ..default()
+    }
 }
 
 /// 升级建筑
+///
+/// `cost_reduction` 为工程技能带来的建造成本降低比例，升级后重新应用到建造成本上。
 pub fn upgrade_building(
     building_entity: Entity,
     inventory: &mut Inventory,
+    registry: &ItemRegistry,
+    cost_reduction: f32,
     building_query: &mut Query<&mut Building>,
 ) -> Result<bool, String> {
     let Ok(mut building) = building_query.get_mut(building_entity) else {
         return Err("建筑未找到".to_string());
     };
 
-    // 计算升级成本
-    let base_cost = 100u32;
-    let upgrade_cost = (base_cost as f32 * (1.0 + building.level as f32 * 0.5)) as u32;
-
-    // 检查资源是否足够
-    if inventory.energy < upgrade_cost {
-        return Err(format!("能源不足，需要 {}", upgrade_cost));
+    if !building.upgrade.can_upgrade(building.level) {
+        return Err("建筑已达到最高等级".to_string());
     }
 
-    // 扣除资源
-    inventory.energy -= upgrade_cost;
+    // 按升级曲线计算成本并扣除资源
+    let upgrade_cost = building.upgrade_cost();
+    if !inventory.remove_all(&upgrade_cost) {
+        return Err(format!("资源不足，需要 {}", describe_amounts(&upgrade_cost, registry)));
+    }
 
     // 升级建筑
-    building.upgrade();
+    building.upgrade(cost_reduction);
 
     info!("建筑升级到等级 {}", building.level);
 
@@ -215,3 +438,143 @@ pub fn stop_building(
 
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::components::item::amounts;
+
+    fn alloy() -> ItemId {
+        ItemId::new("Alloy")
+    }
+
+    /// 插入生产所需的资源，时间前进足够完成一个周期
+    fn production_world() -> World {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs(10));
+        world.insert_resource(time);
+        world.insert_resource(ItemRegistry::default());
+        world
+    }
+
+    fn spawn_building(world: &mut World, building_type: BuildingType, tile: (u32, u32), inventory: Inventory) -> Entity {
+        let mut building = Building::new(building_type);
+        building.is_operational = true;
+        world.spawn((
+            building,
+            BuildingStatus { is_constructing: false, ..default() },
+            BuildingPosition { tile_x: tile.0, tile_y: tile.1 },
+            Transform::from_xyz(tile.0 as f32 * TILE_SIZE, tile.1 as f32 * TILE_SIZE, 0.0),
+            inventory,
+        )).id()
+    }
+
+    fn stock(pairs: &[(ItemId, u32)], capacity: u32) -> Inventory {
+        Inventory {
+            items: amounts(pairs),
+            ..Inventory::with_capacity(capacity)
+        }
+    }
+
+    fn production(world: &World, entity: Entity) -> (ProductionState, f32) {
+        let building = world.get::<Building>(entity).unwrap();
+        (world.get::<BuildingStatus>(entity).unwrap().production, building.production_progress)
+    }
+
+    #[test]
+    fn refinery_completes_a_cycle_from_local_and_linked_storage() {
+        let mut world = production_world();
+        let refinery = spawn_building(&mut world, BuildingType::Refinery, (0, 0), stock(&[(ItemId::METAL, 2)], 100));
+        let storage = spawn_building(&mut world, BuildingType::Storage, (1, 0), stock(&[(ItemId::CRYSTAL, 3)], 100));
+
+        world.run_system_once(update_building_production).unwrap();
+
+        assert_eq!(production(&world, refinery), (ProductionState::Producing, 0.0));
+        let local = world.get::<Inventory>(refinery).unwrap();
+        assert_eq!(local.items, amounts(&[(alloy(), 1)]));
+        assert_eq!(world.get::<Inventory>(storage).unwrap().count(ItemId::CRYSTAL), 2);
+    }
+
+    #[test]
+    fn stalls_without_inputs_and_keeps_stock() {
+        let mut world = production_world();
+        let refinery = spawn_building(&mut world, BuildingType::Refinery, (0, 0), stock(&[(ItemId::METAL, 2)], 100));
+        // 超出关联范围的仓库不参与供料
+        spawn_building(&mut world, BuildingType::Storage, (5, 0), stock(&[(ItemId::CRYSTAL, 3)], 100));
+
+        world.run_system_once(update_building_production).unwrap();
+
+        assert_eq!(production(&world, refinery), (ProductionState::MissingInputs, 1.0));
+        assert_eq!(world.get::<Inventory>(refinery).unwrap().items, amounts(&[(ItemId::METAL, 2)]));
+    }
+
+    #[test]
+    fn stalls_when_output_does_not_fit_and_keeps_linked_stock() {
+        let mut world = production_world();
+        let refinery = spawn_building(&mut world, BuildingType::Refinery, (0, 0), stock(&[(alloy(), 4)], 4));
+        let storage = spawn_building(
+            &mut world,
+            BuildingType::Storage,
+            (1, 0),
+            stock(&[(ItemId::METAL, 2), (ItemId::CRYSTAL, 1)], 100),
+        );
+
+        world.run_system_once(update_building_production).unwrap();
+
+        assert_eq!(production(&world, refinery), (ProductionState::OutputFull, 1.0));
+        assert_eq!(world.get::<Inventory>(refinery).unwrap().count(alloy()), 4);
+        assert_eq!(world.get::<Inventory>(storage).unwrap().total(), 3);
+    }
+
+    #[test]
+    fn player_collects_outputs_into_the_base() {
+        let mut world = production_world();
+        world.spawn((Player::new(1, "test".to_string()), Transform::default()));
+        let base = world.spawn((Base, Inventory::default())).id();
+        let near = spawn_building(&mut world, BuildingType::Refinery, (1, 0), stock(&[(alloy(), 3), (ItemId::METAL, 2)], 100));
+        let far = spawn_building(&mut world, BuildingType::Refinery, (5, 0), stock(&[(alloy(), 3)], 100));
+
+        world.run_system_once(collect_outputs_near_player).unwrap();
+
+        // 只收产出，投入留给下一个周期
+        assert_eq!(world.get::<Inventory>(base).unwrap().items, amounts(&[(alloy(), 3)]));
+        assert_eq!(world.get::<Inventory>(near).unwrap().items, amounts(&[(ItemId::METAL, 2)]));
+        assert_eq!(world.get::<Inventory>(far).unwrap().count(alloy()), 3);
+    }
+
+    fn place(world: &mut World, tile: (u32, u32)) -> Result<(), String> {
+        world.run_system_once(move |
+            mut commands: Commands,
+            map_grid: Res<MapGrid>,
+            catalog: Res<BuildingCatalog>,
+            registry: Res<ItemRegistry>,
+            building_query: Query<(&BuildingPosition, &BuildingStatus)>,
+        | {
+            place_building(&mut commands, BuildingType::Storage, tile.0, tile.1, &map_grid, &catalog, &registry, 0.0, &building_query, false)
+                .map(|_| ())
+        }).unwrap()
+    }
+
+    #[test]
+    fn placement_rejects_occupied_tiles_and_caps_open_sites() {
+        let mut world = production_world();
+        world.insert_resource(MapGrid::new(8, 8, 0));
+        world.insert_resource(BuildingCatalog::default());
+
+        assert_eq!(place(&mut world, (0, 0)), Ok(()));
+        assert!(place(&mut world, (0, 0)).is_err());
+        for x in 1..MAX_CONSTRUCTION_SITES as u32 {
+            assert_eq!(place(&mut world, (x, 0)), Ok(()));
+        }
+        assert!(place(&mut world, (0, 1)).is_err());
+
+        // 完工后腾出工地名额
+        for mut status in world.query::<&mut BuildingStatus>().iter_mut(&mut world) {
+            status.is_constructing = false;
+        }
+        assert_eq!(place(&mut world, (0, 1)), Ok(()));
+    }
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+use crate::components::building::Building;
+use crate::components::building_catalog::{BuildingCatalog, BuildingCatalogAsset};
+use crate::components::skill::SkillBonuses;
+use crate::systems::json_catalog::JsonCatalogPlugin;
+
+/// 建筑目录插件：加载 `*.buildings.json`，热重载后重新计算已放置建筑的属性
+pub struct BuildingCatalogPlugin;
+
+impl Plugin for BuildingCatalogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonCatalogPlugin::<BuildingCatalogAsset>::default())
+            .add_systems(Update, refresh_buildings.run_if(resource_changed::<BuildingCatalog>));
+    }
+}
+
+fn refresh_buildings(
+    catalog: Res<BuildingCatalog>,
+    skill_bonuses: Option<Res<SkillBonuses>>,
+    mut building_query: Query<&mut Building>,
+) {
+    let cost_reduction = skill_bonuses.map_or(0.0, |bonuses| bonuses.building_cost);
+    for mut building in building_query.iter_mut() {
+        if let Some(definition) = catalog.get(building.building_type) {
+            building.apply_definition(definition, cost_reduction);
+        }
+    }
+}
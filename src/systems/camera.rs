@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use bevy::input::mouse::{AccumulatedMouseMotion, MouseScrollUnit, MouseWheel};
+use crate::components::building::BaseUnderAttackEvent;
+use crate::components::camera::{CameraController, CameraFocus, CameraJumpEvent};
+use crate::components::player::Player;
+use crate::resources::world::{MapGrid, TILE_SIZE};
+use crate::systems::enemy_spawn::WaveStartedEvent;
+use crate::ui::focus::wheel_free;
+
+/// 镜头插件：滚轮缩放、边缘/拖拽平移、跟随玩家与事件聚焦（波次来袭、基地遇袭）
+pub struct CameraControlPlugin;
+
+impl Plugin for CameraControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<CameraJumpEvent>()
+            .add_systems(Update, (
+                handle_camera_jumps,
+                camera_zoom.run_if(wheel_free),
+                camera_input,
+            ).chain().run_if(in_state(crate::states::GameState::InGame)))
+            // 在所有移动之后、变换传播之前更新，保证同一帧的拾取与画面一致
+            .add_systems(PostUpdate, update_camera
+                .before(TransformSystems::Propagate)
+                .run_if(in_state(crate::states::GameState::InGame)))
+            .add_systems(OnEnter(crate::states::GameState::InGame), center_on_map
+                .after(crate::systems::map::setup_map))
+            .add_observer(focus_on_wave)
+            .add_observer(focus_on_base_attack);
+    }
+}
+
+/// 波次来袭时镜头停留的时间（秒）
+const WAVE_FOCUS_DURATION: f32 = 3.0;
+
+/// 基地遇袭时镜头停留的时间（秒）
+const BASE_ATTACK_FOCUS_DURATION: f32 = 2.0;
+
+/// 地图生成后把镜头移到地图中心
+fn center_on_map(
+    map_grid: Option<Res<MapGrid>>,
+    mut camera_query: Query<(&mut Transform, &mut CameraController)>,
+) {
+    let Some(map_grid) = map_grid else { return; };
+    let center = map_grid.center();
+
+    for (mut transform, mut controller) in camera_query.iter_mut() {
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+        controller.anchor = center;
+    }
+}
+
+/// 处理镜头跳转请求
+fn handle_camera_jumps(
+    mut jump_events: MessageReader<CameraJumpEvent>,
+    mut camera_query: Query<&mut CameraController>,
+) {
+    // 同一帧多个请求时以最后一个为准
+    let Some(event) = jump_events.read().last() else { return; };
+
+    for mut controller in camera_query.iter_mut() {
+        controller.focus = Some(CameraFocus {
+            position: event.position,
+            remaining: event.duration,
+        });
+    }
+}
+
+/// 波次来袭时把镜头拉到出兵位置
+fn focus_on_wave(
+    event: On<WaveStartedEvent>,
+    mut jump_events: MessageWriter<CameraJumpEvent>,
+) {
+    jump_events.write(CameraJumpEvent {
+        position: event.event().position,
+        duration: WAVE_FOCUS_DURATION,
+    });
+}
+
+/// 建筑遇袭时把镜头拉到受击建筑
+fn focus_on_base_attack(
+    event: On<BaseUnderAttackEvent>,
+    mut jump_events: MessageWriter<CameraJumpEvent>,
+) {
+    jump_events.write(CameraJumpEvent {
+        position: event.event().position,
+        duration: BASE_ATTACK_FOCUS_DURATION,
+    });
+}
+
+/// 滚轮缩放（面板占用滚轮时不运行）
+fn camera_zoom(
+    mut wheel_events: MessageReader<MouseWheel>,
+    mut camera_query: Query<&mut CameraController>,
+) {
+    let scroll: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 24.0,
+        })
+        .sum();
+    if scroll == 0.0 {
+        return;
+    }
+
+    for mut controller in camera_query.iter_mut() {
+        controller.zoom_by(scroll);
+    }
+}
+
+/// 读取平移输入
+fn camera_input(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    windows: Query<&Window>,
+    mut camera_query: Query<(&Transform, &mut CameraController)>,
+) {
+    let Ok((transform, mut controller)) = camera_query.single_mut() else { return; };
+    let current = transform.translation.truncate();
+
+    if keyboard.just_pressed(KeyCode::Home) {
+        controller.recenter();
+    }
+
+    let zoom = controller.zoom;
+
+    // 中键拖拽：画面跟着鼠标走
+    if mouse_buttons.pressed(MouseButton::Middle) && mouse_motion.delta != Vec2::ZERO {
+        let delta = Vec2::new(-mouse_motion.delta.x, mouse_motion.delta.y) * zoom;
+        controller.pan(current, delta);
+        return;
+    }
+
+    // 鼠标贴近窗口边缘时平移
+    let Ok(window) = windows.single() else { return; };
+    if !window.focused {
+        return;
+    }
+    let Some(cursor) = window.cursor_position() else { return; };
+
+    let margin = controller.edge_margin;
+    let mut direction = Vec2::ZERO;
+    if cursor.x < margin {
+        direction.x -= 1.0;
+    } else if cursor.x > window.width() - margin {
+        direction.x += 1.0;
+    }
+    // 窗口坐标 y 轴向下
+    if cursor.y < margin {
+        direction.y += 1.0;
+    } else if cursor.y > window.height() - margin {
+        direction.y -= 1.0;
+    }
+
+    if direction != Vec2::ZERO {
+        let delta = direction.normalize() * controller.pan_speed * zoom * time.delta_secs();
+        controller.pan(current, delta);
+    }
+}
+
+/// 平滑移动与缩放镜头，并限制在地图范围内
+fn update_camera(
+    time: Res<Time>,
+    map_grid: Option<Res<MapGrid>>,
+    player_query: Query<Entity, With<Player>>,
+    target_query: Query<&Transform, Without<CameraController>>,
+    mut camera_query: Query<(&Camera, &mut Transform, &mut Projection, &mut CameraController)>,
+) {
+    let dt = time.delta_secs();
+
+    for (camera, mut transform, mut projection, mut controller) in camera_query.iter_mut() {
+        // 跟随目标缺失或已销毁时回到玩家
+        if controller.follow.is_none_or(|entity| !target_query.contains(entity)) {
+            controller.follow = player_query.iter().next();
+        }
+
+        let mut focus_position = None;
+        if let Some(focus) = controller.focus.as_mut() {
+            focus.remaining -= dt;
+            focus_position = Some(focus.position);
+        }
+        if controller.focus.is_some_and(|focus| focus.remaining <= 0.0) {
+            controller.focus = None;
+        }
+
+        let current = transform.translation.truncate();
+        let follow_position = if controller.free_look {
+            None
+        } else {
+            controller.follow
+                .and_then(|entity| target_query.get(entity).ok())
+                .map(|target| target.translation.truncate())
+        };
+        let target = focus_position.or(follow_position).unwrap_or(controller.anchor);
+
+        let blend = 1.0 - (-controller.smoothing * dt).exp();
+        controller.zoom += (controller.target_zoom - controller.zoom) * blend;
+        if let Projection::Orthographic(orthographic) = projection.as_mut() {
+            orthographic.scale = controller.zoom;
+        }
+
+        let mut next = current.lerp(target, blend);
+
+        if let (Some(map_grid), Some(viewport)) = (map_grid.as_deref(), camera.logical_viewport_size()) {
+            let half_view = viewport * 0.5 * controller.zoom;
+            next = clamp_to_map(map_grid, next, half_view);
+            controller.anchor = clamp_to_map(map_grid, controller.anchor, half_view);
+        }
+
+        transform.translation.x = next.x;
+        transform.translation.y = next.y;
+    }
+}
+
+/// 把镜头中心限制在地图内；视野比地图大时居中
+fn clamp_to_map(map_grid: &MapGrid, center: Vec2, half_view: Vec2) -> Vec2 {
+    let min = map_grid.origin() - Vec2::splat(TILE_SIZE / 2.0);
+    let max = min + map_grid.size.as_vec2() * TILE_SIZE;
+
+    let clamp_axis = |value: f32, min: f32, max: f32, half: f32| {
+        if max - min <= half * 2.0 {
+            (min + max) / 2.0
+        } else {
+            value.clamp(min + half, max - half)
+        }
+    };
+
+    Vec2::new(
+        clamp_axis(center.x, min.x, max.x, half_view.x),
+        clamp_axis(center.y, min.y, max.y, half_view.y),
+    )
+}
@@ -3,8 +3,12 @@ use crate::components::combat::{
     Combat, DamageEvent, HealEvent, DeathEvent, CombatEffect, CombatEffectType,
     CombatStats, DamageType
 };
-use crate::components::player::Player;
+use crate::components::player::{PlayerStats, PlayerVitals};
+use crate::components::building::{BaseAlert, BaseUnderAttackEvent, Building, BuildingStatus};
 use crate::components::enemy::Enemy;
+use crate::components::robot::RobotHealth;
+use crate::components::experience::{ExperienceGainEvent, ExperienceSource};
+use crate::components::game_over::RunStats;
 
 pub struct CombatPlugin;
 
@@ -13,6 +17,8 @@ impl Plugin for CombatPlugin {
         app.add_message::<DamageEvent>()
             .add_message::<HealEvent>()
             .add_message::<DeathEvent>()
+            .add_message::<BaseUnderAttackEvent>()
+            .init_resource::<BaseAlert>()
             .add_systems(Update, (
                 update_combat_cooldowns,
                 update_combat_effects,
@@ -37,26 +43,71 @@ fn update_combat_cooldowns(
 
 fn handle_damage_event(
     event: On<DamageEvent>,
+    time: Res<Time>,
     mut commands: Commands,
+    mut base_alert: ResMut<BaseAlert>,
     mut combat_query: Query<&mut Combat>,
     mut enemy_query: Query<&mut Enemy>,
-    mut player_query: Query<&mut Player>,
+    mut player_query: Query<(&PlayerStats, &mut PlayerVitals)>,
+    mut robot_query: Query<&mut RobotHealth>,
+    mut building_query: Query<(&Building, &mut BuildingStatus, &Transform)>,
 ) {
     let damage_event = event.event();
     let actual_damage = damage_event.damage;
 
     if let Ok(mut combat) = combat_query.get_mut(damage_event.target) {
         combat.damage_taken += actual_damage;
+    }
 
-        if let Ok(mut enemy) = enemy_query.get_mut(damage_event.target) {
+    // 敌人没有 Combat 组件，生命值直接记在 Enemy 上
+    if let Ok(mut enemy) = enemy_query.get_mut(damage_event.target) {
+        // 已死亡的敌人在销毁前可能再次受击，避免重复触发死亡
+        if !enemy.is_dead() {
             enemy.take_damage(actual_damage);
 
             if enemy.is_dead() {
                 commands.trigger(DeathEvent { entity: damage_event.target });
             }
         }
+    }
+
+    // 玩家死亡不走 DeathEvent（不销毁实体），由 GameOverPlugin 检测并结束本局
+    if let Ok((stats, mut vitals)) = player_query.get_mut(damage_event.target) {
+        let taken = stats.mitigate_damage(actual_damage, damage_event.damage_type);
+        vitals.take_damage(taken);
+    }
+
+    // 防御机器人按自身抗性减伤，机体损毁时与敌人一样销毁
+    if let Ok(mut health) = robot_query.get_mut(damage_event.target) {
+        if !health.is_destroyed() {
+            let resistance = combat_query
+                .get(damage_event.target)
+                .map_or(0.0, |combat| combat.defense.resistance(damage_event.damage_type).min(0.8));
+            health.take_damage(actual_damage * (1.0 - resistance));
 
-        if let Ok(_player) = player_query.get_mut(damage_event.target) {
+            if health.is_destroyed() {
+                info!("防御机器人被摧毁: {:?}", damage_event.target);
+                commands.trigger(DeathEvent { entity: damage_event.target });
+            }
+        }
+    }
+
+    // 建筑按防御力减伤，生命归零时拆除；遇袭提醒按间隔节流，避免镜头被每次攻击拉走
+    if let Ok((building, mut status, transform)) = building_query.get_mut(damage_event.target) {
+        if status.health > 0.0 {
+            let destroyed = status.take_damage(actual_damage, building.stats.defense);
+
+            if base_alert.try_alert(time.elapsed_secs()) {
+                warn!("基地遇袭: {:?} 受到攻击", building.building_type);
+                commands.trigger(BaseUnderAttackEvent {
+                    position: transform.translation.truncate(),
+                });
+            }
+
+            if destroyed {
+                warn!("建筑被摧毁: {:?}", building.building_type);
+                commands.trigger(DeathEvent { entity: damage_event.target });
+            }
         }
     }
 
@@ -67,18 +118,25 @@ fn handle_damage_event(
 fn handle_heal_event(
     event: On<HealEvent>,
     mut enemy_query: Query<&mut Enemy>,
+    mut player_query: Query<&mut PlayerVitals>,
 ) {
     let heal_event = event.event();
     if let Ok(mut enemy) = enemy_query.get_mut(heal_event.target) {
         enemy.heal(heal_event.amount);
         info!("治疗事件: {:?}, 恢复: {}", heal_event.target, heal_event.amount);
     }
+
+    if let Ok(mut vitals) = player_query.get_mut(heal_event.target) {
+        vitals.heal(heal_event.amount);
+    }
 }
 
 fn handle_death_event(
     event: On<DeathEvent>,
     mut commands: Commands,
     mut combat_query: Query<&mut CombatStats>,
+    enemy_query: Query<&Enemy>,
+    run_stats: Option<ResMut<RunStats>>,
 ) {
     let death_event = event.event();
 
@@ -86,6 +144,16 @@ fn handle_death_event(
         stats.enemies_defeated += 1;
     }
 
+    if let Ok(enemy) = enemy_query.get(death_event.entity) {
+        if let Some(mut run_stats) = run_stats {
+            run_stats.enemies_killed += 1;
+        }
+        commands.trigger(ExperienceGainEvent {
+            amount: enemy.stats.xp_reward,
+            source: ExperienceSource::Enemy,
+        });
+    }
+
     commands.entity(death_event.entity).despawn();
     info!("死亡事件: {:?}", death_event.entity);
 }
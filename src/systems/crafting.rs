@@ -1,10 +1,10 @@
 use bevy::prelude::*;
 use crate::components::crafting::{
-    CraftingInventory, CraftingRecipe, RecipeBook, QualityControl, UpgradeOptimization,
-    MaterialType
+    CraftingRecipe, RecipeBook, QualityControl, UpgradeOptimization,
 };
 use crate::components::equipment::{Equipment, EquipmentStats, EquipmentType, EquipmentRarity};
-use crate::components::resource::Inventory;
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::{ItemId, ItemRegistry};
 use rand::Rng;
 
 /// 装备制造系统插件
@@ -13,8 +13,6 @@ pub struct CraftingPlugin;
 impl Plugin for CraftingPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<RecipeBook>()
-            .init_resource::<Inventory>()
-            .init_resource::<CraftingInventory>()
             .init_resource::<QualityControl>()
             .init_resource::<UpgradeOptimization>()
             .add_systems(Startup, initialize_recipes)
@@ -35,11 +33,11 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Common,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 10,
             },
             MaterialRequirement {
-                material_type: MaterialType::Energy,
+                item: ItemId::ENERGY,
                 amount: 20,
             },
         ],
@@ -53,15 +51,15 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Uncommon,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 20,
             },
             MaterialRequirement {
-                material_type: MaterialType::Energy,
+                item: ItemId::ENERGY,
                 amount: 40,
             },
             MaterialRequirement {
-                material_type: MaterialType::Crystal,
+                item: ItemId::CRYSTAL,
                 amount: 5,
             },
         ],
@@ -75,15 +73,15 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Rare,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 30,
             },
             MaterialRequirement {
-                material_type: MaterialType::Energy,
+                item: ItemId::ENERGY,
                 amount: 60,
             },
             MaterialRequirement {
-                material_type: MaterialType::Crystal,
+                item: ItemId::CRYSTAL,
                 amount: 10,
             },
         ],
@@ -97,11 +95,11 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Common,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 15,
             },
             MaterialRequirement {
-                material_type: MaterialType::Organic,
+                item: ItemId::ORGANIC,
                 amount: 10,
             },
         ],
@@ -115,11 +113,11 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Uncommon,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 30,
             },
             MaterialRequirement {
-                material_type: MaterialType::Organic,
+                item: ItemId::ORGANIC,
                 amount: 20,
             },
         ],
@@ -133,11 +131,11 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Rare,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Crystal,
+                item: ItemId::CRYSTAL,
                 amount: 15,
             },
             MaterialRequirement {
-                material_type: MaterialType::Energy,
+                item: ItemId::ENERGY,
                 amount: 50,
             },
         ],
@@ -151,11 +149,11 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Common,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Crystal,
+                item: ItemId::CRYSTAL,
                 amount: 5,
             },
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 10,
             },
         ],
@@ -169,11 +167,11 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Uncommon,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Energy,
+                item: ItemId::ENERGY,
                 amount: 30,
             },
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 15,
             },
         ],
@@ -187,15 +185,15 @@ fn initialize_recipes(mut recipe_book: ResMut<RecipeBook>) {
         EquipmentRarity::Rare,
         vec![
             MaterialRequirement {
-                material_type: MaterialType::Crystal,
+                item: ItemId::CRYSTAL,
                 amount: 10,
             },
             MaterialRequirement {
-                material_type: MaterialType::Metal,
+                item: ItemId::METAL,
                 amount: 20,
             },
             MaterialRequirement {
-                material_type: MaterialType::Energy,
+                item: ItemId::ENERGY,
                 amount: 40,
             },
         ],
@@ -225,9 +223,10 @@ fn update_crafting_status(
 /// 检查制造条件
 fn check_crafting_conditions(
     recipe_book: Res<RecipeBook>,
-    inventory: Res<crate::components::crafting::CraftingInventory>,
+    base_query: Query<&Inventory, With<Base>>,
 ) {
-    let craftable = recipe_book.get_craftable_recipes(&inventory);
+    let Ok(inventory) = base_query.single() else { return; };
+    let craftable = recipe_book.get_craftable_recipes(inventory);
     if !craftable.is_empty() {
         info!("可制造的装备数量: {}", craftable.len());
     }
@@ -258,6 +257,7 @@ pub fn start_crafting(
     equipment_type: EquipmentType,
     rarity: EquipmentRarity,
     inventory: &mut Inventory,
+    registry: &ItemRegistry,
     recipe_book: &RecipeBook,
     quality_control: &QualityControl,
     rng: &mut impl Rng,
@@ -275,26 +275,26 @@ pub fn start_crafting(
     }
 
     // 检查能源是否足够
-    if inventory.energy < recipe.energy_cost {
+    if inventory.count(ItemId::ENERGY) < recipe.energy_cost {
         return Err(format!("能源不足，需要 {}", recipe.energy_cost));
     }
 
     // 检查材料是否足够
     for material in &recipe.materials {
-        let amount = inventory.get_material(material.material_type);
+        let amount = inventory.count(material.item);
         if amount < material.amount {
             return Err(format!(
                 "{} 不足，需要 {}",
-                material.material_type.name(),
+                registry.name(material.item),
                 material.amount
             ));
         }
     }
 
     // 扣除能源和材料
-    inventory.energy -= recipe.energy_cost;
+    inventory.remove(ItemId::ENERGY, recipe.energy_cost);
     for material in &recipe.materials {
-        inventory.remove_material(material.material_type, material.amount);
+        inventory.take(material.item, material.amount);
     }
 
     // 计算品质
@@ -340,14 +340,11 @@ pub fn upgrade_equipment(
     let base_cost = 50u32;
     let cost = upgrade_optimization.calculate_upgrade_cost(base_cost, equipment.level);
 
-    // 检查能源是否足够
-    if inventory.energy < cost {
+    // 扣除能源
+    if !inventory.remove(ItemId::ENERGY, cost) {
         return Err(format!("能源不足，需要 {}", cost));
     }
 
-    // 扣除能源
-    inventory.energy -= cost;
-
     // 检查升级是否成功
     if upgrade_optimization.check_upgrade_success(rng) {
         equipment.upgrade();
@@ -8,7 +8,9 @@ impl Plugin for DefenseRangePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, (
             update_defense_ranges.run_if(in_state(crate::states::GameState::InGame)),
-            draw_defense_ranges.run_if(in_state(crate::states::GameState::InGame)),
+            draw_defense_ranges
+                .run_if(in_state(crate::states::GameState::InGame))
+                .run_if(crate::systems::headless::rendering_enabled),
         ));
     }
 }
@@ -2,8 +2,10 @@ use bevy::prelude::*;
 use crate::components::defense::{
     DefenseTower, DefenseTowerType, DefenseEffect, DefenseEffectType, DefenseStats
 };
+use crate::components::building_catalog::BuildingCatalog;
 use crate::components::enemy::Enemy;
 use crate::components::combat::{DamageEvent, DamageType};
+use crate::components::power::{PowerNode, PowerPriority, CABLE_CONNECTION_RANGE};
 
 /// 防御塔系统插件
 pub struct DefenseTowerPlugin;
@@ -248,11 +250,13 @@ fn handle_damage_stats(
     }
 }
 
-/// 创建防御塔
+/// 创建防御塔（`rendering` 为 false 时不带外观）
 pub fn create_defense_tower(
     commands: &mut Commands,
     tower_type: DefenseTowerType,
     position: Vec3,
+    catalog: &BuildingCatalog,
+    rendering: bool,
 ) -> Entity {
     let tower = DefenseTower::new(tower_type);
     let color = match tower_type {
@@ -264,17 +268,26 @@ pub fn create_defense_tower(
         DefenseTowerType::ElectricTower => Color::srgb(1.0, 0.8, 0.0),
     };
 
-    commands.spawn((
-        Sprite {
-            color,
-            custom_size: Some(Vec2::new(32.0, 32.0)),
-            ..default()
-        },
+    let mut entity = commands.spawn((
         Transform::from_xyz(position.x, position.y, 1.0),
         GlobalTransform::default(),
+        // 防御塔从电网取电，断电时停止攻击
+        PowerNode::consumer(
+            tower.stats.energy_cost * catalog.tower_power_per_energy_cost,
+            CABLE_CONNECTION_RANGE,
+            PowerPriority::High,
+        ),
         tower,
         DefenseStats::default(),
-    )).id()
+    ));
+    if rendering {
+        entity.insert(Sprite {
+            color,
+            custom_size: Some(Vec2::new(32.0, 32.0)),
+            ..default()
+        });
+    }
+    entity.id()
 }
 
 /// 升级防御塔
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use crate::components::defense::{DefenseWall, DefenseStats};
 use crate::components::player::Player;
+use crate::systems::headless::HeadlessConfig;
 
 /// 防御墙系统插件
 pub struct DefenseWallPlugin;
@@ -19,12 +20,15 @@ fn update_defense_walls(
     mut commands: Commands,
     wall_query: Query<(Entity, &DefenseWall, &Transform), Without<Player>>,
     _time: Res<Time>,
+    headless: Option<Res<HeadlessConfig>>,
 ) {
     for (entity, wall, transform) in wall_query.iter() {
         // 检查是否被摧毁
         if wall.is_destroyed() {
-            // 播放摧毁效果
-            spawn_wall_destruction(&mut commands, transform);
+            // 播放摧毁效果（无头模式下跳过）
+            if headless.is_none() {
+                spawn_wall_destruction(&mut commands, transform);
+            }
 
             // 移除墙体
             commands.entity(entity).despawn();
@@ -81,24 +85,28 @@ pub struct WallDebris {
     pub timer: f32,
 }
 
-/// 创建防御墙
+/// 创建防御墙（`rendering` 为 false 时不带外观）
 pub fn create_defense_wall(
     commands: &mut Commands,
     position: Vec3,
+    rendering: bool,
 ) -> Entity {
     let wall = DefenseWall::new();
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.4, 0.4, 0.4),
-            custom_size: Some(Vec2::new(32.0, 32.0)),
-            ..default()
-        },
+    let mut entity = commands.spawn((
         Transform::from_xyz(position.x, position.y, 0.5),
         wall,
         DefenseStats::default(),
         GlobalTransform::default(),
-    )).id()
+    ));
+    if rendering {
+        entity.insert(Sprite {
+            color: Color::srgb(0.4, 0.4, 0.4),
+            custom_size: Some(Vec2::new(32.0, 32.0)),
+            ..default()
+        });
+    }
+    entity.id()
 }
 
 /// 修理防御墙
@@ -1,9 +1,14 @@
 use bevy::prelude::*;
 use crate::components::enemy::{
-    Enemy, EnemyType, EnemyPosition, EnemyStatus, AIState, AIBehavior, AttackType, EnemyBase
+    Enemy, EnemyType, EnemyPosition, EnemyStatus, AIState, AIBehavior, EnemyBase
 };
 use crate::components::player::Player;
-use crate::resources::world::WorldMap;
+use crate::components::building::Building;
+use crate::components::robot::RobotHealth;
+use crate::components::pathfinding::{PathCache, NavObstacles, FlowFields};
+use crate::resources::world::{MapGrid, TILE_SIZE};
+use crate::systems::flow_field::step_along_flow;
+use crate::systems::pathfinding::step_along_path;
 
 /// 敌人AI系统插件
 pub struct EnemyPlugin;
@@ -13,7 +18,7 @@ impl Plugin for EnemyPlugin {
         app.add_systems(Update, (
             update_enemy_ai.run_if(in_state(crate::states::GameState::InGame)),
             update_enemy_movement.run_if(in_state(crate::states::GameState::InGame)),
-            update_enemy_attack.run_if(in_state(crate::states::GameState::InGame)),
+            // 攻击冷却与伤害由 EnemyAttackPlugin 负责
         ));
     }
 }
@@ -29,16 +34,15 @@ fn update_enemy_ai(
         &EnemyPosition
     ), Without<Player>>,
     player_query: Query<(Entity, &Transform), (With<Player>, Without<Enemy>)>,
-    world_map: Option<Res<WorldMap>>,
+    defender_query: Query<(Entity, &Transform), (With<RobotHealth>, Without<Enemy>)>,
+    building_query: Query<(Entity, &Transform), (With<Building>, Without<Enemy>)>,
+    map_grid: Option<Res<MapGrid>>,
 ) {
-    let world_map = match world_map {
-        Some(wm) => wm,
+    let map_grid = match map_grid {
+        Some(grid) => grid,
         None => return,
     };
 
-    let Ok((player_entity, player_transform)) = player_query.single() else { return; };
-    let player_pos = player_transform.translation;
-
     for (_entity, mut enemy, mut status, transform, position) in enemy_query.iter_mut() {
         if enemy.is_dead() {
             enemy.ai_state = AIState::Dead;
@@ -46,10 +50,22 @@ fn update_enemy_ai(
         }
 
         let enemy_pos = transform.translation;
-        let distance_to_player = enemy_pos.distance(player_pos);
+
+        // 在玩家、防御机器人与建筑中选择最近的目标（玩家阵亡后仍会攻击机器人和建筑）
+        let target = player_query
+            .iter()
+            .chain(defender_query.iter())
+            .chain(building_query.iter())
+            .min_by(|(_, a), (_, b)| {
+                a.translation.distance(enemy_pos).total_cmp(&b.translation.distance(enemy_pos))
+            });
+        let target_entity = target.map(|(target_entity, _)| target_entity);
+        // 敌人属性中的距离以地块为单位，没有任何目标时视为无穷远
+        let distance_to_target = target
+            .map_or(f32::INFINITY, |(_, target_transform)| enemy_pos.distance(target_transform.translation) / TILE_SIZE);
 
         // 根据敌人类型和当前状态更新AI
-        let behavior = enemy.enemy_type.ai_behavior();
+        let behavior = enemy.behavior;
 
         match behavior {
             AIBehavior::Passive => {
@@ -62,26 +78,26 @@ fn update_enemy_ai(
             }
 
             AIBehavior::Patrol => {
-                // 巡逻AI：在区域内巡逻，发现玩家后追踪
-                if distance_to_player <= enemy.stats.detection_range {
+                // 巡逻AI：在区域内巡逻，发现目标后追踪
+                if distance_to_target <= enemy.stats.detection_range {
                     enemy.ai_state = AIState::Chase;
-                    enemy.target = Some(player_entity);
+                    enemy.target = target_entity;
                 } else {
                     enemy.ai_state = AIState::Patrol;
                     // 巡逻逻辑
-                    patrol_ai(&mut enemy, &time, &world_map, position);
+                    patrol_ai(&mut enemy, &time, &map_grid, position);
                 }
             }
 
             AIBehavior::Aggressive => {
-                // 主动AI：主动攻击玩家
-                if distance_to_player <= enemy.stats.detection_range {
-                    if distance_to_player <= enemy.stats.attack_range {
+                // 主动AI：主动攻击最近的目标
+                if distance_to_target <= enemy.stats.detection_range {
+                    if distance_to_target <= enemy.stats.attack_range {
                         enemy.ai_state = AIState::Attack;
-                        enemy.target = Some(player_entity);
+                        enemy.target = target_entity;
                     } else {
                         enemy.ai_state = AIState::Chase;
-                        enemy.target = Some(player_entity);
+                        enemy.target = target_entity;
                     }
                 } else {
                     enemy.ai_state = AIState::Idle;
@@ -90,9 +106,9 @@ fn update_enemy_ai(
 
             AIBehavior::Guard => {
                 // 守卫AI：保护特定区域
-                if distance_to_player <= enemy.stats.detection_range {
+                if distance_to_target <= enemy.stats.detection_range {
                     enemy.ai_state = AIState::Chase;
-                    enemy.target = Some(player_entity);
+                    enemy.target = target_entity;
                 } else {
                     enemy.ai_state = AIState::Idle;
                     // 守卫逻辑：返回守卫位置
@@ -102,17 +118,17 @@ fn update_enemy_ai(
 
             AIBehavior::Ranged => {
                 // 远程AI：保持距离攻击
-                if distance_to_player <= enemy.stats.detection_range {
-                    if distance_to_player <= enemy.stats.attack_range {
+                if distance_to_target <= enemy.stats.detection_range {
+                    if distance_to_target <= enemy.stats.attack_range {
                         enemy.ai_state = AIState::Attack;
-                        enemy.target = Some(player_entity);
-                    } else if distance_to_player > enemy.stats.attack_range * 1.5 {
+                        enemy.target = target_entity;
+                    } else if distance_to_target > enemy.stats.attack_range * 1.5 {
                         enemy.ai_state = AIState::Chase;
-                        enemy.target = Some(player_entity);
+                        enemy.target = target_entity;
                     } else {
                         // 保持最佳攻击距离
                         enemy.ai_state = AIState::Chase;
-                        enemy.target = Some(player_entity);
+                        enemy.target = target_entity;
                     }
                 } else {
                     enemy.ai_state = AIState::Idle;
@@ -121,7 +137,8 @@ fn update_enemy_ai(
 
             AIBehavior::Boss => {
                 // Boss AI：特殊行为
-                boss_ai(&mut enemy, &mut status, distance_to_player, &player_query);
+                boss_ai(&mut enemy, &mut status, distance_to_target, &player_query);
+                enemy.target = target_entity;
             }
 
             AIBehavior::Spawn => {
@@ -133,9 +150,8 @@ fn update_enemy_ai(
 }
 
 /// 巡逻AI逻辑
-fn patrol_ai(_enemy: &mut Enemy, time: &Time, world_map: &WorldMap, position: &EnemyPosition) {
+fn patrol_ai(_enemy: &mut Enemy, time: &Time, map_grid: &MapGrid, position: &EnemyPosition) {
     // 简化的巡逻逻辑：随机选择相邻瓦片
-    let tile_size = 32.0;
     let patrol_radius = 5.0;
 
     // 使用时间作为随机种子
@@ -155,16 +171,13 @@ fn patrol_ai(_enemy: &mut Enemy, time: &Time, world_map: &WorldMap, position: &E
         let new_x = new_x as u32;
         let new_y = new_y as u32;
 
-        if let Some(tile) = world_map.get_tile(new_x, new_y) {
+        if let Some(tile) = map_grid.get(new_x, new_y) {
             if tile.tile_type.is_walkable() {
                 // 计算距离，确保不超出巡逻范围
-                let center_x = position.tile_x as f32 * tile_size;
-                let center_y = position.tile_y as f32 * tile_size;
-                let target_x = new_x as f32 * tile_size;
-                let target_y = new_y as f32 * tile_size;
+                let center = map_grid.grid_to_world(UVec2::new(position.tile_x, position.tile_y));
+                let target = map_grid.grid_to_world(UVec2::new(new_x, new_y));
 
-                let distance = ((target_x - center_x).powi(2) + (target_y - center_y).powi(2)).sqrt();
-                if distance <= patrol_radius * tile_size {
+                if center.distance(target) <= patrol_radius * TILE_SIZE {
                     // 更新目标位置（实际移动在movement系统中处理）
                 }
             }
@@ -217,29 +230,46 @@ fn boss_ai(
 /// 更新敌人移动
 fn update_enemy_movement(
     time: Res<Time>,
-    mut enemy_query: Query<(&mut Transform, &Enemy, &EnemyPosition), Without<EnemyBase>>,
+    map_grid: Option<Res<MapGrid>>,
+    obstacles: Res<NavObstacles>,
+    flow_fields: Res<FlowFields>,
+    mut enemy_query: Query<(&mut Transform, &mut Enemy, &mut EnemyPosition, &mut PathCache), Without<EnemyBase>>,
+    target_query: Query<&Transform, Without<Enemy>>,
 ) {
-    for (mut transform, enemy, _position) in enemy_query.iter_mut() {
+    let Some(map_grid) = map_grid else { return; };
+
+    for (mut transform, mut enemy, mut position, mut path) in enemy_query.iter_mut() {
         if enemy.ai_state == AIState::Dead {
             continue;
         }
 
-        let tile_size = 32.0;
-        let speed = enemy.stats.movement_speed * tile_size * time.delta_secs();
+        let speed = enemy.stats.movement_speed * TILE_SIZE * time.delta_secs();
 
         match enemy.ai_state {
             AIState::Chase => {
-                // 追逐玩家
-                if let Some(_target) = enemy.target {
-                    // 这里需要获取目标的位置
-                    // 简化处理：向目标方向移动
-                    // 实际实现需要查询目标实体的Transform
+                // 追逐目标：主动型与巡逻型敌人沿目标的共享流场前进，
+                // 目标没有流场（如防御机器人）或流场走不通时使用 A* 路径
+                let Some(target) = enemy.target else { continue; };
+                let Ok(target_transform) = target_query.get(target) else { continue; };
+                let target_pos = target_transform.translation.truncate();
+
+                let flowed = matches!(enemy.behavior, AIBehavior::Aggressive | AIBehavior::Patrol)
+                    && flow_fields
+                        .get(target)
+                        .is_some_and(|field| step_along_flow(&map_grid, field, &mut transform, target_pos, speed));
+                if !flowed && !step_along_path(&map_grid, &obstacles, &mut path, &mut transform, target_pos, speed) {
+                    // 目标不可达：放弃追击，等障碍变化后由 AI 重新选择
+                    debug!("{:?} 无法到达目标 {:?}", enemy.enemy_type, target);
+                    enemy.ai_state = AIState::Idle;
+                    enemy.target = None;
                 }
             }
             AIState::Retreat => {
-                // 撤退
-                let retreat_direction = transform.translation.normalize();
-                transform.translation += retreat_direction * speed;
+                // 撤退：远离地图中心，撤向三格之外的地块
+                let current = transform.translation.truncate();
+                let retreat_direction = current.normalize_or_zero();
+                let retreat_pos = current + retreat_direction * TILE_SIZE * 3.0;
+                step_along_path(&map_grid, &obstacles, &mut path, &mut transform, retreat_pos, speed);
             }
             AIState::Patrol => {
                 // 巡逻移动
@@ -249,99 +279,38 @@ fn update_enemy_movement(
                 // 其他状态不移动
             }
         }
-    }
-}
-
-/// 更新敌人攻击
-fn update_enemy_attack(
-    time: Res<Time>,
-    mut enemy_query: Query<(&mut Enemy, &mut EnemyStatus)>,
-) {
-    for (mut enemy, mut status) in enemy_query.iter_mut() {
-        if enemy.ai_state == AIState::Dead {
-            continue;
-        }
 
-        // 更新攻击冷却
-        if enemy.attack_cooldown > 0.0 {
-            enemy.attack_cooldown -= time.delta_secs();
-        }
-
-        match enemy.ai_state {
-            AIState::Attack => {
-                // 攻击逻辑
-                if enemy.attack_cooldown <= 0.0 {
-                    status.is_attacking = true;
-                    status.attack_timer = 1.0 / enemy.stats.attack_speed;
-                    enemy.attack_cooldown = 1.0 / enemy.stats.attack_speed;
-
-                    // 根据攻击类型执行不同的攻击
-                    match enemy.enemy_type.attack_type() {
-                        AttackType::Melee => {
-                            // 近战攻击
-                            perform_melee_attack(&enemy);
-                        }
-                        AttackType::Laser => {
-                            // 激光攻击
-                            perform_laser_attack(&enemy);
-                        }
-                        AttackType::Spit => {
-                            // 喷吐攻击
-                            perform_spit_attack(&enemy);
-                        }
-                        AttackType::Summon => {
-                            // 召唤攻击
-                            perform_summon_attack(&enemy);
-                        }
-                        AttackType::None => {
-                            // 无攻击
-                        }
-                    }
-                }
-            }
-            _ => {
-                status.is_attacking = false;
-            }
+        // 同步所在地块
+        if let Some(grid_pos) = map_grid.world_to_grid(transform.translation.truncate()) {
+            position.tile_x = grid_pos.x;
+            position.tile_y = grid_pos.y;
         }
     }
 }
 
-/// 执行近战攻击
-fn perform_melee_attack(enemy: &Enemy) {
-    info!("近战攻击: {:?}, 伤害: {}", enemy.enemy_type, enemy.stats.damage);
-    // 实际实现需要：
-    // 1. 检测攻击范围内的目标
-    // 2. 对目标造成伤害
-    // 3. 播放攻击动画
-    // 4. 创建攻击效果
-}
-
-/// 执行激光攻击
-fn perform_laser_attack(enemy: &Enemy) {
-    info!("激光攻击: {:?}, 伤害: {}", enemy.enemy_type, enemy.stats.damage);
-    // 实际实现需要：
-    // 1. 创建激光束实体
-    // 2. 对路径上的目标造成伤害
-    // 3. 播放激光效果
-    // 4. 播放攻击动画
-}
-
-/// 执行喷吐攻击
-fn perform_spit_attack(enemy: &Enemy) {
-    info!("喷吐攻击: {:?}, 伤害: {}", enemy.enemy_type, enemy.stats.damage);
-    // 实际实现需要：
-    // 1. 创建喷吐物实体
-    // 2. 设置喷吐物运动轨迹
-    // 3. 碰撞时对目标造成伤害
-    // 4. 播放喷吐效果
-}
-
-/// 执行召唤攻击
-fn perform_summon_attack(enemy: &Enemy) {
-    info!("召唤攻击: {:?}", enemy.enemy_type);
-    // 实际实现需要：
-    // 1. 确定召唤的敌人类型
-    // 2. 在附近位置生成敌人
-    // 3. 播放召唤效果
-    // 4. 更新召唤计数
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::components::building::BuildingType;
+
+    #[test]
+    fn targets_buildings_without_a_player() {
+        let mut world = World::new();
+        world.insert_resource(Time::<()>::default());
+        world.insert_resource(MapGrid::new(8, 8, 0));
+        let building = world.spawn((Building::new(BuildingType::Storage), Transform::from_xyz(TILE_SIZE * 0.5, 0.0, 0.0))).id();
+        let enemy = world.spawn((
+            Enemy { behavior: AIBehavior::Aggressive, ..Enemy::new(EnemyType::CombatBot, 1) },
+            EnemyStatus::default(),
+            Transform::default(),
+            EnemyPosition { tile_x: 0, tile_y: 0 },
+        )).id();
+
+        world.run_system_once(update_enemy_ai).unwrap();
+
+        let enemy = world.get::<Enemy>(enemy).unwrap();
+        assert_eq!(enemy.target, Some(building));
+        assert_eq!(enemy.ai_state, AIState::Attack);
+    }
 }
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
+use crate::components::combat::{DamageEvent, DamageType};
 use crate::components::enemy::{Enemy, EnemyStatus, AttackType};
 use crate::components::player::Player;
+use crate::resources::world::TILE_SIZE;
+use crate::systems::headless::HeadlessConfig;
 
 /// 敌人攻击系统插件
 pub struct EnemyAttackPlugin;
@@ -42,10 +45,15 @@ impl AttackEffect {
 fn update_enemy_attacks(
     time: Res<Time>,
     mut commands: Commands,
-    mut enemy_query: Query<(&mut Enemy, &mut EnemyStatus, &Transform), Without<Player>>,
+    mut enemy_query: Query<(Entity, &mut Enemy, &mut EnemyStatus, &Transform), Without<Player>>,
     player_query: Query<(Entity, &Transform), (With<Player>, Without<Enemy>)>,
+    target_query: Query<&Transform, Without<Enemy>>,
+    headless: Option<Res<HeadlessConfig>>,
 ) {
-    for (mut enemy, mut status, transform) in enemy_query.iter_mut() {
+    // 无头模式下不生成攻击效果精灵
+    let rendering = headless.is_none();
+
+    for (enemy_entity, mut enemy, mut status, transform) in enemy_query.iter_mut() {
         if enemy.ai_state != crate::components::enemy::AIState::Attack {
             continue;
         }
@@ -64,29 +72,38 @@ fn update_enemy_attacks(
             continue;
         }
 
-        // 执行攻击
-        let Ok((player_entity, player_transform)) = player_query.single() else { continue; };
-        let distance = transform.translation.distance(player_transform.translation);
+        // 攻击 AI 选定的目标（玩家、防御机器人或建筑），没有目标时攻击玩家
+        let target = enemy
+            .target
+            .and_then(|target| target_query.get(target).ok().map(|target_transform| (target, target_transform)))
+            .or_else(|| player_query.single().ok());
+        let Some((target_entity, target_transform)) = target else { continue; };
+        // 攻击范围以地块为单位
+        let distance = transform.translation.distance(target_transform.translation) / TILE_SIZE;
 
         if distance <= enemy.stats.attack_range {
             // 根据攻击类型执行攻击
-            match enemy.enemy_type.attack_type() {
+            match enemy.attack_type {
                 AttackType::Melee => {
                     perform_melee_attack(
                         &mut commands,
+                        enemy_entity,
                         &enemy,
                         transform,
-                        player_entity,
-                        player_transform,
+                        target_entity,
+                        target_transform,
+                        rendering,
                     );
                 }
                 AttackType::Laser => {
                     perform_laser_attack(
                         &mut commands,
+                        enemy_entity,
                         &enemy,
                         transform,
-                        player_entity,
-                        player_transform,
+                        target_entity,
+                        target_transform,
+                        rendering,
                     );
                 }
                 AttackType::Spit => {
@@ -94,11 +111,12 @@ fn update_enemy_attacks(
                         &mut commands,
                         &enemy,
                         transform,
-                        player_transform,
+                        target_transform,
+                        rendering,
                     );
                 }
                 AttackType::Summon => {
-                    perform_summon_attack(&mut commands, &enemy, transform);
+                    perform_summon_attack(&mut commands, &enemy, transform, rendering);
                 }
                 AttackType::None => {
                     // 无攻击
@@ -116,23 +134,20 @@ fn update_enemy_attacks(
 /// 执行近战攻击
 fn perform_melee_attack(
     commands: &mut Commands,
+    enemy_entity: Entity,
     enemy: &Enemy,
     enemy_transform: &Transform,
-    _target_entity: Entity,
+    target_entity: Entity,
     target_transform: &Transform,
+    rendering: bool,
 ) {
     info!("近战攻击: {:?}, 伤害: {}", enemy.enemy_type, enemy.stats.damage);
 
     // 创建攻击效果
     let direction = (target_transform.translation - enemy_transform.translation).normalize();
-    let attack_range = enemy.stats.attack_range;
+    let attack_range = enemy.stats.attack_range * TILE_SIZE;
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(1.0, 0.0, 0.0),
-            custom_size: Some(Vec2::new(attack_range, attack_range * 0.5)),
-            ..default()
-        },
+    let mut effect = commands.spawn((
         Transform {
             translation: enemy_transform.translation + direction * (attack_range / 2.0),
             rotation: Quat::from_rotation_z(direction.y.atan2(direction.x)),
@@ -142,30 +157,41 @@ fn perform_melee_attack(
         AttackEffect::new(enemy.stats.damage, 0.2, AttackType::Melee),
     ));
 
+    if rendering {
+        effect.insert(Sprite {
+            color: Color::srgb(1.0, 0.0, 0.0),
+            custom_size: Some(Vec2::new(attack_range, attack_range * 0.5)),
+            ..default()
+        });
+    }
+
     // 对目标造成伤害
-    // 实际实现需要通过事件系统或直接修改玩家生命值
+    commands.trigger(DamageEvent {
+        source: enemy_entity,
+        target: target_entity,
+        damage: enemy.stats.damage,
+        damage_type: DamageType::Physical,
+        is_critical: false,
+    });
 }
 
 /// 执行激光攻击
 fn perform_laser_attack(
     commands: &mut Commands,
+    enemy_entity: Entity,
     enemy: &Enemy,
     enemy_transform: &Transform,
-    _target_entity: Entity,
+    target_entity: Entity,
     target_transform: &Transform,
+    rendering: bool,
 ) {
     info!("激光攻击: {:?}, 伤害: {}", enemy.enemy_type, enemy.stats.damage);
 
     // 计算激光方向
     let direction = (target_transform.translation - enemy_transform.translation).normalize();
-    let laser_length = enemy.stats.attack_range;
+    let laser_length = enemy.stats.attack_range * TILE_SIZE;
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.0, 1.0, 1.0),
-            custom_size: Some(Vec2::new(laser_length, 4.0)),
-            ..default()
-        },
+    let mut effect = commands.spawn((
         Transform {
             translation: enemy_transform.translation + direction * (laser_length / 2.0),
             rotation: Quat::from_rotation_z(direction.y.atan2(direction.x)),
@@ -175,8 +201,22 @@ fn perform_laser_attack(
         AttackEffect::new(enemy.stats.damage, 0.3, AttackType::Laser),
     ));
 
-    // 激光攻击对路径上的所有目标造成伤害
-    // 实际实现需要射线检测
+    if rendering {
+        effect.insert(Sprite {
+            color: Color::srgb(0.0, 1.0, 1.0),
+            custom_size: Some(Vec2::new(laser_length, 4.0)),
+            ..default()
+        });
+    }
+
+    // 激光直接命中目标；路径上的其他目标需要射线检测，暂不处理
+    commands.trigger(DamageEvent {
+        source: enemy_entity,
+        target: target_entity,
+        damage: enemy.stats.damage,
+        damage_type: DamageType::Laser,
+        is_critical: false,
+    });
 }
 
 /// 执行喷吐攻击
@@ -185,6 +225,7 @@ fn perform_spit_attack(
     enemy: &Enemy,
     enemy_transform: &Transform,
     target_transform: &Transform,
+    rendering: bool,
 ) {
     info!("喷吐攻击: {:?}, 伤害: {}", enemy.enemy_type, enemy.stats.damage);
 
@@ -192,12 +233,7 @@ fn perform_spit_attack(
     let direction = (target_transform.translation - enemy_transform.translation).normalize();
     let speed = 200.0;
 
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.5, 1.0, 0.5),
-            custom_size: Some(Vec2::new(8.0, 8.0)),
-            ..default()
-        },
+    let mut projectile = commands.spawn((
         *enemy_transform,
         GlobalTransform::default(),
         AttackEffect::new(enemy.stats.damage, 2.0, AttackType::Spit),
@@ -208,6 +244,14 @@ fn perform_spit_attack(
         },
     ));
 
+    if rendering {
+        projectile.insert(Sprite {
+            color: Color::srgb(0.5, 1.0, 0.5),
+            custom_size: Some(Vec2::new(8.0, 8.0)),
+            ..default()
+        });
+    }
+
     // 喷吐物会移动并在碰撞时造成伤害
 }
 
@@ -224,20 +268,24 @@ fn perform_summon_attack(
     commands: &mut Commands,
     enemy: &Enemy,
     enemy_transform: &Transform,
+    rendering: bool,
 ) {
     info!("召唤攻击: {:?}", enemy.enemy_type);
 
     // 创建召唤效果
-    commands.spawn((
-            Sprite {
-                color: Color::srgb(1.0, 0.5, 1.0),
-                custom_size: Some(Vec2::new(64.0, 64.0)),
-                ..default()
-            },
-            Transform::from_translation(enemy_transform.translation),
-            GlobalTransform::default(),
-            AttackEffect::new(0.0, 1.0, AttackType::Summon),
-        ));
+    let mut effect = commands.spawn((
+        Transform::from_translation(enemy_transform.translation),
+        GlobalTransform::default(),
+        AttackEffect::new(0.0, 1.0, AttackType::Summon),
+    ));
+
+    if rendering {
+        effect.insert(Sprite {
+            color: Color::srgb(1.0, 0.5, 1.0),
+            custom_size: Some(Vec2::new(64.0, 64.0)),
+            ..default()
+        });
+    }
 
     // 实际召唤逻辑在生成系统中实现
     // 这里只创建视觉效果
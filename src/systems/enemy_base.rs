@@ -1,8 +1,11 @@
 use bevy::prelude::*;
 use bevy::sprite_render::prelude::*;
 use bevy::mesh::Mesh;
-use crate::components::enemy::{EnemyBase, EnemyType, EnemyPosition, Enemy};
+use crate::components::enemy::{EnemyBase, EnemyType, EnemyPosition, EnemyStatus, Enemy};
+use crate::components::enemy_catalog::EnemyCatalog;
+use crate::components::pathfinding::PathCache;
 use crate::systems::enemy_spawn::EnemyRenderAssets;
+use crate::resources::world::{MapGrid, TILE_SIZE};
 
 pub struct EnemyBasePlugin;
 
@@ -16,10 +19,15 @@ fn update_base_spawning(
     time: Res<Time>,
     mut base_query: Query<(Entity, &mut EnemyBase, &Transform), Without<Enemy>>,
     mut commands: Commands,
-    assets: Res<EnemyRenderAssets>,
+    map_grid: Option<Res<MapGrid>>,
+    assets: Option<Res<EnemyRenderAssets>>,
+    catalog: Res<EnemyCatalog>,
 ) {
+    let Some(map_grid) = map_grid else { return; };
+
     for (_entity, mut base, transform) in base_query.iter_mut() {
-        if !base.active {
+        // 达到当日生成上限后停止出兵
+        if !base.can_spawn() {
             continue;
         }
 
@@ -28,14 +36,18 @@ fn update_base_spawning(
         if base.spawn_timer >= base.spawn_interval {
             base.spawn_timer = 0.0;
 
-            match base.base_type {
+            let spawned = match base.base_type {
                 EnemyType::RobotFortress => {
-                    spawn_from_fortress(&mut commands, &base, transform, &assets);
+                    spawn_from_fortress(&mut commands, &map_grid, &catalog, &base, transform, assets.as_deref())
                 }
                 EnemyType::AIMotherBase => {
-                    spawn_from_mother_base(&mut commands, &base, transform, &assets);
+                    spawn_from_mother_base(&mut commands, &map_grid, &catalog, &base, transform, assets.as_deref())
                 }
-                _ => {}
+                _ => false,
+            };
+
+            if spawned {
+                base.current_spawn_count += 1;
             }
         }
     }
@@ -43,88 +55,100 @@ fn update_base_spawning(
 
 fn spawn_from_fortress(
     commands: &mut Commands,
+    map_grid: &MapGrid,
+    catalog: &EnemyCatalog,
     base: &EnemyBase,
     transform: &Transform,
-    assets: &EnemyRenderAssets,
-) {
+    assets: Option<&EnemyRenderAssets>,
+) -> bool {
     use rand::Rng;
     let mut rng = rand::thread_rng();
 
-    let spawn_types = base.get_spawn_types();
-    let enemy_type = spawn_types[rng.gen_range(0..spawn_types.len())];
+    let Some(definition) = catalog.random_of_types(&base.get_spawn_types(), &mut rng) else { return false; };
 
-    let tile_size = 32.0;
     let spawn_range = base.spawn_range;
     let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
-    let distance = rng.gen_range(2.0..spawn_range) * tile_size;
-
-    let offset_x = angle.cos() * distance;
-    let offset_y = angle.sin() * distance;
-
-    let tile_x = ((transform.translation.x + offset_x) / tile_size).round() as u32;
-    let tile_y = ((transform.translation.y + offset_y) / tile_size).round() as u32;
-
-    commands.spawn((
-        bevy::mesh::Mesh2d(assets.enemy_mesh.clone()),
-        MeshMaterial2d(assets.enemy_material.clone()),
-        Transform::from_xyz(
-            transform.translation.x + offset_x,
-            transform.translation.y + offset_y,
-            1.0
-        ),
+    let distance = rng.gen_range(2.0..spawn_range) * TILE_SIZE;
+    let offset = Vec2::new(angle.cos(), angle.sin()) * distance;
+
+    // 超出地图的生成点直接放弃
+    let Some(grid_pos) = map_grid.world_to_grid(transform.translation.truncate() + offset) else { return false; };
+    let (tile_x, tile_y) = (grid_pos.x, grid_pos.y);
+    let world_pos = map_grid.grid_to_world(grid_pos);
+
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 1.0),
         GlobalTransform::default(),
-        Enemy::new(enemy_type, 1),
+        Enemy::from_definition(definition, 1),
         EnemyPosition { tile_x, tile_y },
+        EnemyStatus::default(),
+        PathCache::default(),
     ));
 
-    info!("从机器人堡垒生成: {:?} at ({}, {})", enemy_type, tile_x, tile_y);
+    if let Some(assets) = assets {
+        entity.insert((
+            bevy::mesh::Mesh2d(assets.enemy_mesh.clone()),
+            MeshMaterial2d(assets.enemy_material.clone()),
+        ));
+    }
+
+    info!("从机器人堡垒生成: {} at ({}, {})", definition.id, tile_x, tile_y);
+
+    true
 }
 
 fn spawn_from_mother_base(
     commands: &mut Commands,
+    map_grid: &MapGrid,
+    catalog: &EnemyCatalog,
     base: &EnemyBase,
     transform: &Transform,
-    assets: &EnemyRenderAssets,
-) {
+    assets: Option<&EnemyRenderAssets>,
+) -> bool {
     use rand::Rng;
     let mut rng = rand::thread_rng();
 
-    let spawn_types = base.get_spawn_types();
-    let enemy_type = spawn_types[rng.gen_range(0..spawn_types.len())];
+    let Some(definition) = catalog.random_of_types(&base.get_spawn_types(), &mut rng) else { return false; };
 
-    let tile_size = 32.0;
     let spawn_range = base.spawn_range;
     let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
-    let distance = rng.gen_range(3.0..spawn_range) * tile_size;
-
-    let offset_x = angle.cos() * distance;
-    let offset_y = angle.sin() * distance;
-
-    let tile_x = ((transform.translation.x + offset_x) / tile_size).round() as u32;
-    let tile_y = ((transform.translation.y + offset_y) / tile_size).round() as u32;
-
-    commands.spawn((
-        bevy::mesh::Mesh2d(assets.enemy_mesh.clone()),
-        MeshMaterial2d(assets.enemy_material.clone()),
-        Transform::from_xyz(
-            transform.translation.x + offset_x,
-            transform.translation.y + offset_y,
-            1.0
-        ),
+    let distance = rng.gen_range(3.0..spawn_range) * TILE_SIZE;
+    let offset = Vec2::new(angle.cos(), angle.sin()) * distance;
+
+    // 超出地图的生成点直接放弃
+    let Some(grid_pos) = map_grid.world_to_grid(transform.translation.truncate() + offset) else { return false; };
+    let (tile_x, tile_y) = (grid_pos.x, grid_pos.y);
+    let world_pos = map_grid.grid_to_world(grid_pos);
+
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 1.0),
         GlobalTransform::default(),
-        Enemy::new(enemy_type, 1),
+        Enemy::from_definition(definition, 1),
         EnemyPosition { tile_x, tile_y },
+        EnemyStatus::default(),
+        PathCache::default(),
     ));
 
-    info!("从AI母巢生成: {:?} at ({}, {})", enemy_type, tile_x, tile_y);
+    if let Some(assets) = assets {
+        entity.insert((
+            bevy::mesh::Mesh2d(assets.enemy_mesh.clone()),
+            MeshMaterial2d(assets.enemy_material.clone()),
+        ));
+    }
+
+    info!("从AI母巢生成: {} at ({}, {})", definition.id, tile_x, tile_y);
+
+    true
 }
 
 pub fn initialize_enemy_bases(
     commands: &mut Commands,
-    map_width: u32,
-    map_height: u32,
-    assets: &EnemyRenderAssets,
+    map_grid: &MapGrid,
+    assets: Option<&EnemyRenderAssets>,
 ) {
+    let map_width = map_grid.width();
+    let map_height = map_grid.height();
+
     use rand::Rng;
     let mut rng = rand::thread_rng();
 
@@ -139,17 +163,21 @@ pub fn initialize_enemy_bases(
             _ => (0, 0),
         };
 
-        let pos_x = tile_x as f32 * 32.0;
-        let pos_y = tile_y as f32 * 32.0;
+        let world_pos = map_grid.grid_to_world(UVec2::new(tile_x, tile_y));
 
-        commands.spawn((
-            bevy::mesh::Mesh2d(assets.base_mesh.clone()),
-            MeshMaterial2d(assets.fortress_material.clone()),
-            Transform::from_xyz(pos_x, pos_y, 0.5),
+        let mut entity = commands.spawn((
+            Transform::from_xyz(world_pos.x, world_pos.y, 0.5),
             GlobalTransform::default(),
             EnemyBase::new(EnemyType::RobotFortress),
             EnemyPosition { tile_x, tile_y },
         ));
+
+        if let Some(assets) = assets {
+            entity.insert((
+                bevy::mesh::Mesh2d(assets.base_mesh.clone()),
+                MeshMaterial2d(assets.fortress_material.clone()),
+            ));
+        }
     }
 
     let center_x = map_width / 2;
@@ -166,15 +194,21 @@ pub fn initialize_enemy_bases(
         center_y - offset
     };
 
-    let pos_x = mother_base_x.min(map_width - 1) as f32 * 32.0;
-    let pos_y = mother_base_y.min(map_height - 1) as f32 * 32.0;
+    let mother_base_x = mother_base_x.min(map_width - 1);
+    let mother_base_y = mother_base_y.min(map_height - 1);
+    let world_pos = map_grid.grid_to_world(UVec2::new(mother_base_x, mother_base_y));
 
-    commands.spawn((
-        bevy::mesh::Mesh2d(assets.base_mesh.clone()),
-        MeshMaterial2d(assets.mother_base_material.clone()),
-        Transform::from_xyz(pos_x, pos_y, 0.5),
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 0.5),
         GlobalTransform::default(),
         EnemyBase::new(EnemyType::AIMotherBase),
-        EnemyPosition { tile_x: mother_base_x.min(map_width - 1), tile_y: mother_base_y.min(map_height - 1) },
+        EnemyPosition { tile_x: mother_base_x, tile_y: mother_base_y },
     ));
+
+    if let Some(assets) = assets {
+        entity.insert((
+            bevy::mesh::Mesh2d(assets.base_mesh.clone()),
+            MeshMaterial2d(assets.mother_base_material.clone()),
+        ));
+    }
 }
\ No newline at end of file
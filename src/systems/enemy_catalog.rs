@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+use crate::components::enemy::Enemy;
+use crate::components::enemy_catalog::{EnemyCatalog, EnemyCatalogAsset};
+use crate::systems::json_catalog::JsonCatalogPlugin;
+
+/// 敌人原型数据插件：加载 `*.enemies.json`，热重载后把新数值套用到场上敌人
+pub struct EnemyCatalogPlugin;
+
+impl Plugin for EnemyCatalogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonCatalogPlugin::<EnemyCatalogAsset>::default())
+            .add_systems(Update, refresh_enemies.run_if(resource_changed::<EnemyCatalog>));
+    }
+}
+
+fn refresh_enemies(catalog: Res<EnemyCatalog>, mut enemy_query: Query<&mut Enemy>) {
+    for mut enemy in enemy_query.iter_mut() {
+        let definition = catalog
+            .get(&enemy.archetype)
+            .cloned()
+            .unwrap_or_else(|| catalog.for_type(enemy.enemy_type));
+        enemy.apply_definition(&definition);
+    }
+}
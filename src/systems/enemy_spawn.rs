@@ -4,10 +4,14 @@ use bevy::mesh::Mesh2d;
 use bevy::mesh::Mesh;
 use rand::Rng;
 use crate::components::enemy::{
-    Enemy, EnemyType, EnemyBase, EnemyPosition, EnemyStatus, EnemySpawnConfig
+    Enemy, EnemyType, EnemyBase, EnemyPosition, EnemyStatus,
+    EnemyWaveConfig, EnemyWaveState, WaveMember, WAVE_TIMEOUT
 };
-use crate::components::player::Player;
-use crate::resources::world::WorldMap;
+use crate::components::enemy_catalog::{EnemyCatalog, EnemyDefinition};
+use crate::components::pathfinding::PathCache;
+use crate::resources::world::{MapGrid, TILE_SIZE};
+use crate::systems::time::{GameTime, DayPhase};
+use crate::systems::game_over::{reset_resource, restarting};
 
 #[derive(Resource)]
 pub struct EnemyRenderAssets {
@@ -22,180 +26,341 @@ pub struct EnemySpawnPlugin;
 
 impl Plugin for EnemySpawnPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<EnemySpawnConfig>();
+        app.init_resource::<EnemyWaveConfig>()
+            .init_resource::<EnemyWaveState>()
+            .add_message::<WaveStartedEvent>()
+            .add_message::<WaveClearedEvent>()
+            .add_systems(OnEnter(crate::states::GameState::InGame), (
+                reset_resource::<EnemyWaveConfig>,
+                reset_resource::<EnemyWaveState>,
+            ).run_if(restarting));
 
         app.add_systems(Update, (
-            update_enemy_spawns,
-            update_base_spawns,
-        ).chain());
+            update_daily_spawns,
+            update_wave_director,
+            check_wave_cleared,
+        ).chain().run_if(in_state(crate::states::GameState::InGame)));
     }
 }
 
-fn update_enemy_spawns(
-    time: Res<Time>,
+/// 敌人波次开始事件
+#[derive(Event, Message, Debug, Clone)]
+pub struct WaveStartedEvent {
+    pub wave: u32,
+    pub day: u32,
+    pub enemy_count: u32,
+    pub level: u32,
+    pub position: Vec2,     // 出兵位置（首个敌人的出生点，世界坐标）
+}
+
+/// 敌人波次清除事件
+#[derive(Event, Message, Debug, Clone)]
+pub struct WaveClearedEvent {
+    pub wave: u32,
+    pub day: u32,
+}
+
+/// 每日结算：重置基地生成计数，并按扫描强度决定是否建立新的机器人堡垒
+fn update_daily_spawns(
     mut commands: Commands,
-    mut enemy_query: Query<(Entity, &mut EnemyBase, &Transform), Without<Player>>,
-    world_map: Option<Res<WorldMap>>,
-    assets: Res<EnemyRenderAssets>,
+    game_time: Res<GameTime>,
+    map_grid: Option<Res<MapGrid>>,
+    assets: Option<Res<EnemyRenderAssets>>,
+    mut wave_state: ResMut<EnemyWaveState>,
+    mut base_query: Query<&mut EnemyBase>,
 ) {
-    let world_map = match world_map {
-        Some(wm) => wm,
-        None => return,
-    };
+    let Some(map_grid) = map_grid else { return; };
+
+    if wave_state.last_day == game_time.day {
+        return;
+    }
+    wave_state.last_day = game_time.day;
+    // 进行中的波次继续计时，超时后再调度
+    if wave_state.active_wave.is_none() {
+        wave_state.wave_timer = 0.0;
+    }
+
+    for mut base in base_query.iter_mut() {
+        base.reset_spawn_count();
+    }
 
     let mut rng = rand::thread_rng();
+    let scan_intensity = game_time.moon_phase.scan_intensity();
 
-    for (entity, mut base, transform) in enemy_query.iter_mut() {
-        if !base.active {
-            continue;
+    if rng.gen_bool(scan_intensity.clamp(0.0, 1.0) as f64) {
+        if let Some(grid_pos) = random_edge_tile(&map_grid, &mut rng) {
+            spawn_base(
+                &mut commands,
+                &map_grid,
+                EnemyType::RobotFortress,
+                grid_pos.x,
+                grid_pos.y,
+                assets.as_deref().map(|a| (&a.base_mesh, &a.fortress_material)),
+            );
         }
+    }
+}
+
+/// 波次调度：黄昏和夜晚按间隔发动波次，规模与等级随日期和月相扫描强度提升
+fn update_wave_director(
+    time: Res<Time>,
+    mut commands: Commands,
+    game_time: Res<GameTime>,
+    map_grid: Option<Res<MapGrid>>,
+    assets: Option<Res<EnemyRenderAssets>>,
+    catalog: Res<EnemyCatalog>,
+    wave_config: Res<EnemyWaveConfig>,
+    mut wave_state: ResMut<EnemyWaveState>,
+    mut base_query: Query<(&mut EnemyBase, &Transform)>,
+) {
+    let Some(map_grid) = map_grid else { return; };
+
+    if !matches!(game_time.current_phase, DayPhase::Dusk | DayPhase::Night) {
+        return;
+    }
+
+    if wave_state.active_wave.is_some() {
+        return;
+    }
 
-        base.spawn_timer += time.delta().as_secs_f32();
-
-        if base.spawn_timer >= base.spawn_interval {
-            base.spawn_timer = 0.0;
-
-            let spawn_pos = if base.spawn_range > 0.0 {
-                let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
-                let distance = rng.gen_range(1.0..base.spawn_range);
-
-                let offset_x = angle.cos() * distance * 32.0;
-                let offset_y = angle.sin() * distance * 32.0;
-
-                let tile_x = ((transform.translation.x + offset_x) / 32.0).round() as i32;
-                let tile_y = ((transform.translation.y + offset_y) / 32.0).round() as i32;
-
-                if tile_x >= 0 && tile_x < world_map.width as i32 &&
-                   tile_y >= 0 && tile_y < world_map.height as i32 {
-                    Some((tile_x as u32, tile_y as u32))
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-
-            if let Some((tile_x, tile_y)) = spawn_pos {
-                spawn_enemy(
-                    &mut commands,
-                    EnemyType::CombatBot,
-                    tile_x,
-                    tile_y,
-                    Some(entity),
-                    &assets.enemy_mesh,
-                    &assets.enemy_material,
-                );
+    // 满月时 AI 系统过热停机，不发动波次
+    let scan_intensity = game_time.moon_phase.scan_intensity();
+    if scan_intensity <= 0.0 {
+        return;
+    }
+
+    wave_state.wave_timer -= time.delta_secs();
+    if wave_state.wave_timer > 0.0 {
+        return;
+    }
+    wave_state.wave_timer = wave_config.wave_interval;
+
+    let wave = wave_config.current_wave;
+    let enemy_count = wave_config.enemy_count(scan_intensity);
+    let level = wave_config.enemy_level(scan_intensity, game_time.day);
+
+    let mut rng = rand::thread_rng();
+    let mut bases: Vec<_> = base_query.iter_mut().collect();
+    let mut next_base = 0;
+    let mut spawned = 0;
+    let mut first_position = None;
+
+    for _ in 0..enemy_count {
+        let Some(definition) = catalog.random_spawn(scan_intensity, &mut rng) else { break; };
+
+        // 优先从尚未达到生成上限的基地出兵，否则从地图边缘进入
+        let mut spawn_pos = None;
+        for offset in 0..bases.len() {
+            let index = (next_base + offset) % bases.len();
+            let (base, transform) = &mut bases[index];
+            if !base.can_spawn() {
+                continue;
+            }
+
+            if let Some(grid_pos) = random_tile_near(&map_grid, transform.translation.truncate(), base.spawn_range, &mut rng) {
+                base.current_spawn_count += 1;
+                next_base = index + 1;
+                spawn_pos = Some(grid_pos);
+                break;
             }
         }
+
+        let Some(grid_pos) = spawn_pos.or_else(|| random_edge_tile(&map_grid, &mut rng)) else { continue; };
+
+        let entity = spawn_enemy(
+            &mut commands,
+            &map_grid,
+            definition,
+            level,
+            grid_pos.x,
+            grid_pos.y,
+            assets.as_deref().map(|a| (&a.enemy_mesh, &a.enemy_material)),
+        );
+        commands.entity(entity).insert(WaveMember { wave });
+        first_position.get_or_insert(map_grid.grid_to_world(grid_pos));
+        spawned += 1;
     }
+
+    let Some(position) = first_position else { return; };
+
+    wave_state.active_wave = Some(wave);
+    wave_state.wave_timer = WAVE_TIMEOUT;
+    info!("第 {} 波敌人来袭: {} 个, 等级 {}", wave, spawned, level);
+
+    commands.trigger(WaveStartedEvent {
+        wave,
+        day: game_time.day,
+        enemy_count: spawned,
+        level,
+        position,
+    });
 }
 
-fn update_base_spawns(
+/// 当前波次的敌人全部死亡后结束波次
+///
+/// 有敌人卡住或够不到目标时，超过 `WAVE_TIMEOUT` 放弃等待：波次照常推进，
+/// 但不算清除，剩余的敌人留在场上。
+fn check_wave_cleared(
     time: Res<Time>,
     mut commands: Commands,
-    world_map: Option<Res<WorldMap>>,
-    assets: Res<EnemyRenderAssets>,
+    game_time: Res<GameTime>,
+    mut wave_config: ResMut<EnemyWaveConfig>,
+    mut wave_state: ResMut<EnemyWaveState>,
+    member_query: Query<(&WaveMember, &Enemy)>,
 ) {
-    let world_map = match world_map {
-        Some(wm) => wm,
-        None => return,
-    };
+    let Some(wave) = wave_state.active_wave else { return; };
 
-    let mut rng = rand::thread_rng();
+    let remaining = member_query
+        .iter()
+        .filter(|(member, enemy)| member.wave == wave && !enemy.is_dead())
+        .count();
+
+    if remaining > 0 {
+        wave_state.wave_timer -= time.delta_secs();
+        if wave_state.wave_timer > 0.0 {
+            return;
+        }
+
+        wave_state.active_wave = None;
+        wave_state.wave_timer = wave_config.wave_interval;
+        wave_config.advance();
+        info!("第 {} 波敌人超时未清除（剩余 {} 个），继续调度下一波", wave, remaining);
+        return;
+    }
+
+    wave_state.active_wave = None;
+    wave_state.wave_timer = wave_config.wave_interval;
+    wave_config.advance();
+    info!("第 {} 波敌人已被清除", wave);
+
+    commands.trigger(WaveClearedEvent {
+        wave,
+        day: game_time.day,
+    });
+}
+
+/// 在中心点附近随机选择可通行地块
+fn random_tile_near(map_grid: &MapGrid, center: Vec2, range: f32, rng: &mut impl Rng) -> Option<UVec2> {
+    for _ in 0..10 {
+        let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
+        let distance = rng.gen_range(1.0..range.max(1.5)) * TILE_SIZE;
+        let offset = Vec2::new(angle.cos(), angle.sin()) * distance;
+
+        if let Some(grid_pos) = map_grid.world_to_grid(center + offset) {
+            if is_walkable(map_grid, grid_pos) {
+                return Some(grid_pos);
+            }
+        }
+    }
+
+    None
+}
 
-    if rng.gen_bool(0.01) {
+/// 在地图边缘随机选择可通行地块
+fn random_edge_tile(map_grid: &MapGrid, rng: &mut impl Rng) -> Option<UVec2> {
+    for _ in 0..10 {
         let edge = rng.gen_range(0..4);
         let (tile_x, tile_y) = match edge {
-            0 => (rng.gen_range(0..world_map.width), 0u32),
-            1 => (rng.gen_range(0..world_map.width), world_map.height - 1),
-            2 => (0, rng.gen_range(0..world_map.height)),
-            3 => (world_map.width - 1, rng.gen_range(0..world_map.height)),
+            0 => (rng.gen_range(0..map_grid.width()), 0u32),
+            1 => (rng.gen_range(0..map_grid.width()), map_grid.height() - 1),
+            2 => (0, rng.gen_range(0..map_grid.height())),
+            3 => (map_grid.width() - 1, rng.gen_range(0..map_grid.height())),
             _ => (0, 0),
         };
 
-        spawn_base(
-            &mut commands,
-            EnemyType::RobotFortress,
-            tile_x,
-            tile_y,
-            &assets.base_mesh,
-            &assets.fortress_material,
-        );
+        let grid_pos = UVec2::new(tile_x, tile_y);
+        if is_walkable(map_grid, grid_pos) {
+            return Some(grid_pos);
+        }
     }
+
+    None
+}
+
+fn is_walkable(map_grid: &MapGrid, grid_pos: UVec2) -> bool {
+    map_grid
+        .get(grid_pos.x, grid_pos.y)
+        .is_some_and(|tile| tile.tile_type.is_walkable())
 }
 
 fn spawn_enemy(
     commands: &mut Commands,
-    enemy_type: EnemyType,
+    map_grid: &MapGrid,
+    definition: &EnemyDefinition,
+    level: u32,
     tile_x: u32,
     tile_y: u32,
-    _base_entity: Option<Entity>,
-    mesh_handle: &Handle<Mesh>,
-    material_handle: &Handle<ColorMaterial>,
-) {
-    let tile_size = 32.0;
-    let pos_x = tile_x as f32 * tile_size;
-    let pos_y = tile_y as f32 * tile_size;
+    visual: Option<(&Handle<Mesh>, &Handle<ColorMaterial>)>,
+) -> Entity {
+    let world_pos = map_grid.grid_to_world(UVec2::new(tile_x, tile_y));
 
-    let enemy = Enemy::new(enemy_type, 1);
+    let enemy = Enemy::from_definition(definition, level);
 
-    commands.spawn((
-        Mesh2d(mesh_handle.clone()),
-        MeshMaterial2d(material_handle.clone()),
-        Transform::from_xyz(pos_x, pos_y, 1.0),
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 1.0),
         GlobalTransform::default(),
         enemy,
         EnemyPosition { tile_x, tile_y },
         EnemyStatus::default(),
+        PathCache::default(),
     ));
 
-    info!("生成敌人: {:?} at ({}, {})", enemy_type, tile_x, tile_y);
+    if let Some((mesh_handle, material_handle)) = visual {
+        entity.insert((Mesh2d(mesh_handle.clone()), MeshMaterial2d(material_handle.clone())));
+    }
+
+    info!("生成敌人: {} Lv{} at ({}, {})", definition.id, level, tile_x, tile_y);
+
+    entity.id()
 }
 
 fn spawn_base(
     commands: &mut Commands,
+    map_grid: &MapGrid,
     base_type: EnemyType,
     tile_x: u32,
     tile_y: u32,
-    mesh_handle: &Handle<Mesh>,
-    material_handle: &Handle<ColorMaterial>,
-) {
-    let tile_size = 32.0;
-    let pos_x = tile_x as f32 * tile_size;
-    let pos_y = tile_y as f32 * tile_size;
-
-    commands.spawn((
-        Mesh2d(mesh_handle.clone()),
-        MeshMaterial2d(material_handle.clone()),
-        Transform::from_xyz(pos_x, pos_y, 0.5),
+    visual: Option<(&Handle<Mesh>, &Handle<ColorMaterial>)>,
+) -> Entity {
+    let world_pos = map_grid.grid_to_world(UVec2::new(tile_x, tile_y));
+
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 0.5),
         GlobalTransform::default(),
         EnemyBase::new(base_type),
         EnemyPosition { tile_x, tile_y },
     ));
 
+    if let Some((mesh_handle, material_handle)) = visual {
+        entity.insert((Mesh2d(mesh_handle.clone()), MeshMaterial2d(material_handle.clone())));
+    }
+
     info!("生成基地: {:?} at ({}, {})", base_type, tile_x, tile_y);
+
+    entity.id()
 }
 
 pub fn spawn_enemy_at(
     commands: &mut Commands,
-    enemy_type: EnemyType,
+    map_grid: &MapGrid,
+    definition: &EnemyDefinition,
+    level: u32,
     tile_x: u32,
     tile_y: u32,
-    mesh_handle: &Handle<Mesh>,
-    material_handle: &Handle<ColorMaterial>,
-) {
-    spawn_enemy(commands, enemy_type, tile_x, tile_y, None, mesh_handle, material_handle);
+    visual: Option<(&Handle<Mesh>, &Handle<ColorMaterial>)>,
+) -> Entity {
+    spawn_enemy(commands, map_grid, definition, level, tile_x, tile_y, visual)
 }
 
 pub fn spawn_base_at(
     commands: &mut Commands,
+    map_grid: &MapGrid,
     base_type: EnemyType,
     tile_x: u32,
     tile_y: u32,
-    mesh_handle: &Handle<Mesh>,
-    material_handle: &Handle<ColorMaterial>,
-) {
-    spawn_base(commands, base_type, tile_x, tile_y, mesh_handle, material_handle);
+    visual: Option<(&Handle<Mesh>, &Handle<ColorMaterial>)>,
+) -> Entity {
+    spawn_base(commands, map_grid, base_type, tile_x, tile_y, visual)
 }
 
 pub fn init_enemy_assets(
@@ -216,4 +381,44 @@ pub fn init_enemy_assets(
         fortress_material,
         mother_base_material,
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn advance(world: &mut World, secs: f32) {
+        world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(secs));
+    }
+
+    #[test]
+    fn stalled_wave_does_not_block_the_next_one() {
+        let mut world = World::new();
+        world.insert_resource(Time::<()>::default());
+        world.insert_resource(GameTime { current_phase: DayPhase::Night, ..default() });
+        world.insert_resource(MapGrid::new(16, 16, 0));
+        world.insert_resource(EnemyCatalog::default());
+        world.insert_resource(EnemyWaveConfig::default());
+        world.insert_resource(EnemyWaveState { active_wave: Some(1), wave_timer: WAVE_TIMEOUT, last_day: 1 });
+        // 卡住的敌人一直活着
+        world.spawn((Enemy::new(EnemyType::CombatBot, 1), WaveMember { wave: 1 }));
+
+        advance(&mut world, 1.0);
+        world.run_system_once(check_wave_cleared).unwrap();
+        world.run_system_once(update_wave_director).unwrap();
+        assert_eq!(world.resource::<EnemyWaveState>().active_wave, Some(1));
+
+        advance(&mut world, WAVE_TIMEOUT);
+        world.run_system_once(check_wave_cleared).unwrap();
+        assert_eq!(world.resource::<EnemyWaveState>().active_wave, None);
+        assert_eq!(world.resource::<EnemyWaveConfig>().current_wave, 2);
+
+        let interval = world.resource::<EnemyWaveConfig>().wave_interval;
+        advance(&mut world, interval);
+        world.run_system_once(update_wave_director).unwrap();
+        assert_eq!(world.resource::<EnemyWaveState>().active_wave, Some(2));
+        let members = world.query::<&WaveMember>().iter(&world).filter(|member| member.wave == 2).count();
+        assert!(members > 0);
+    }
+}
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use crate::components::equipment::{Equipment, EquipmentType, EquipmentRarity, EquipmentBar};
 use crate::components::player::Player;
-use crate::components::resource::Inventory;
+use crate::components::inventory::Inventory;
+use crate::components::item::ItemId;
+use crate::systems::headless::HeadlessConfig;
 use rand::Rng;
 
 pub struct EquipmentPlugin;
@@ -12,10 +14,20 @@ impl Plugin for EquipmentPlugin {
     }
 }
 
+/// 装备外观（按稀有度着色）
+pub fn equipment_sprite(equipment: &Equipment) -> Sprite {
+    Sprite {
+        color: equipment.rarity.color(),
+        custom_size: Some(Vec2::splat(20.0)),
+        ..default()
+    }
+}
+
 /// 生成随机装备
 pub fn spawn_random_equipment(
     mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    headless: Option<Res<HeadlessConfig>>,
     player_query: Query<&Transform, (With<Player>, Without<Equipment>)>,
 ) {
     if keyboard_input.just_pressed(KeyCode::KeyE) {
@@ -23,20 +35,25 @@ pub fn spawn_random_equipment(
         let position = player_transform.translation;
         let equipment = generate_random_equipment();
 
-        commands.spawn((
-            Sprite {
-                color: equipment.rarity.color(),
-                custom_size: Some(Vec2::splat(20.0)),
-                ..default()
-            },
-            Transform::from_xyz(position.x, position.y, 1.0),
-            equipment.clone(),
-        ));
+        spawn_equipment(&mut commands, equipment.clone(), position, headless.is_none());
 
         info!("生成了装备: {} ({})", equipment.equipment_type.name(), format_rarity(equipment.rarity));
     }
 }
 
+/// 在指定位置生成装备实体（`rendering` 为 false 时不带外观）
+pub fn spawn_equipment(commands: &mut Commands, equipment: Equipment, position: Vec3, rendering: bool) -> Entity {
+    let sprite = rendering.then(|| equipment_sprite(&equipment));
+    let mut entity = commands.spawn((
+        Transform::from_xyz(position.x, position.y, 1.0),
+        equipment,
+    ));
+    if let Some(sprite) = sprite {
+        entity.insert(sprite);
+    }
+    entity.id()
+}
+
 /// 生成随机装备
 fn generate_random_equipment() -> Equipment {
     let mut rng = rand::thread_rng();
@@ -132,12 +149,12 @@ pub fn upgrade_equipment(
     let upgrade_cost = 50;
 
     let Ok(mut inventory) = player_inventory.single_mut() else { return; };
-    if inventory.energy >= upgrade_cost {
+    if inventory.count(ItemId::ENERGY) >= upgrade_cost {
         // 升级第一个找到的装备
         for (_entity, mut equipment) in equipment_query.iter_mut() {
             if equipment.level < 10 {
                 equipment.upgrade();
-                inventory.energy -= upgrade_cost;
+                inventory.remove(ItemId::ENERGY, upgrade_cost);
                 info!("升级了装备: {} (+{} 级)", equipment.equipment_type.name(), equipment.level);
                 break;
             }
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use crate::components::experience::{ExperienceGainEvent, LevelCurve, LevelUpEvent, PlayerExperience};
+use crate::components::player::{Player, PlayerVitals, ENERGY_PER_LEVEL, HEALTH_PER_LEVEL};
+use crate::systems::achievement_events::send_reach_level_event;
+
+/// 经验与升级插件
+pub struct ExperiencePlugin;
+
+impl Plugin for ExperiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelCurve>()
+            .add_message::<ExperienceGainEvent>()
+            .add_message::<LevelUpEvent>()
+            .add_observer(apply_experience);
+    }
+}
+
+/// 结算经验并处理升级
+fn apply_experience(
+    event: On<ExperienceGainEvent>,
+    mut commands: Commands,
+    curve: Res<LevelCurve>,
+    mut player_query: Query<(&mut Player, &mut PlayerExperience, &mut PlayerVitals)>,
+) {
+    let gain_event = event.event();
+    if gain_event.amount == 0 {
+        return;
+    }
+
+    let Ok((mut player, mut experience, mut vitals)) = player_query.single_mut() else { return; };
+
+    let previous_level = player.level;
+    let levels_gained = experience.gain(gain_event.amount, &mut player.level, &curve);
+    debug!("获得经验: {} ({:?})", gain_event.amount, gain_event.source);
+
+    if levels_gained == 0 {
+        return;
+    }
+
+    // 上限由 apply_player_stats 按等级重算，这里先抬高再回满，避免回满后被截断
+    let gained = levels_gained as f32;
+    let max_health = vitals.max_health + HEALTH_PER_LEVEL * gained;
+    let max_energy = vitals.max_energy + ENERGY_PER_LEVEL * gained;
+    vitals.set_limits(max_health, max_energy);
+    vitals.restore();
+
+    for level in previous_level + 1..=player.level {
+        commands.trigger(LevelUpEvent { level });
+        info!("玩家升级到 {} 级", level);
+    }
+    send_reach_level_event(commands.reborrow(), player.level);
+}
@@ -0,0 +1,380 @@
+use bevy::prelude::*;
+use std::collections::{BinaryHeap, HashSet};
+use crate::components::building::{Building, BuildingPosition};
+use crate::components::pathfinding::{FlowField, FlowFields, NavObstacles};
+use crate::components::player::Player;
+use crate::resources::world::MapGrid;
+use crate::systems::game_over::{reset_resource, restarting};
+use crate::systems::pathfinding::{OpenNode, move_towards, sync_nav_obstacles};
+
+/// 流场导航插件
+pub struct FlowFieldPlugin;
+
+impl Plugin for FlowFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlowFields>()
+            .add_systems(PreUpdate, update_flow_fields
+                .after(sync_nav_obstacles)
+                .run_if(in_state(crate::states::GameState::InGame)))
+            .add_systems(OnEnter(crate::states::GameState::InGame), reset_resource::<FlowFields>.run_if(restarting));
+    }
+}
+
+/// 八方向邻居（对角移动不允许穿过障碍拐角）
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// 比较积分场代价时的误差
+const COST_EPSILON: f32 = 1e-4;
+
+/// 维护玩家与关键建筑各自的流场
+///
+/// 目标换了地块或地形变化时重建该流场，防御墙、建筑增减时按障碍差异增量修补。
+fn update_flow_fields(
+    map_grid: Option<Res<MapGrid>>,
+    obstacles: Res<NavObstacles>,
+    mut flow_fields: ResMut<FlowFields>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    building_query: Query<(Entity, &Building, &BuildingPosition)>,
+) {
+    let Some(map_grid) = map_grid else { return; };
+
+    let mut targets: Vec<(Entity, UVec2)> = player_query
+        .iter()
+        .filter_map(|(entity, transform)| {
+            map_grid.world_to_grid(transform.translation.truncate()).map(|tile| (entity, tile))
+        })
+        .collect();
+    targets.extend(
+        building_query
+            .iter()
+            .filter(|(_, building, _)| building.building_type.is_key_target())
+            .map(|(entity, _, position)| (entity, UVec2::new(position.tile_x, position.tile_y))),
+    );
+
+    // 目标已不存在时丢弃流场
+    flow_fields.fields.retain(|entity, _| targets.iter().any(|(target, _)| target == entity));
+
+    for (entity, goal) in targets {
+        match flow_fields.fields.get_mut(&entity) {
+            Some(field) if field.goal == goal && field.size == map_grid.size && !map_grid.is_changed() => {
+                if obstacles.is_changed() && field.blocked != obstacles.blocked {
+                    repair_flow_field(&map_grid, &obstacles, field);
+                }
+            }
+            _ => {
+                flow_fields.fields.insert(entity, build_flow_field(&map_grid, &obstacles, goal));
+            }
+        }
+    }
+}
+
+/// 地块是否可供流场通行
+fn is_open(map_grid: &MapGrid, obstacles: &NavObstacles, tile: IVec2) -> bool {
+    map_grid.contains(tile)
+        && map_grid
+            .get(tile.x as u32, tile.y as u32)
+            .is_some_and(|data| data.tile_type.is_walkable())
+        && !obstacles.is_blocked(tile.as_uvec2())
+}
+
+/// 对角方向需要两侧都可通行
+fn corners_open(map_grid: &MapGrid, obstacles: &NavObstacles, tile: IVec2, offset: IVec2) -> bool {
+    offset.x == 0
+        || offset.y == 0
+        || (is_open(map_grid, obstacles, tile + IVec2::new(offset.x, 0))
+            && is_open(map_grid, obstacles, tile + IVec2::new(0, offset.y)))
+}
+
+/// 沿 `offset` 进入 `tile` 的代价（按进入地块的地形计）
+fn step_cost(map_grid: &MapGrid, tile: UVec2, offset: IVec2) -> f32 {
+    map_grid
+        .get(tile.x, tile.y)
+        .map_or(f32::INFINITY, |data| data.tile_type.movement_cost() * offset.as_vec2().length())
+}
+
+fn tile_index(size: UVec2, tile: UVec2) -> usize {
+    (tile.y * size.x + tile.x) as usize
+}
+
+/// 地图内的相邻地块及偏移
+fn neighbours(size: UVec2, tile: UVec2) -> impl Iterator<Item = (UVec2, IVec2)> {
+    NEIGHBOURS.into_iter().filter_map(move |offset| {
+        let next = tile.as_ivec2() + offset;
+        (next.x >= 0 && next.y >= 0 && (next.x as u32) < size.x && (next.y as u32) < size.y)
+            .then(|| (next.as_uvec2(), offset))
+    })
+}
+
+/// 从目标出发做 Dijkstra 得到积分场，再为每个地块选出代价最低的邻居
+pub fn build_flow_field(map_grid: &MapGrid, obstacles: &NavObstacles, goal: UVec2) -> FlowField {
+    let size = map_grid.size;
+    let tile_count = (size.x * size.y) as usize;
+
+    let mut costs = vec![f32::INFINITY; tile_count];
+    let mut open = BinaryHeap::new();
+    if goal.x < size.x && goal.y < size.y {
+        costs[tile_index(size, goal)] = 0.0;
+        open.push(OpenNode { tile: goal, estimated: 0.0 });
+    }
+    propagate_costs(map_grid, obstacles, &mut costs, &mut open);
+
+    let mut directions = vec![IVec2::ZERO; tile_count];
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let tile = UVec2::new(x, y);
+            directions[tile_index(size, tile)] = best_direction(map_grid, obstacles, &costs, tile);
+        }
+    }
+
+    FlowField {
+        size,
+        goal,
+        blocked: obstacles.blocked.clone(),
+        costs,
+        directions,
+    }
+}
+
+/// 按障碍差异增量修补流场
+///
+/// 新增障碍时，清空经由这些地块（包括绕过其拐角的对角步）得到代价的地块，再从周边仍然有效的地块重新扩展；
+/// 移除障碍时，从重新开放的地块及其邻居向外松弛。最后只重算代价变化的地块及其邻居的方向。
+pub fn repair_flow_field(map_grid: &MapGrid, obstacles: &NavObstacles, field: &mut FlowField) {
+    let size = field.size;
+    let goal = field.goal;
+    let added: Vec<UVec2> = obstacles.blocked.difference(&field.blocked).copied().collect();
+    let removed: Vec<UVec2> = field.blocked.difference(&obstacles.blocked).copied().collect();
+    let old_costs = field.costs.clone();
+    let costs = &mut field.costs;
+
+    // 障碍变化地块及其邻居的代价都可能受影响
+    let mut seeds: Vec<UVec2> = added
+        .iter()
+        .chain(&removed)
+        .filter(|tile| tile.x < size.x && tile.y < size.y)
+        .flat_map(|&tile| std::iter::once(tile).chain(neighbours(size, tile).map(|(next, _)| next)))
+        .filter(|&tile| tile != goal)
+        .collect();
+    seeds.sort_by_key(|tile| (tile.y, tile.x));
+    seeds.dedup();
+
+    // 新增障碍：沿代价来源向外找出所有受影响的地块，置为不可达
+    let mut affected: HashSet<UVec2> = HashSet::new();
+    if !added.is_empty() {
+        let mut stack: Vec<UVec2> = seeds.clone();
+        affected.extend(seeds.iter().copied());
+        while let Some(tile) = stack.pop() {
+            let cost = costs[tile_index(size, tile)];
+            if cost.is_infinite() {
+                continue;
+            }
+            for (next, offset) in neighbours(size, tile) {
+                if next == goal || affected.contains(&next) {
+                    continue;
+                }
+                let next_cost = costs[tile_index(size, next)];
+                if (next_cost - (cost + step_cost(map_grid, next, offset))).abs() <= COST_EPSILON {
+                    affected.insert(next);
+                    stack.push(next);
+                }
+            }
+        }
+        for &tile in &affected {
+            costs[tile_index(size, tile)] = f32::INFINITY;
+        }
+    }
+
+    // 受影响与重新开放的地块从仍然有效的邻居取代价，再整体向外扩展
+    let mut open = BinaryHeap::new();
+    for &tile in affected.iter().chain(&seeds) {
+        if !is_open(map_grid, obstacles, tile.as_ivec2()) {
+            continue;
+        }
+        for (next, offset) in neighbours(size, tile) {
+            let next_cost = costs[tile_index(size, next)];
+            // 从 next 走到 tile，偏移方向相反
+            if next_cost.is_infinite() || !corners_open(map_grid, obstacles, next.as_ivec2(), -offset) {
+                continue;
+            }
+            let candidate = next_cost + step_cost(map_grid, tile, -offset);
+            if candidate < costs[tile_index(size, tile)] {
+                costs[tile_index(size, tile)] = candidate;
+                open.push(OpenNode { tile, estimated: candidate });
+            }
+        }
+    }
+    propagate_costs(map_grid, obstacles, costs, &mut open);
+
+    // 方向只在代价变化的地块及其邻居、障碍变化处需要重算
+    let mut dirty: HashSet<UVec2> = seeds.into_iter().collect();
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let tile = UVec2::new(x, y);
+            let index = tile_index(size, tile);
+            let (old, new) = (old_costs[index], costs[index]);
+            let changed = if old.is_infinite() || new.is_infinite() {
+                old.is_infinite() != new.is_infinite()
+            } else {
+                (old - new).abs() > COST_EPSILON
+            };
+            if changed {
+                dirty.insert(tile);
+                dirty.extend(neighbours(size, tile).map(|(next, _)| next));
+            }
+        }
+    }
+    for tile in dirty {
+        field.directions[tile_index(size, tile)] = best_direction(map_grid, obstacles, &field.costs, tile);
+    }
+
+    field.blocked = obstacles.blocked.clone();
+}
+
+/// Dijkstra 扩展积分场（开放列表中为代价已下降的地块）
+fn propagate_costs(map_grid: &MapGrid, obstacles: &NavObstacles, costs: &mut [f32], open: &mut BinaryHeap<OpenNode>) {
+    let size = map_grid.size;
+
+    while let Some(OpenNode { tile: current, estimated }) = open.pop() {
+        if estimated > costs[tile_index(size, current)] {
+            continue;
+        }
+
+        for (next, offset) in neighbours(size, current) {
+            if !is_open(map_grid, obstacles, next.as_ivec2())
+                || !corners_open(map_grid, obstacles, current.as_ivec2(), offset)
+            {
+                continue;
+            }
+
+            let new_cost = estimated + step_cost(map_grid, next, offset);
+            if new_cost < costs[tile_index(size, next)] {
+                costs[tile_index(size, next)] = new_cost;
+                open.push(OpenNode { tile: next, estimated: new_cost });
+            }
+        }
+    }
+}
+
+/// 地块的流动方向：代价最低的可走邻居，目标与不可达地块为零向量
+fn best_direction(map_grid: &MapGrid, obstacles: &NavObstacles, costs: &[f32], tile: UVec2) -> IVec2 {
+    let size = map_grid.size;
+    let cost = costs[tile_index(size, tile)];
+    if cost == 0.0 || cost.is_infinite() {
+        return IVec2::ZERO;
+    }
+
+    let mut best = (IVec2::ZERO, cost);
+    for (next, offset) in neighbours(size, tile) {
+        if !corners_open(map_grid, obstacles, tile.as_ivec2(), offset) {
+            continue;
+        }
+        let next_cost = costs[tile_index(size, next)];
+        if next_cost < best.1 {
+            best = (offset, next_cost);
+        }
+    }
+    best.0
+}
+
+/// 沿流场移动；到达目标地块后直接靠近目标点
+///
+/// 返回 false 表示所在地块无法到达目标（实体保持不动），调用方应改用 A* 路径。
+pub fn step_along_flow(
+    map_grid: &MapGrid,
+    flow_field: &FlowField,
+    transform: &mut Transform,
+    target: Vec2,
+    distance: f32,
+) -> bool {
+    let Some(tile) = map_grid.world_to_grid(transform.translation.truncate()) else {
+        move_towards(transform, target, distance);
+        return true;
+    };
+
+    match flow_field.direction_at(tile) {
+        Some(IVec2::ZERO) => {
+            move_towards(transform, target, distance);
+            true
+        }
+        Some(direction) => {
+            let next_pos = map_grid.tile_to_world(tile.as_ivec2() + direction);
+            move_towards(transform, next_pos, distance);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use crate::systems::map::generate_default_map;
+
+    fn assert_same_costs(repaired: &FlowField, rebuilt: &FlowField) {
+        for (index, (a, b)) in repaired.costs.iter().zip(&rebuilt.costs).enumerate() {
+            let same = (a.is_infinite() && b.is_infinite()) || (a - b).abs() <= COST_EPSILON;
+            assert!(same, "tile {} cost {} != {}", index, a, b);
+        }
+        for (index, (a, b)) in repaired.directions.iter().zip(&rebuilt.directions).enumerate() {
+            // 代价相同的方向可能并列，只要求指向同等代价的邻居
+            if a != b {
+                let tile = UVec2::new(index as u32 % repaired.size.x, index as u32 / repaired.size.x);
+                let cost_via = |offset: IVec2| repaired.cost_at((tile.as_ivec2() + offset).as_uvec2());
+                assert!((cost_via(*a) - cost_via(*b)).abs() <= COST_EPSILON, "tile {:?} direction {:?} != {:?}", tile, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn incremental_repair_matches_rebuild() {
+        let map_grid = generate_default_map();
+        let mut rng = StdRng::seed_from_u64(7);
+        let goal = map_grid.size / 2;
+        let mut obstacles = NavObstacles::default();
+        let mut field = build_flow_field(&map_grid, &obstacles, goal);
+
+        for _ in 0..30 {
+            // 每轮随机加减几个障碍
+            for _ in 0..3 {
+                let tile = UVec2::new(rng.gen_range(0..map_grid.size.x), rng.gen_range(0..map_grid.size.y));
+                if tile == goal {
+                    continue;
+                }
+                if !obstacles.blocked.insert(tile) {
+                    obstacles.blocked.remove(&tile);
+                }
+            }
+
+            repair_flow_field(&map_grid, &obstacles, &mut field);
+            assert_same_costs(&field, &build_flow_field(&map_grid, &obstacles, goal));
+        }
+    }
+
+    #[test]
+    fn walls_around_goal_make_it_unreachable() {
+        let map_grid = generate_default_map();
+        let goal = map_grid.size / 2;
+        let mut obstacles = NavObstacles::default();
+        let mut field = build_flow_field(&map_grid, &obstacles, goal);
+        let start = goal + UVec2::new(3, 0);
+
+        obstacles.blocked.extend(neighbours(map_grid.size, goal).map(|(tile, _)| tile));
+        repair_flow_field(&map_grid, &obstacles, &mut field);
+        assert!(field.direction_at(start).is_none());
+
+        obstacles.blocked.remove(&(goal + UVec2::new(1, 0)));
+        repair_flow_field(&map_grid, &obstacles, &mut field);
+        assert!(field.direction_at(start).is_some());
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use crate::components::building::Building;
+use crate::components::game_over::{GameOverSummary, RestartGameEvent, Restarting, RunStats};
+use crate::components::player::{Player, PlayerVitals};
+use crate::components::save::Saveable;
+use crate::states::GameState;
+use crate::systems::time::GameTime;
+
+/// 玩家死亡与结算插件
+///
+/// 玩家生命归零后进入 `GameOver`；结算界面可以读取最近的存档或重新开局。
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_message::<RestartGameEvent>()
+            .add_systems(Update, check_player_death.run_if(in_state(GameState::InGame)))
+            .add_systems(Update, restart_game.run_if(in_state(GameState::GameOver)))
+            .add_systems(OnEnter(GameState::InGame), reset_resource::<RunStats>.run_if(restarting))
+            // 标记在进入后的第一帧移除，保证 OnEnter 中的重置都已执行
+            .add_systems(Update, finish_restart.run_if(in_state(GameState::InGame)).run_if(restarting))
+            .add_systems(OnExit(GameState::GameOver), |mut commands: Commands| {
+                commands.remove_resource::<GameOverSummary>();
+            });
+    }
+}
+
+/// 玩家死亡时生成结算信息并结束本局
+fn check_player_death(
+    mut commands: Commands,
+    game_time: Option<Res<GameTime>>,
+    run_stats: Res<RunStats>,
+    player_query: Query<&PlayerVitals, With<Player>>,
+    building_query: Query<(), With<Building>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !player_query.iter().any(|vitals| vitals.is_dead()) {
+        return;
+    }
+
+    let summary = GameOverSummary {
+        days_survived: game_time.map(|game_time| game_time.day).unwrap_or(1),
+        enemies_killed: run_stats.enemies_killed,
+        buildings: building_query.iter().count(),
+    };
+    info!(
+        "玩家死亡: 存活 {} 天, 击杀 {} 个敌人, 建筑 {} 座",
+        summary.days_survived, summary.enemies_killed, summary.buildings
+    );
+
+    commands.insert_resource(summary);
+    next_state.set(GameState::GameOver);
+}
+
+/// 重新开局：清除本局的实体，回到 `InGame` 后由各插件重置自己的状态并重新生成
+fn restart_game(
+    mut commands: Commands,
+    mut restart_events: MessageReader<RestartGameEvent>,
+    saved_query: Query<Entity, With<Saveable>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if restart_events.read().count() == 0 {
+        return;
+    }
+
+    for entity in saved_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    info!("重新开局");
+    commands.insert_resource(Restarting);
+    next_state.set(GameState::InGame);
+}
+
+fn finish_restart(mut commands: Commands) {
+    commands.remove_resource::<Restarting>();
+}
+
+/// 运行条件：正在重新开局
+pub fn restarting(marker: Option<Res<Restarting>>) -> bool {
+    marker.is_some()
+}
+
+/// 把已存在的资源恢复为初始值（各插件在重新开局时重置自己的资源）
+pub fn reset_resource<T: Resource + FromWorld>(world: &mut World) {
+    if world.contains_resource::<T>() {
+        let value = T::from_world(world);
+        world.insert_resource(value);
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::asset::AssetPlugin;
+use bevy::input::InputPlugin;
+use bevy::log::LogPlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use std::time::Duration;
+use crate::components::building::Building;
+use crate::components::enemy::{Enemy, EnemyBase, EnemyWaveConfig};
+use crate::components::game_over::GameOverSummary;
+use crate::components::plant::Plant;
+use crate::components::quest::{Quest, QuestStatus};
+use crate::components::robot::Robot;
+use crate::states::GameState;
+use crate::systems::time::GameTime;
+
+/// 无头模拟配置
+///
+/// 存在该资源即表示当前以无头模式运行：不创建窗口，不生成任何 Mesh2d/Sprite/UI。
+#[derive(Resource, Clone, Debug)]
+pub struct HeadlessConfig {
+    pub days: u32,      // 模拟天数
+    pub timestep: f32,  // 固定时间步长（秒）
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            days: 15,
+            timestep: 1.0 / 60.0,
+        }
+    }
+}
+
+impl HeadlessConfig {
+    /// 从环境变量读取配置（HEADLESS_DAYS、HEADLESS_TIMESTEP）
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        let days = std::env::var("HEADLESS_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(default.days);
+
+        let timestep = std::env::var("HEADLESS_TIMESTEP")
+            .ok()
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| *v > 0.0)
+            .unwrap_or(default.timestep);
+
+        Self { days, timestep }
+    }
+}
+
+/// 检查是否请求了无头模式（HEADLESS=1）
+pub fn headless_requested() -> bool {
+    std::env::var("HEADLESS")
+        .map(|v| !v.is_empty() && v != "0")
+        .unwrap_or(false)
+}
+
+/// 运行条件：仅在有窗口渲染时运行纯视觉系统
+pub fn rendering_enabled(headless: Option<Res<HeadlessConfig>>) -> bool {
+    headless.is_none()
+}
+
+/// 无头模拟插件
+///
+/// 用 MinimalPlugins 替代 DefaultPlugins，并以固定时间步长尽可能快地推进模拟，
+/// 到达指定天数后输出统计并退出。
+pub struct HeadlessPlugin {
+    pub config: HeadlessConfig,
+}
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        let timestep = Duration::from_secs_f32(self.config.timestep);
+
+        app.add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::ZERO)))
+            .add_plugins((
+                LogPlugin::default(),
+                StatesPlugin,
+                AssetPlugin::default(),
+                InputPlugin,
+            ))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(timestep))
+            .insert_resource(self.config.clone())
+            .add_systems(Update, finish_simulation)
+            .add_systems(OnEnter(GameState::GameOver), finish_on_game_over);
+
+        info!(
+            "无头模式: 模拟 {} 天, 步长 {:.4} 秒",
+            self.config.days, self.config.timestep
+        );
+    }
+}
+
+/// 到达目标天数后输出统计并退出
+fn finish_simulation(
+    config: Res<HeadlessConfig>,
+    game_time: Option<Res<GameTime>>,
+    enemy_query: Query<&Enemy>,
+    base_query: Query<&EnemyBase>,
+    building_query: Query<&Building>,
+    plant_query: Query<&Plant>,
+    robot_query: Query<&Robot>,
+    quest_query: Query<&Quest>,
+    wave_config: Option<Res<EnemyWaveConfig>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let Some(game_time) = game_time else { return; };
+
+    if game_time.day <= config.days {
+        return;
+    }
+
+    let completed_quests = quest_query
+        .iter()
+        .filter(|quest| quest.status == QuestStatus::Completed)
+        .count();
+
+    info!("=== 模拟结束: 共 {} 天 ===", config.days);
+    info!("敌人: {}, 敌人基地: {}", enemy_query.iter().len(), base_query.iter().len());
+    info!("建筑: {}, 植物: {}, 机器人: {}", building_query.iter().len(), plant_query.iter().len(), robot_query.iter().len());
+    info!("任务: {}/{} 已完成", completed_quests, quest_query.iter().len());
+    if let Some(wave_config) = wave_config {
+        info!("已清除波次: {}", wave_config.current_wave.saturating_sub(1));
+    }
+
+    exit.write(AppExit::Success);
+}
+
+/// 玩家死亡后无法继续推进，输出结算并退出
+fn finish_on_game_over(
+    summary: Option<Res<GameOverSummary>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if let Some(summary) = summary {
+        info!(
+            "=== 模拟提前结束: 玩家在第 {} 天死亡, 击杀 {}, 建筑 {} ===",
+            summary.days_survived, summary.enemies_killed, summary.buildings
+        );
+    }
+
+    exit.write(AppExit::Success);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::systems::{item, map, player, robot};
+
+    #[test]
+    fn setup_spawns_no_sprites_in_headless_mode() {
+        let mut world = World::new();
+        world.insert_resource(HeadlessConfig::default());
+        world.run_system_once(map::setup_map).unwrap();
+        world.run_system_once(map::spawn_environment_decorations).unwrap();
+        world.run_system_once(item::spawn_base).unwrap();
+        world.run_system_once(player::spawn_player).unwrap();
+
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::Digit1);
+        world.insert_resource(keys);
+        world.run_system_once(robot::spawn_robot).unwrap();
+
+        assert_eq!(world.query::<&Robot>().iter(&world).count(), 2);
+        assert_eq!(world.query::<&Sprite>().iter(&world).count(), 0);
+    }
+}
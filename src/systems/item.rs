@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use crate::components::inventory::{Base, Inventory, InventoryOwner, TransferItemsEvent};
+use crate::components::item::{describe_amounts, ItemAmounts, ItemCatalogAsset, ItemRegistry};
+use crate::components::save::Saveable;
+use crate::systems::json_catalog::JsonCatalogPlugin;
+
+/// 物品插件：加载物品注册表，生成基地并处理库存间的物品转移
+pub struct ItemPlugin;
+
+impl Plugin for ItemPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonCatalogPlugin::<ItemCatalogAsset>::default())
+            .add_message::<TransferItemsEvent>()
+            .add_systems(OnEnter(crate::states::GameState::InGame), spawn_base)
+            .add_systems(Update, handle_item_transfers.run_if(in_state(crate::states::GameState::InGame)));
+    }
+}
+
+/// 生成基地实体（读档回到游戏时基地已经存在；重新开始时随可存档实体一起清除后重新生成）
+pub fn spawn_base(mut commands: Commands, base_query: Query<(), With<Base>>) {
+    if base_query.is_empty() {
+        commands.spawn((Base, Inventory::default(), Saveable));
+    }
+}
+
+/// 处理库存间的物品转移请求
+fn handle_item_transfers(
+    mut transfer_events: MessageReader<TransferItemsEvent>,
+    registry: Res<ItemRegistry>,
+    base_query: Query<Entity, With<Base>>,
+    mut inventory_query: Query<&mut Inventory>,
+) {
+    let base = base_query.single().ok();
+
+    for event in transfer_events.read() {
+        let resolve = |owner| match owner {
+            InventoryOwner::Base => base,
+            InventoryOwner::Entity(entity) => Some(entity),
+        };
+        let (Some(from), Some(to)) = (resolve(event.from), resolve(event.to)) else {
+            warn!("基地不存在，无法转移: {:?} -> {:?}", event.from, event.to);
+            continue;
+        };
+        if from == to {
+            continue;
+        }
+
+        let Ok([mut source, mut target]) = inventory_query.get_many_mut([from, to]) else {
+            warn!("转移双方必须都有库存: {:?} -> {:?}", event.from, event.to);
+            continue;
+        };
+        let moved = transfer_items(&mut source, &mut target, event, &registry);
+
+        if moved.is_empty() {
+            debug!("库存转移未移动任何物品: {:?} -> {:?}", event.from, event.to);
+        } else {
+            debug!("库存转移 {:?} -> {:?}: {}", event.from, event.to, describe_amounts(&moved, &registry));
+        }
+    }
+}
+
+fn transfer_items(
+    source: &mut Inventory,
+    target: &mut Inventory,
+    event: &TransferItemsEvent,
+    registry: &ItemRegistry,
+) -> ItemAmounts {
+    event
+        .items
+        .iter()
+        .map(|(&item, &amount)| (item, source.transfer_to(target, item, amount, registry)))
+        .filter(|&(_, moved)| moved > 0)
+        .collect()
+}
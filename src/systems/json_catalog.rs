@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AssetLoadFailedEvent, LoadContext};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// 从 JSON 数据文件加载、支持热重载的数据目录
+///
+/// 同一份数据文件既由资源服务器加载，也通过 `include_str!` 编译进程序作为内置默认值。
+pub trait JsonCatalog: Asset + DeserializeOwned {
+    /// 日志中的名称
+    const LABEL: &'static str;
+    /// 数据文件扩展名（不含开头的点）
+    const EXTENSION: &'static str;
+    /// 随游戏发布的数据文件（相对 assets 目录）
+    const PATH: &'static str;
+
+    /// 当前生效的目录资源
+    type Catalog: Resource + Default;
+
+    /// 校验数值合法性
+    fn validate(&self) -> Result<(), String>;
+
+    /// 用数据文件替换目录资源，返回生效的条目数
+    fn apply(&self, catalog: &mut Self::Catalog) -> usize;
+}
+
+/// 解析并校验数据文件
+pub fn parse_catalog<T: JsonCatalog>(bytes: &[u8]) -> Result<T, String> {
+    let catalog: T = serde_json::from_slice(bytes)
+        .map_err(|e| format!("{}解析失败: {}", T::LABEL, e))?;
+    catalog.validate()?;
+    Ok(catalog)
+}
+
+/// 当前加载的数据文件句柄（热重载时据此过滤资源事件）
+#[derive(Resource)]
+pub struct CatalogHandle<T: JsonCatalog>(pub Option<Handle<T>>);
+
+impl<T: JsonCatalog> Default for CatalogHandle<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+/// 数据目录插件：注册加载器，启动时加载数据文件，加载或修改后替换目录资源
+pub struct JsonCatalogPlugin<T: JsonCatalog>(PhantomData<fn() -> T>);
+
+impl<T: JsonCatalog> Default for JsonCatalogPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: JsonCatalog> Plugin for JsonCatalogPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<T>()
+            .register_asset_loader(JsonCatalogLoader::<T>::default())
+            .init_resource::<T::Catalog>()
+            .init_resource::<CatalogHandle<T>>()
+            .add_systems(Startup, load_catalog::<T>)
+            .add_systems(Update, (apply_catalog::<T>, report_catalog_errors::<T>));
+    }
+}
+
+/// `*.<EXTENSION>` 加载器
+#[derive(TypePath)]
+pub struct JsonCatalogLoader<T: JsonCatalog> {
+    extensions: [&'static str; 1],
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: JsonCatalog> Default for JsonCatalogLoader<T> {
+    fn default() -> Self {
+        Self {
+            extensions: [T::EXTENSION],
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: JsonCatalog> AssetLoader for JsonCatalogLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(parse_catalog(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &self.extensions
+    }
+}
+
+fn load_catalog<T: JsonCatalog>(asset_server: Res<AssetServer>, mut handle: ResMut<CatalogHandle<T>>) {
+    handle.0 = Some(asset_server.load(T::PATH));
+}
+
+fn apply_catalog<T: JsonCatalog>(
+    mut events: MessageReader<AssetEvent<T>>,
+    assets: Res<Assets<T>>,
+    handle: Res<CatalogHandle<T>>,
+    mut catalog: ResMut<T::Catalog>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event else {
+            continue;
+        };
+        if handle.0.as_ref().is_none_or(|handle| handle.id() != id) {
+            continue;
+        }
+        let Some(asset) = assets.get(id) else { continue; };
+
+        let count = asset.apply(&mut catalog);
+        info!("{}已加载: {} 项", T::LABEL, count);
+    }
+}
+
+/// 数据文件无效时保留当前目录
+fn report_catalog_errors<T: JsonCatalog>(mut events: MessageReader<AssetLoadFailedEvent<T>>) {
+    for event in events.read() {
+        warn!("{} {} 加载失败，继续使用当前数据: {}", T::LABEL, event.path, event.error);
+    }
+}
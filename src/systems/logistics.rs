@@ -0,0 +1,402 @@
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use std::time::Duration;
+use crate::components::building::{Building, BuildingStatus, ConstructionSite};
+use crate::components::inventory::{Base, Inventory, InventoryOwner};
+use crate::components::item::{ItemAmounts, ItemId, ItemRegistry};
+use crate::components::logistics::{
+    Courier, HubSlot, JobBoard, LogisticsRole, HAUL_BATCH, LOGISTICS_PLAN_INTERVAL, MIN_HAUL, REQUEST_BUFFER_CYCLES,
+};
+use crate::components::player::Player;
+use crate::systems::game_over::{reset_resource, restarting};
+
+/// 物流插件：持有搬运任务板，按建筑与工地的供需定期发布任务，由采集、建造机器人承接
+pub struct LogisticsPlugin;
+
+impl Plugin for LogisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<JobBoard>()
+            // 重新开局时清空指向旧实体的任务
+            .add_systems(OnEnter(crate::states::GameState::InGame), reset_resource::<JobBoard>.run_if(restarting))
+            .add_systems(Update, plan_logistics_jobs
+                .run_if(in_state(crate::states::GameState::InGame))
+                .run_if(on_timer(Duration::from_secs_f32(LOGISTICS_PLAN_INTERVAL))));
+    }
+}
+
+/// 参与规划的建筑快照
+struct Site {
+    entity: Entity,
+    position: Vec2,
+    inventory: Inventory,
+    inputs: ItemAmounts,
+    outputs: ItemAmounts,
+    roles: Vec<LogisticsRole>,
+}
+
+impl Site {
+    fn has_role(&self, role: LogisticsRole) -> bool {
+        self.roles.contains(&role)
+    }
+}
+
+/// 按供需重新发布搬运任务
+///
+/// 先为工地补齐材料、为需求方补足若干周期的投入（优先从供应方取货，其次从仓库，
+/// 工地最后还可以从基地库存取货；同类来源按距离），再把供应方剩余的产出送到最近的
+/// 有空位的仓库，没有可用仓库时送回基地（玩家身边）。仓库只为需求方留下储备，
+/// 其余物品继续送回基地，供建造、制造、升级等从基地库存扣除的开销使用。
+/// 已承接的任务计入在途数量，不会重复派单。
+fn plan_logistics_jobs(
+    mut board: ResMut<JobBoard>,
+    registry: Res<ItemRegistry>,
+    base_query: Query<&Inventory, With<Base>>,
+    building_query: Query<(Entity, &Building, &BuildingStatus, &Transform, &Inventory)>,
+    site_query: Query<(Entity, &ConstructionSite, &BuildingStatus, &Transform, &Inventory)>,
+    player_query: Query<&Transform, With<Player>>,
+    courier_query: Query<&Courier>,
+) {
+    // 承接任务的机器人已不在或已放弃时撤下任务，其余未承接的任务重新规划
+    board.jobs.retain(|job| {
+        job.carrier.is_none_or(|carrier| {
+            courier_query
+                .get(carrier)
+                .is_ok_and(|courier| courier.delivery.as_ref().is_some_and(|delivery| delivery.job == Some(job.id)))
+        })
+    });
+    board.clear_open();
+
+    let roles = [LogisticsRole::Provider, LogisticsRole::Requester, LogisticsRole::Storage];
+    let sites: Vec<Site> = building_query
+        .iter()
+        .filter(|(_, _, status, _, _)| !status.is_constructing)
+        .map(|(entity, building, _, transform, inventory)| Site {
+            entity,
+            position: transform.translation.truncate(),
+            inventory: inventory.clone(),
+            inputs: building.stats.inputs.clone(),
+            outputs: building.stats.outputs.clone(),
+            roles: roles.into_iter().filter(|&role| building.has_logistics_role(role)).collect(),
+        })
+        .collect();
+
+    let hubs: Vec<HubSlot> = sites
+        .iter()
+        .filter(|site| site.has_role(LogisticsRole::Storage))
+        .map(|site| {
+            let free = site
+                .inventory
+                .capacity
+                .map_or(u32::MAX, |capacity| capacity.saturating_sub(site.inventory.total()));
+            HubSlot {
+                entity: site.entity,
+                position: site.position,
+                free: free.saturating_sub(board.incoming_total(InventoryOwner::Entity(site.entity))),
+            }
+        })
+        .collect();
+    board.hubs = hubs;
+
+    let base = player_query
+        .single()
+        .ok()
+        .zip(base_query.single().ok())
+        .map(|(player_transform, base_inventory)| (player_transform.translation.truncate(), base_inventory));
+
+    // 工地：补齐材料
+    for (entity, site, status, transform, inventory) in site_query.iter() {
+        if !status.is_constructing {
+            continue;
+        }
+        let to = InventoryOwner::Entity(entity);
+        let position = transform.translation.truncate();
+        for (&item, &amount) in &site.materials {
+            let shortage = amount.saturating_sub(inventory.count(item) + board.incoming(to, item));
+            request_supply(&mut board, &sites, base, to, position, item, shortage);
+        }
+    }
+
+    // 需求方：补足投入
+    for site in sites.iter().filter(|site| site.has_role(LogisticsRole::Requester)) {
+        let to = InventoryOwner::Entity(site.entity);
+        for (&item, &per_cycle) in &site.inputs {
+            let stocked = site.inventory.count(item) + board.incoming(to, item);
+            let space = site
+                .inventory
+                .space_for(item, &registry)
+                .saturating_sub(board.incoming_total(to));
+            let shortage = (per_cycle * REQUEST_BUFFER_CYCLES).saturating_sub(stocked).min(space);
+            request_supply(&mut board, &sites, None, to, site.position, item, shortage);
+        }
+    }
+
+    // 供应方：剩余产出送到仓库或基地
+    for site in sites
+        .iter()
+        .filter(|site| site.has_role(LogisticsRole::Provider) && !site.has_role(LogisticsRole::Storage))
+    {
+        let from = InventoryOwner::Entity(site.entity);
+        for &item in site.outputs.keys() {
+            let available = site.inventory.count(item).saturating_sub(board.outgoing(from, item));
+            if available == 0 || (available < MIN_HAUL && !site.inventory.is_full(&registry)) {
+                continue;
+            }
+
+            let amount = available.min(HAUL_BATCH);
+            let hub = board
+                .hubs
+                .iter_mut()
+                .filter(|hub| hub.free > 0)
+                .min_by(|a, b| a.position.distance(site.position).total_cmp(&b.position.distance(site.position)));
+            let (to, amount) = match hub {
+                Some(hub) => {
+                    let amount = amount.min(hub.free);
+                    hub.free -= amount;
+                    (InventoryOwner::Entity(hub.entity), amount)
+                }
+                None => (InventoryOwner::Base, amount),
+            };
+            board.post(from, site.position, to, item, amount);
+        }
+    }
+
+    // 仓库：需求方储备之外的物品送回基地
+    let mut reserve = ItemAmounts::new();
+    for site in sites.iter().filter(|site| site.has_role(LogisticsRole::Requester)) {
+        for (&item, &per_cycle) in &site.inputs {
+            *reserve.entry(item).or_insert(0) += per_cycle * REQUEST_BUFFER_CYCLES;
+        }
+    }
+    for site in sites.iter().filter(|site| site.has_role(LogisticsRole::Storage)) {
+        let from = InventoryOwner::Entity(site.entity);
+        for (&item, &count) in &site.inventory.items {
+            let kept = reserve.get(&item).copied().unwrap_or(0);
+            let available = count.saturating_sub(kept).saturating_sub(board.outgoing(from, item));
+            if available == 0 || (available < MIN_HAUL && !site.inventory.is_full(&registry)) {
+                continue;
+            }
+            board.post(from, site.position, InventoryOwner::Base, item, available.min(HAUL_BATCH));
+        }
+    }
+}
+
+/// 为缺少 `shortage` 个物品的需求方发布取货任务
+///
+/// 来源依次为产出该物品的供应方、仓库、基地库存（`base` 为 None 时不从基地取货），同类按距离。
+fn request_supply(
+    board: &mut JobBoard,
+    sites: &[Site],
+    base: Option<(Vec2, &Inventory)>,
+    to: InventoryOwner,
+    position: Vec2,
+    item: ItemId,
+    mut shortage: u32,
+) {
+    if shortage == 0 {
+        return;
+    }
+
+    let mut sources: Vec<(u8, InventoryOwner, Vec2, u32)> = sites
+        .iter()
+        .filter(|source| InventoryOwner::Entity(source.entity) != to)
+        .filter_map(|source| {
+            let tier = if source.outputs.contains_key(&item) {
+                0
+            } else if source.has_role(LogisticsRole::Storage) {
+                1
+            } else {
+                return None;
+            };
+            Some((tier, InventoryOwner::Entity(source.entity), source.position, source.inventory.count(item)))
+        })
+        .collect();
+    if let Some((base_position, base_inventory)) = base {
+        sources.push((2, InventoryOwner::Base, base_position, base_inventory.count(item)));
+    }
+    sources.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then(a.2.distance(position).total_cmp(&b.2.distance(position)))
+    });
+
+    for (_, from, pickup, stock) in sources {
+        let amount = stock
+            .saturating_sub(board.outgoing(from, item))
+            .min(shortage)
+            .min(HAUL_BATCH);
+        if amount == 0 {
+            continue;
+        }
+        board.post(from, pickup, to, item, amount);
+        shortage -= amount;
+        if shortage == 0 {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::components::building::BuildingType;
+    use crate::components::item::amounts;
+    use crate::components::logistics::{Delivery, HaulJob};
+
+    fn spawn_building(world: &mut World, building_type: BuildingType, x: f32, inventory: Inventory) -> Entity {
+        let mut building = Building::new(building_type);
+        building.is_operational = true;
+        world.spawn((
+            building,
+            BuildingStatus { is_constructing: false, ..default() },
+            Transform::from_xyz(x, 0.0, 0.0),
+            inventory,
+        )).id()
+    }
+
+    fn jobs_from(world: &World, from: Entity) -> Vec<(InventoryOwner, ItemId, u32)> {
+        world
+            .resource::<JobBoard>()
+            .jobs
+            .iter()
+            .filter(|job| job.from == InventoryOwner::Entity(from))
+            .map(|job| (job.to, job.item, job.amount))
+            .collect()
+    }
+
+    fn logistics_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<JobBoard>();
+        world.insert_resource(ItemRegistry::default());
+        world
+    }
+
+    fn refinery_buffer() -> ItemAmounts {
+        Building::new(BuildingType::Refinery)
+            .stats
+            .inputs
+            .iter()
+            .map(|(&item, &count)| (item, count * REQUEST_BUFFER_CYCLES))
+            .collect()
+    }
+
+    /// 每个未承接的任务由一台机器人承接
+    fn claim_all(world: &mut World) -> Vec<(Entity, HaulJob)> {
+        let mut claimed = Vec::new();
+        loop {
+            let carrier = world.spawn_empty().id();
+            let Some(job) = world.resource_mut::<JobBoard>().claim(carrier, Vec2::ZERO, u32::MAX) else {
+                world.despawn(carrier);
+                return claimed;
+            };
+            world.entity_mut(carrier).insert(Courier { delivery: Some(Delivery::from_job(&job)) });
+            claimed.push((carrier, job));
+        }
+    }
+
+    #[test]
+    fn storage_sends_surplus_above_requester_buffers_to_the_base() {
+        let mut world = logistics_world();
+
+        // 需求方的缓冲已满，不再向仓库要货
+        let buffer = refinery_buffer();
+        let metal_reserve = buffer[&ItemId::METAL];
+        spawn_building(&mut world, BuildingType::Refinery, 0.0, Inventory { items: buffer, ..Inventory::with_capacity(100) });
+        let storage = spawn_building(&mut world, BuildingType::Storage, 64.0, Inventory {
+            items: amounts(&[(ItemId::METAL, metal_reserve + HAUL_BATCH + 5), (ItemId::ORGANIC, MIN_HAUL - 1)]),
+            ..Inventory::with_capacity(200)
+        });
+
+        world.run_system_once(plan_logistics_jobs).unwrap();
+
+        // 储备留在仓库，零头不值得跑一趟
+        assert_eq!(jobs_from(&world, storage), vec![(InventoryOwner::Base, ItemId::METAL, HAUL_BATCH)]);
+    }
+
+    #[test]
+    fn full_storage_returns_small_surplus_too() {
+        let mut world = logistics_world();
+        let storage = spawn_building(&mut world, BuildingType::Storage, 0.0, Inventory {
+            items: amounts(&[(ItemId::ORGANIC, MIN_HAUL - 1), (ItemId::SOIL, 7)]),
+            ..Inventory::with_capacity(MIN_HAUL + 6)
+        });
+
+        world.run_system_once(plan_logistics_jobs).unwrap();
+
+        assert_eq!(jobs_from(&world, storage), vec![
+            (InventoryOwner::Base, ItemId::ORGANIC, MIN_HAUL - 1),
+            (InventoryOwner::Base, ItemId::SOIL, 7),
+        ]);
+    }
+
+    #[test]
+    fn requester_buffers_are_filled_once() {
+        let mut world = logistics_world();
+        let buffer = refinery_buffer();
+        let refinery = spawn_building(&mut world, BuildingType::Refinery, 0.0, Inventory {
+            items: amounts(&[(ItemId::METAL, 1)]),
+            ..Inventory::with_capacity(100)
+        });
+        let stock: ItemAmounts = buffer.keys().map(|&item| (item, 50)).collect();
+        let storage = spawn_building(&mut world, BuildingType::Storage, 64.0, Inventory {
+            items: stock,
+            ..Inventory::with_capacity(500)
+        });
+
+        world.run_system_once(plan_logistics_jobs).unwrap();
+
+        // 已有的存货计入缓冲
+        let mut expected: Vec<_> = buffer
+            .iter()
+            .map(|(&item, &amount)| {
+                let stocked = if item == ItemId::METAL { 1 } else { 0 };
+                (InventoryOwner::Entity(refinery), item, amount - stocked)
+            })
+            .collect();
+        let requested = |world: &World| {
+            jobs_from(world, storage)
+                .into_iter()
+                .filter(|(to, _, _)| *to == InventoryOwner::Entity(refinery))
+                .collect::<Vec<_>>()
+        };
+        expected.sort_by_key(|(_, item, _)| *item);
+        assert_eq!(requested(&world), expected);
+
+        // 在途的任务计入缓冲，不重复派单
+        claim_all(&mut world);
+        world.run_system_once(plan_logistics_jobs).unwrap();
+        let board = world.resource::<JobBoard>();
+        assert!(board.jobs.iter().all(|job| job.carrier.is_some() || job.to != InventoryOwner::Entity(refinery)));
+        assert_eq!(requested(&world), expected);
+    }
+
+    #[test]
+    fn abandoned_jobs_are_requeued() {
+        let mut world = logistics_world();
+        let storage = spawn_building(&mut world, BuildingType::Storage, 0.0, Inventory {
+            items: amounts(&[(ItemId::METAL, 10), (ItemId::CRYSTAL, 10), (ItemId::SOIL, 10)]),
+            ..Inventory::with_capacity(500)
+        });
+
+        world.run_system_once(plan_logistics_jobs).unwrap();
+        let claimed = claim_all(&mut world);
+        assert_eq!(claimed.len(), 3);
+
+        // 第一台仍在运送，第二台放弃了任务，第三台已经不在
+        world.get_mut::<Courier>(claimed[1].0).unwrap().delivery = None;
+        world.despawn(claimed[2].0);
+
+        world.run_system_once(plan_logistics_jobs).unwrap();
+
+        let board = world.resource::<JobBoard>();
+        let carried: Vec<_> = board.jobs.iter().filter_map(|job| job.carrier).collect();
+        assert_eq!(carried, vec![claimed[0].0]);
+        let open: Vec<_> = board
+            .jobs
+            .iter()
+            .filter(|job| job.carrier.is_none())
+            .map(|job| (job.item, job.amount))
+            .collect();
+        assert_eq!(open, claimed[1..].iter().map(|(_, job)| (job.item, job.amount)).collect::<Vec<_>>());
+        assert_eq!(jobs_from(&world, storage).len(), 3);
+    }
+}
@@ -5,6 +5,7 @@ use crate::resources::world::{MapGrid, TileType, TileData, TILE_SIZE, CHUNK_SIZE
 use crate::systems::time::{GameTime, DayPhase, MoonPhase};
 use crate::components::player::Player;
 use crate::states::GameState;
+use crate::systems::game_over::restarting;
 use noise::{NoiseFn, Simplex, Seedable};
 use rand::{Rng, SeedableRng};
 
@@ -59,29 +60,54 @@ pub struct MapPlugin;
 
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), setup_map)
-            .add_systems(OnEnter(GameState::InGame), spawn_environment_decorations.after(setup_map));
+        // 读档回到游戏时地图已经存在，不再重新生成；重新开局只还原地块数据
+        app.add_systems(OnEnter(GameState::InGame), (
+            reset_map.run_if(restarting),
+            (
+                setup_map,
+                spawn_environment_decorations,
+            ).chain().run_if(not(resource_exists::<MapGrid>)),
+        ).chain());
     }
 }
 
+/// 地图尺寸与种子
+const MAP_WIDTH: u32 = 20;
+const MAP_HEIGHT: u32 = 20;
+const MAP_SEED: u32 = 42;
+
+/// 默认尺寸的空地图，只用于坐标换算（如迁移没有记录地图的旧存档）
+pub fn default_map_layout() -> MapGrid {
+    MapGrid::new(MAP_WIDTH, MAP_HEIGHT, MAP_SEED as u64)
+}
+
+/// 按固定尺寸与种子生成地图
+pub fn generate_default_map() -> MapGrid {
+    generate_map_with_noise(MAP_WIDTH, MAP_HEIGHT, MAP_SEED)
+}
+
+/// 重新开局：地图种子固定，重新生成即可还原被改动的地块，地块网格与装饰无需重建
+fn reset_map(mut map_grid: ResMut<MapGrid>) {
+    *map_grid = generate_default_map();
+}
+
 pub fn setup_map(
     mut commands: Commands,
-    assets: Res<MapRenderAssets>,
+    assets: Option<Res<MapRenderAssets>>,
 ) {
-    let width = 20u32;
-    let height = 20u32;
-    let seed = 42u32;
+    let map_grid = generate_default_map();
 
-    let map_grid = generate_map_with_noise(width, height, seed);
+    // 无头模式下没有渲染资源，跳过地块网格
+    if let Some(assets) = assets {
+        spawn_chunk_mesh(&mut commands, &assets, &map_grid);
+    }
 
     commands.insert_resource(map_grid);
 
-    spawn_chunk_mesh(&mut commands, &assets, width, height);
-
     commands.trigger(MapReadyEvent {
-        width,
-        height,
-        seed: seed as u64,
+        width: MAP_WIDTH,
+        height: MAP_HEIGHT,
+        seed: MAP_SEED as u64,
     });
 
     info!("MapGrid initialized with Simplex noise generation");
@@ -133,22 +159,17 @@ fn generate_map_with_noise(width: u32, height: u32, seed: u32) -> MapGrid {
 fn spawn_chunk_mesh(
     commands: &mut Commands,
     assets: &MapRenderAssets,
-    width: u32,
-    height: u32,
+    map_grid: &MapGrid,
 ) {
-    let offset_x = -(width as f32 * TILE_SIZE) / 2.0 + TILE_SIZE / 2.0;
-    let offset_y = -(height as f32 * TILE_SIZE) / 2.0 + TILE_SIZE / 2.0;
-
-    for y in 0..height {
-        for x in 0..width {
-            let world_x = offset_x + x as f32 * TILE_SIZE;
-            let world_y = offset_y + y as f32 * TILE_SIZE;
+    for y in 0..map_grid.height() {
+        for x in 0..map_grid.width() {
+            let world_pos = map_grid.grid_to_world(UVec2::new(x, y));
             let z = 0.0;
 
             commands.spawn((
                 Mesh2d(assets.tile_mesh.clone()),
                 MeshMaterial2d(assets.materials[0].clone()),
-                Transform::from_xyz(world_x, world_y, z),
+                Transform::from_xyz(world_pos.x, world_pos.y, z),
             ));
         }
     }
@@ -156,24 +177,20 @@ fn spawn_chunk_mesh(
 
 pub fn spawn_environment_decorations(
     mut commands: Commands,
-    assets: Res<MapRenderAssets>,
+    assets: Option<Res<MapRenderAssets>>,
     map_grid: Res<MapGrid>,
 ) {
-    let width = map_grid.size.x;
-    let height = map_grid.size.y;
-
-    let offset_x = -(width as f32 * TILE_SIZE) / 2.0 + TILE_SIZE / 2.0;
-    let offset_y = -(height as f32 * TILE_SIZE) / 2.0 + TILE_SIZE / 2.0;
+    // 环境装饰纯属视觉效果，无头模式下跳过
+    let Some(assets) = assets else { return; };
 
     let mut rng = rand::thread_rng();
 
-    for y in 0..height {
-        for x in 0..width {
-            let world_x = offset_x + x as f32 * TILE_SIZE;
-            let world_y = offset_y + y as f32 * TILE_SIZE;
+    for y in 0..map_grid.height() {
+        for x in 0..map_grid.width() {
+            let world_pos = map_grid.grid_to_world(UVec2::new(x, y));
 
             if let Some(tile) = map_grid.get(x, y) {
-                spawn_decoration_for_tile(&mut commands, &assets, world_x, world_y, tile.tile_type, &mut rng);
+                spawn_decoration_for_tile(&mut commands, &assets, world_pos.x, world_pos.y, tile.tile_type, &mut rng);
             }
         }
     }
@@ -1,6 +1,5 @@
 pub mod map;
 pub mod time;
-pub mod energy;
 pub mod player;
 pub mod lighting;
 pub mod plant;
@@ -27,3 +26,19 @@ pub mod achievement_events;
 pub mod achievement_generator;
 pub mod save_manager;
 pub mod save_ui;
+pub mod pathfinding;
+pub mod flow_field;
+pub mod headless;
+pub mod json_catalog;
+pub mod enemy_catalog;
+pub mod building_catalog;
+pub mod save_file;
+pub mod save_migration;
+pub mod camera;
+pub mod vitals;
+pub mod game_over;
+pub mod experience;
+pub mod skill;
+pub mod item;
+pub mod power;
+pub mod logistics;
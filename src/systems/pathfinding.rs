@@ -0,0 +1,319 @@
+use bevy::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use crate::components::building::{Building, BuildingPosition};
+use crate::components::defense::DefenseWall;
+use crate::components::pathfinding::{PathCache, NavObstacles};
+use crate::resources::world::MapGrid;
+use crate::systems::game_over::{reset_resource, restarting};
+
+/// 寻路系统插件
+pub struct PathfindingPlugin;
+
+impl Plugin for PathfindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavObstacles>()
+            .add_systems(PreUpdate, sync_nav_obstacles.run_if(in_state(crate::states::GameState::InGame)))
+            .add_systems(OnEnter(crate::states::GameState::InGame), reset_resource::<NavObstacles>.run_if(restarting));
+    }
+}
+
+/// 同步障碍物：防御墙或建筑增减、地形变化时重建障碍集合并使路径失效
+pub fn sync_nav_obstacles(
+    map_grid: Option<Res<MapGrid>>,
+    mut obstacles: ResMut<NavObstacles>,
+    wall_query: Query<&Transform, With<DefenseWall>>,
+    building_query: Query<&BuildingPosition, With<Building>>,
+    added_walls: Query<(), Added<DefenseWall>>,
+    added_buildings: Query<(), Added<Building>>,
+    mut removed_walls: RemovedComponents<DefenseWall>,
+    mut removed_buildings: RemovedComponents<Building>,
+    mut cache_query: Query<&mut PathCache>,
+) {
+    let Some(map_grid) = map_grid else { return; };
+
+    // 注意：两个 RemovedComponents 都需要读完，避免遗留到下一帧
+    let walls_removed = removed_walls.read().count() > 0;
+    let buildings_removed = removed_buildings.read().count() > 0;
+
+    let changed = map_grid.is_changed()
+        || walls_removed
+        || buildings_removed
+        || !added_walls.is_empty()
+        || !added_buildings.is_empty();
+
+    if !changed {
+        return;
+    }
+
+    obstacles.blocked.clear();
+    obstacles.blocked.extend(
+        wall_query
+            .iter()
+            .filter_map(|transform| map_grid.world_to_grid(transform.translation.truncate())),
+    );
+    obstacles.blocked.extend(
+        building_query
+            .iter()
+            .map(|position| UVec2::new(position.tile_x, position.tile_y)),
+    );
+
+    for mut cache in cache_query.iter_mut() {
+        cache.invalidate();
+    }
+}
+
+/// 开放列表节点（A* 与流场共用）
+#[derive(Clone, Copy)]
+pub(crate) struct OpenNode {
+    pub tile: UVec2,
+    pub estimated: f32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimated == other.estimated
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    // BinaryHeap 是最大堆，反转比较以优先取出代价最小的节点
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimated.total_cmp(&self.estimated)
+    }
+}
+
+/// 地块是否可通行（目标地块总是允许进入，以便攻击或交互）
+fn is_passable(map_grid: &MapGrid, obstacles: &NavObstacles, tile: UVec2, goal: UVec2) -> bool {
+    if tile == goal {
+        return map_grid.get(tile.x, tile.y).is_some();
+    }
+
+    map_grid
+        .get(tile.x, tile.y)
+        .is_some_and(|data| data.tile_type.is_walkable())
+        && !obstacles.is_blocked(tile)
+}
+
+/// 基于地形代价的 A* 寻路（四方向），返回不含起点的路径
+pub fn find_path(
+    map_grid: &MapGrid,
+    obstacles: &NavObstacles,
+    start: UVec2,
+    goal: UVec2,
+) -> Option<Vec<UVec2>> {
+    if start == goal {
+        return Some(Vec::new());
+    }
+
+    if !is_passable(map_grid, obstacles, goal, goal) {
+        return None;
+    }
+
+    let heuristic = |tile: UVec2| -> f32 {
+        (tile.x.abs_diff(goal.x) + tile.y.abs_diff(goal.y)) as f32
+    };
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<UVec2, UVec2> = HashMap::new();
+    let mut cost_so_far: HashMap<UVec2, f32> = HashMap::new();
+
+    open.push(OpenNode { tile: start, estimated: heuristic(start) });
+    cost_so_far.insert(start, 0.0);
+
+    while let Some(OpenNode { tile: current, .. }) = open.pop() {
+        if current == goal {
+            let mut path = vec![current];
+            let mut node = current;
+            while let Some(&previous) = came_from.get(&node) {
+                if previous == start {
+                    break;
+                }
+                path.push(previous);
+                node = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let current_cost = cost_so_far[&current];
+
+        for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = current.as_ivec2() + offset;
+            if !map_grid.contains(next) {
+                continue;
+            }
+            let next = next.as_uvec2();
+
+            if !is_passable(map_grid, obstacles, next, goal) {
+                continue;
+            }
+
+            let Some(tile) = map_grid.get(next.x, next.y) else { continue; };
+            // 目标地块可能不可通行（例如建筑），按基础代价计算
+            let step_cost = if tile.tile_type.is_walkable() {
+                tile.tile_type.movement_cost()
+            } else {
+                1.0
+            };
+            let new_cost = current_cost + step_cost;
+
+            if cost_so_far.get(&next).is_none_or(|&cost| new_cost < cost) {
+                cost_so_far.insert(next, new_cost);
+                came_from.insert(next, current);
+                open.push(OpenNode { tile: next, estimated: new_cost + heuristic(next) });
+            }
+        }
+    }
+
+    None
+}
+
+/// 沿缓存路径向目标移动，路径失效或目标变化时重新计算
+///
+/// 返回 false 表示目标不可达（实体保持不动）。
+pub fn step_along_path(
+    map_grid: &MapGrid,
+    obstacles: &NavObstacles,
+    cache: &mut PathCache,
+    transform: &mut Transform,
+    target: Vec2,
+    distance: f32,
+) -> bool {
+    let current = transform.translation.truncate();
+
+    // 不在地图内时直接朝目标移动，回到地图后再寻路
+    let Some(start) = map_grid.world_to_grid(current) else {
+        move_towards(transform, target, distance);
+        return true;
+    };
+    let Some(goal) = map_grid.world_to_grid(target) else {
+        return false;
+    };
+
+    if cache.dirty || cache.goal != Some(goal) {
+        match find_path(map_grid, obstacles, start, goal) {
+            Some(path) => {
+                cache.waypoints = path.into();
+                cache.unreachable = false;
+            }
+            None => {
+                cache.waypoints.clear();
+                cache.unreachable = true;
+            }
+        }
+        cache.goal = Some(goal);
+        cache.dirty = false;
+    }
+
+    if cache.unreachable {
+        return false;
+    }
+
+    let mut remaining = distance;
+    while remaining > 0.0 {
+        // 路径走完后在目标地块内直接靠近目标点
+        let Some(&waypoint) = cache.waypoints.front() else {
+            move_towards(transform, target, remaining);
+            break;
+        };
+
+        let waypoint_pos = map_grid.grid_to_world(waypoint);
+        let to_waypoint = transform.translation.truncate().distance(waypoint_pos);
+
+        if to_waypoint <= remaining {
+            transform.translation.x = waypoint_pos.x;
+            transform.translation.y = waypoint_pos.y;
+            remaining -= to_waypoint;
+            cache.waypoints.pop_front();
+        } else {
+            move_towards(transform, waypoint_pos, remaining);
+            break;
+        }
+    }
+
+    true
+}
+
+/// 朝目标点移动（不越过目标点）
+pub fn move_towards(transform: &mut Transform, target: Vec2, distance: f32) {
+    let current = transform.translation.truncate();
+    let offset = target - current;
+
+    let new_pos = if offset.length() <= distance {
+        target
+    } else {
+        current + offset.normalize_or_zero() * distance
+    };
+
+    transform.translation.x = new_pos.x;
+    transform.translation.y = new_pos.y;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::world::{TileData, TileType};
+
+    fn open_map() -> MapGrid {
+        MapGrid::new(8, 8, 0)
+    }
+
+    fn set_tile(map_grid: &mut MapGrid, x: u32, y: u32, tile_type: TileType) {
+        map_grid.set(x, y, TileData::new(tile_type));
+    }
+
+    #[test]
+    fn straight_path_excludes_the_start() {
+        let path = find_path(&open_map(), &NavObstacles::default(), UVec2::new(0, 0), UVec2::new(3, 0)).unwrap();
+
+        assert_eq!(path, vec![UVec2::new(1, 0), UVec2::new(2, 0), UVec2::new(3, 0)]);
+    }
+
+    #[test]
+    fn routes_around_a_wall() {
+        let mut map_grid = open_map();
+        for y in 0..7 {
+            set_tile(&mut map_grid, 3, y, TileType::Mountain);
+        }
+
+        let path = find_path(&map_grid, &NavObstacles::default(), UVec2::new(0, 0), UVec2::new(6, 0)).unwrap();
+
+        assert_eq!(path.last(), Some(&UVec2::new(6, 0)));
+        assert!(path.contains(&UVec2::new(3, 7)));
+        assert!(path.iter().all(|tile| map_grid[*tile].tile_type.is_walkable()));
+    }
+
+    #[test]
+    fn blocked_goal_is_still_reached() {
+        // 建筑占据的目标地块允许进入，以便攻击或交互
+        let goal = UVec2::new(4, 4);
+        let obstacles = NavObstacles { blocked: [goal].into() };
+
+        let path = find_path(&open_map(), &obstacles, UVec2::new(0, 4), goal).unwrap();
+
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(path.len(), 4);
+    }
+
+    #[test]
+    fn no_path_when_the_goal_is_enclosed() {
+        let mut map_grid = open_map();
+        let goal = UVec2::new(4, 4);
+        for (x, y) in [(3, 4), (5, 4), (4, 3), (4, 5)] {
+            set_tile(&mut map_grid, x, y, TileType::Water);
+        }
+
+        assert_eq!(find_path(&map_grid, &NavObstacles::default(), UVec2::new(0, 0), goal), None);
+        let walled_in = NavObstacles { blocked: [UVec2::new(1, 0), UVec2::new(0, 1)].into() };
+        assert_eq!(find_path(&open_map(), &walled_in, UVec2::new(0, 0), goal), None);
+    }
+}
@@ -1,16 +1,23 @@
 use bevy::prelude::*;
 use crate::components::plant::{Plant, PlantType, Growable, Plantable, Harvestable};
 use crate::components::plant_upgrade::PlantUpgrade;
-use crate::components::resource::{ResourceItem, ResourceType};
+use crate::components::inventory::Inventory;
+use crate::components::item::{ItemId, ItemRegistry};
+use crate::components::resource::ResourceItem;
 use crate::components::player::Player;
-use crate::resources::world::{WorldMap, TileType};
+use crate::components::skill::SkillBonuses;
+use crate::resources::world::{MapGrid, TileType, TILE_SIZE};
 use crate::systems::time::{GameTime, DayPhase};
+use crate::systems::game_over::{reset_resource, restarting};
+use crate::systems::headless::HeadlessConfig;
 
 pub struct PlantPlugin;
 
 impl Plugin for PlantPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<crate::components::plant_upgrade::PlantHarvestStats>();
+        app.add_systems(OnEnter(crate::states::GameState::InGame),
+            reset_resource::<crate::components::plant_upgrade::PlantHarvestStats>.run_if(restarting));
         app.add_systems(Update, (plant_seed, grow_plants, harvest_plants, plant_decay));
     }
 }
@@ -18,13 +25,14 @@ impl Plugin for PlantPlugin {
 /// 种植植物
 pub fn plant_seed(
     mut commands: Commands,
-    world_map: Option<Res<WorldMap>>,
+    map_grid: Option<Res<MapGrid>>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window>,
     cameras: Query<(&Camera, &GlobalTransform)>,
+    headless: Option<Res<HeadlessConfig>>,
 ) {
-    let world_map = match world_map {
-        Some(wm) => wm,
+    let map_grid = match map_grid {
+        Some(grid) => grid,
         None => return,
     };
 
@@ -35,77 +43,69 @@ pub fn plant_seed(
     let Ok(window) = windows.single() else { return; };
     let Ok((camera, camera_transform)) = cameras.single() else { return; };
 
-    if let Some(world_position) = window.cursor_position()
-        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok())
-        .map(|ray| ray.origin.truncate())
+    if let Some(grid_pos) = window.cursor_position()
+        .and_then(|cursor| map_grid.cursor_to_grid(camera, camera_transform, cursor))
     {
-        let tile_size = 32.0;
-        let offset_x = -(world_map.width as f32 * tile_size) / 2.0 + tile_size / 2.0;
-        let offset_y = -(world_map.height as f32 * tile_size) / 2.0 + tile_size / 2.0;
-
-        let tile_x = ((world_position.x - offset_x) / tile_size).round() as i32;
-        let tile_y = ((world_position.y - offset_y) / tile_size).round() as i32;
-
-        if tile_x >= 0 && tile_y >= 0 {
-            let tile_x = tile_x as u32;
-            let tile_y = tile_y as u32;
-
-            if let Some(tile) = world_map.get_tile(tile_x, tile_y) {
-                // 只能在草地、森林或黑暗森林上种植
-                if matches!(tile.tile_type, TileType::Grass | TileType::Forest | TileType::DarkForest) {
-                    let plant_type = match tile.tile_type {
-                        TileType::Grass => PlantType::Grass,
-                        TileType::Forest => PlantType::Bush,
-                        TileType::DarkForest => PlantType::EnergyFlower,
-                        _ => PlantType::Grass,
-                    };
-
-                    spawn_plant(&mut commands, plant_type, tile_x, tile_y, tile_size, offset_x, offset_y);
-                    info!("种植了 {:?} 在 ({}, {})", plant_type, tile_x, tile_y);
-                }
+        if let Some(tile) = map_grid.get(grid_pos.x, grid_pos.y) {
+            // 只能在草地、森林或黑暗森林上种植
+            if matches!(tile.tile_type, TileType::Grass | TileType::Forest | TileType::DarkForest) {
+                let plant_type = match tile.tile_type {
+                    TileType::Grass => PlantType::Grass,
+                    TileType::Forest => PlantType::Bush,
+                    TileType::DarkForest => PlantType::EnergyFlower,
+                    _ => PlantType::Grass,
+                };
+
+                spawn_plant(&mut commands, plant_type, map_grid.grid_to_world(grid_pos), headless.is_none());
+                info!("种植了 {:?} 在 ({}, {})", plant_type, grid_pos.x, grid_pos.y);
             }
         }
     }
 }
 
-/// 生成植物实体
-fn spawn_plant(
+/// 生成植物实体（`rendering` 为 false 时不带外观）
+pub fn spawn_plant(
     commands: &mut Commands,
     plant_type: PlantType,
-    tile_x: u32,
-    tile_y: u32,
-    tile_size: f32,
-    offset_x: f32,
-    offset_y: f32,
-) {
+    world_pos: Vec2,
+    rendering: bool,
+) -> Entity {
     let plant = Plant::new(plant_type);
     let growable = Growable::new(plant_type.base_growth_rate(), 5);
 
-    commands.spawn((
-        Sprite {
-            color: plant_type.color(),
-            custom_size: Some(Vec2::splat(tile_size * 0.6)),
-            ..default()
-        },
-        Transform::from_xyz(
-            offset_x + tile_x as f32 * tile_size,
-            offset_y + tile_y as f32 * tile_size,
-            1.0
-        ),
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 1.0),
         plant,
         growable,
         PlantUpgrade::new(),
         Plantable,
     ));
+    if rendering {
+        entity.insert(plant_sprite(plant_type));
+    }
+    entity.id()
+}
+
+/// 植物外观
+pub fn plant_sprite(plant_type: PlantType) -> Sprite {
+    Sprite {
+        color: plant_type.color(),
+        custom_size: Some(Vec2::splat(TILE_SIZE * 0.6)),
+        ..default()
+    }
 }
 
 /// 植物生长系统
 pub fn grow_plants(
     time: Res<Time>,
     game_time: Res<GameTime>,
+    skill_bonuses: Option<Res<SkillBonuses>>,
     mut commands: Commands,
     mut query: Query<(Entity, &mut Plant, &mut Growable, &mut Transform, Option<&PlantUpgrade>), Without<Player>>,
 ) {
+    // 种植技能加成
+    let skill_multiplier = 1.0 + skill_bonuses.map_or(0.0, |bonuses| bonuses.plant_growth);
+
     // 白天生长更快
     let day_multiplier = match game_time.current_phase {
         DayPhase::Day => 1.5,
@@ -126,6 +126,7 @@ pub fn grow_plants(
         } else {
             base_growth_rate * day_multiplier * plant.health
         };
+        let growth_rate = growth_rate * skill_multiplier;
         growable.growth_progress += growth_rate * time.delta_secs();
 
         // 更新植物状态
@@ -156,7 +157,8 @@ pub fn grow_plants(
 /// 收获植物
 pub fn harvest_plants(
     mut commands: Commands,
-    mut player_inventory: Query<&mut crate::components::resource::Inventory, With<crate::components::player::Player>>,
+    mut player_inventory: Query<&mut Inventory, With<Player>>,
+    registry: Res<ItemRegistry>,
     mut harvest_stats: ResMut<crate::components::plant_upgrade::PlantHarvestStats>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window>,
@@ -194,13 +196,16 @@ pub fn harvest_plants(
 
                 // 添加资源到玩家背包
                 let Ok(mut inventory) = player_inventory.single_mut() else { continue; };
-                inventory.energy += reward;
-                info!("收获 {:?} 获得 {} 能源", plant.plant_type, reward);
+                let added = inventory.add(ItemId::ENERGY, reward, &registry);
+                if added < reward {
+                    info!("背包已满，{} 能源未能放入", reward - added);
+                }
+                info!("收获 {:?} 获得 {} 能源", plant.plant_type, added);
 
                 // 生成资源掉落物
                 commands.spawn((
                     ResourceItem {
-                        resource_type: ResourceType::Energy,
+                        item: ItemId::ENERGY,
                         amount: reward,
                     },
                     Transform::from_translation(transform.translation),
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use crate::components::plant_upgrade::{PlantUpgrade, PlantVarietyTree, PlantHarvestStats, PlantLevel};
 use crate::components::plant::{Plant, PlantType};
-use crate::components::resource::Inventory;
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::ItemId;
 
 /// 植物升级系统插件
 pub struct PlantUpgradePlugin;
@@ -20,14 +21,16 @@ impl Plugin for PlantUpgradePlugin {
 /// 检查升级条件
 fn check_upgrade_conditions(
     plant_query: Query<(&Plant, &PlantUpgrade)>,
-    inventory: Res<Inventory>,
+    base_query: Query<&Inventory, With<Base>>,
     _harvest_stats: Res<PlantHarvestStats>,
 ) {
+    let Ok(inventory) = base_query.single() else { return; };
+
     for (plant, upgrade) in plant_query.iter() {
         // 检查是否可以升级
         if upgrade.can_upgrade() {
             let cost = upgrade.get_upgrade_cost();
-            if inventory.energy >= cost {
+            if inventory.count(ItemId::ENERGY) >= cost {
                 // 可以升级
                 info!("Plant {:?} can be upgraded for {} energy", plant.plant_type, cost);
             }
@@ -68,20 +71,17 @@ pub fn upgrade_plant(
         return Err("Plant is already at max level".to_string());
     }
 
-    if inventory.energy < cost {
-        return Err(format!("Not enough energy. Need {}, have {}", cost, inventory.energy));
+    let energy = inventory.count(ItemId::ENERGY);
+    if energy < cost {
+        return Err(format!("Not enough energy. Need {}, have {}", cost, energy));
     }
 
-    // 扣除能源
-    inventory.energy -= cost;
-
-    // 应用升级
+    // 应用升级，成功后扣除能源
     if upgrade.apply_upgrade() {
+        inventory.remove(ItemId::ENERGY, cost);
         info!("Plant upgraded to level {:?}", upgrade.level);
         Ok(true)
     } else {
-        // 升级失败，返还能源
-        inventory.energy += cost;
         Err("Failed to upgrade plant".to_string())
     }
 }
@@ -114,7 +114,7 @@ pub fn unlock_plant_variety(
     let current_level = PlantLevel::Level1; // TODO: 从玩家数据获取
     let harvest_count = harvest_stats.get_harvest_count(plant_type);
 
-    if !variety_tree.can_unlock(plant_type, current_level, harvest_count, inventory.energy) {
+    if !variety_tree.can_unlock(plant_type, current_level, harvest_count, inventory.count(ItemId::ENERGY)) {
         return Err(format!(
             "Cannot unlock {:?}. Need level {:?}, {} harvests, and {} energy",
             plant_type,
@@ -124,16 +124,12 @@ pub fn unlock_plant_variety(
         ));
     }
 
-    // 扣除能源
-    inventory.energy -= energy_cost;
-
-    // 解锁品种
+    // 解锁品种，成功后扣除能源
     if variety_tree.unlock(plant_type) {
+        inventory.remove(ItemId::ENERGY, energy_cost);
         info!("Unlocked plant variety: {:?}", plant_type);
         Ok(true)
     } else {
-        // 解锁失败，返还能源
-        inventory.energy += energy_cost;
         Err("Failed to unlock plant variety".to_string())
     }
 }
@@ -2,13 +2,18 @@ use bevy::prelude::*;
 use bevy::sprite_render::prelude::*;
 use bevy::mesh::Mesh2d;
 use bevy::mesh::Mesh;
-use crate::resources::world::WorldMap;
-use crate::components::player::Player;
-use crate::components::resource::Inventory;
+use crate::resources::world::MapGrid;
+use crate::components::player::{Player, PlayerStats, PLAYER_INVENTORY_SLOTS, STARTING_ENERGY};
+use crate::components::inventory::Inventory;
+use crate::components::item::{amounts, ItemId};
 use crate::components::equipment::EquipmentBar;
-use crate::components::enemy::Enemy;
+use crate::components::combat::Combat;
+use crate::components::robot::RobotType;
+use crate::systems::player_combat::PlayerCombat;
+use crate::systems::headless::HeadlessConfig;
+use crate::systems::robot::spawn_robot_entity;
+use crate::components::pathfinding::NavObstacles;
 use crate::systems::map::MapRenderAssets;
-use rand::Rng;
 
 #[derive(Resource)]
 pub struct PlayerRenderAssets {
@@ -18,95 +23,150 @@ pub struct PlayerRenderAssets {
 
 pub fn spawn_player(
     mut commands: Commands,
-    world_map: Option<Res<WorldMap>>,
-    assets: Res<PlayerRenderAssets>,
+    map_grid: Option<Res<MapGrid>>,
+    assets: Option<Res<PlayerRenderAssets>>,
+    headless: Option<Res<HeadlessConfig>>,
+    player_query: Query<(), With<Player>>,
 ) {
-    let world_map = match world_map {
-        Some(wm) => wm,
+    let map_grid = match map_grid {
+        Some(grid) => grid,
         None => return,
     };
 
-    let center_x = world_map.width / 2;
-    let center_y = world_map.height / 2;
+    // 读档回到游戏时玩家已经存在
+    if !player_query.is_empty() {
+        return;
+    }
+
+    let center_x = map_grid.width() / 2;
+    let center_y = map_grid.height() / 2;
 
-    let tile_size = 32.0;
-    let offset_x = -(world_map.width as f32 * tile_size) / 2.0 + tile_size / 2.0;
-    let offset_y = -(world_map.height as f32 * tile_size) / 2.0 + tile_size / 2.0;
+    let world_pos = map_grid.grid_to_world(UVec2::new(center_x, center_y));
+    spawn_player_entity(&mut commands, world_pos, assets.as_deref());
+    // 新开局附带一台建造机器人，放下的工地不必先按 2 才有人施工
+    spawn_robot_entity(&mut commands, RobotType::Builder, world_pos.extend(0.0), headless.is_none());
 
-    let pos_x = offset_x + center_x as f32 * tile_size;
-    let pos_y = offset_y + center_y as f32 * tile_size;
+    info!("Player spawned at ({}, {}) [World: {:.1}, {:.1}]", center_x, center_y, world_pos.x, world_pos.y);
+}
 
-    commands.spawn((
+/// 在指定世界坐标生成玩家实体（新游戏与读档共用）
+pub fn spawn_player_entity(
+    commands: &mut Commands,
+    world_pos: Vec2,
+    assets: Option<&PlayerRenderAssets>,
+) -> Entity {
+    let mut player = commands.spawn((
         Player {
             id: 0,
             name: "Administrator".into(),
             level: 1,
         },
         Inventory {
-            metal: 0,
-            soil: 0,
-            energy: 100,
+            items: amounts(&[(ItemId::ENERGY, STARTING_ENERGY)]),
+            ..Inventory::with_slots(PLAYER_INVENTORY_SLOTS)
         },
         EquipmentBar::default(),
-        Mesh2d(assets.player_mesh.clone()),
-        MeshMaterial2d(assets.player_material.clone()),
-        Transform::from_xyz(pos_x, pos_y, 1.0),
+        Combat::new(),
+        PlayerCombat::default(),
+        Transform::from_xyz(world_pos.x, world_pos.y, 1.0),
         GlobalTransform::default(),
     ));
 
-    info!("Player spawned at ({}, {}) [World: {:.1}, {:.1}]", center_x, center_y, pos_x, pos_y);
+    // 无头模式下没有渲染资源
+    if let Some(assets) = assets {
+        player.insert((
+            Mesh2d(assets.player_mesh.clone()),
+            MeshMaterial2d(assets.player_material.clone()),
+        ));
+    }
+
+    player.id()
 }
 
-pub fn move_player_randomly(
-    time: Res<Time>,
-    mut timer: Local<Timer>,
-    mut query: Query<(&Player, &mut Transform, &Inventory), Without<Enemy>>,
-    world_map: Option<Res<WorldMap>>,
-) {
-    let world_map = match world_map {
-        Some(wm) => wm,
-        None => return,
-    };
+/// 玩家操控插件（WASD/方向键移动）
+pub struct PlayerControlPlugin;
 
-    if timer.duration() == std::time::Duration::ZERO {
-        *timer = Timer::from_seconds(1.0, TimerMode::Repeating);
+impl Plugin for PlayerControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, move_player.run_if(in_state(crate::states::GameState::InGame)));
     }
+}
+
+/// 玩家碰撞盒半边长（略小于地块，便于穿过单格通道）
+const PLAYER_HALF_SIZE: f32 = 12.0;
 
-    timer.tick(time.delta());
+/// 键盘移动玩家
+///
+/// 按轴分别移动并检测碰撞，撞墙时可以沿墙滑动；速度受脚下地形影响。
+pub fn move_player(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    map_grid: Option<Res<MapGrid>>,
+    obstacles: Option<Res<NavObstacles>>,
+    mut query: Query<(&PlayerStats, &mut Transform), With<Player>>,
+) {
+    let Some(map_grid) = map_grid else { return; };
 
-    if timer.just_finished() {
-        let mut rng = rand::thread_rng();
+    let mut direction = Vec2::ZERO;
+    if keyboard.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        direction.y += 1.0;
+    }
+    if keyboard.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        direction.y -= 1.0;
+    }
+    if keyboard.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        direction.x -= 1.0;
+    }
+    if keyboard.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        direction.x += 1.0;
+    }
 
-        for (player, mut transform, inventory) in query.iter_mut() {
-            let direction = rng.gen_range(0..4);
-            let mut new_x = transform.translation.x;
-            let mut new_y = transform.translation.y;
+    // 斜向移动不应更快
+    let direction = direction.normalize_or_zero();
+    if direction == Vec2::ZERO {
+        return;
+    }
 
-            match direction {
-                0 => new_y += 1.0,
-                1 => new_y -= 1.0,
-                2 => new_x -= 1.0,
-                3 => new_x += 1.0,
-                _ => {}
+    let obstacles = obstacles.as_deref();
+    for (stats, mut transform) in query.iter_mut() {
+        let position = transform.translation.truncate();
+        let terrain_multiplier = map_grid
+            .world_to_grid(position)
+            .and_then(|tile| map_grid.get(tile.x, tile.y))
+            .map(|tile| tile.tile_type.move_speed_multiplier())
+            .unwrap_or(1.0);
+        let delta = direction * stats.move_speed * terrain_multiplier * time.delta_secs();
+
+        // 被建筑压住等已处于阻挡中时允许任意移动，以便脱困
+        let stuck = !is_position_free(&map_grid, obstacles, position);
+        let mut target = position;
+        for step in [Vec2::new(delta.x, 0.0), Vec2::new(0.0, delta.y)] {
+            if step != Vec2::ZERO && (stuck || is_position_free(&map_grid, obstacles, target + step)) {
+                target += step;
             }
+        }
 
-            if new_x >= 0.0 && new_x < world_map.width as f32 &&
-               new_y >= 0.0 && new_y < world_map.height as f32 {
-
-                let tile_size = 32.0;
-                let offset_x = -(world_map.width as f32 * tile_size) / 2.0 + tile_size / 2.0;
-                let offset_y = -(world_map.height as f32 * tile_size) / 2.0 + tile_size / 2.0;
+        transform.translation.x = target.x;
+        transform.translation.y = target.y;
+    }
+}
 
-                transform.translation.x = offset_x + new_x * tile_size;
-                transform.translation.y = offset_y + new_y * tile_size;
+/// 玩家碰撞盒覆盖的地块是否都可通行
+fn is_position_free(map_grid: &MapGrid, obstacles: Option<&NavObstacles>, position: Vec2) -> bool {
+    let min = map_grid.world_to_tile(position - Vec2::splat(PLAYER_HALF_SIZE));
+    let max = map_grid.world_to_tile(position + Vec2::splat(PLAYER_HALF_SIZE));
 
-                info!("Player {} moved to ({:.0}, {:.0}) | Energy: {}, Metal: {}, Soil: {}",
-                      player.name, new_x, new_y, inventory.energy, inventory.metal, inventory.soil);
-            } else {
-                info!("Player {} hit wall at ({:.0}, {:.0})", player.name, new_x, new_y);
+    (min.y..=max.y).all(|y| {
+        (min.x..=max.x).all(|x| {
+            let tile = IVec2::new(x, y);
+            if !map_grid.contains(tile) {
+                return false;
             }
-        }
-    }
+            let tile = tile.as_uvec2();
+            map_grid.get(tile.x, tile.y).is_some_and(|data| data.tile_type.is_walkable())
+                && !obstacles.is_some_and(|obstacles| obstacles.is_blocked(tile))
+        })
+    })
 }
 
 pub fn init_player_assets(
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use crate::components::combat::{Combat, DamageEvent, CombatEffect, CombatEffectType};
 use crate::components::enemy::Enemy;
 use crate::components::player::Player;
+use crate::resources::world::TILE_SIZE;
 
 pub struct PlayerCombatPlugin;
 
@@ -16,6 +17,16 @@ impl Plugin for PlayerCombatPlugin {
     }
 }
 
+/// 特殊攻击（空格）基础伤害倍率
+pub const BASE_SPECIAL_MULTIPLIER: f32 = 2.0;
+/// 特殊攻击基础冷却（秒）
+pub const BASE_SPECIAL_COOLDOWN: f32 = 10.0;
+/// 终极攻击（E）基础伤害倍率
+pub const BASE_ULTIMATE_MULTIPLIER: f32 = 3.0;
+/// 终极攻击基础冷却（秒）
+pub const BASE_ULTIMATE_COOLDOWN: f32 = 30.0;
+
+/// 玩家攻击技能（倍率与冷却由技能树调整）
 #[derive(Component, Clone, Debug)]
 pub struct PlayerCombat {
     pub attack_type: PlayerAttackType,
@@ -23,6 +34,10 @@ pub struct PlayerCombat {
     pub combo_timer: f32,
     pub special_attack_cooldown: f32,
     pub ultimate_cooldown: f32,
+    pub special_damage_multiplier: f32,     // 特殊攻击伤害倍率
+    pub special_cooldown_time: f32,         // 特殊攻击冷却（秒）
+    pub ultimate_damage_multiplier: f32,    // 终极攻击伤害倍率
+    pub ultimate_cooldown_time: f32,        // 终极攻击冷却（秒）
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -40,6 +55,10 @@ impl Default for PlayerCombat {
             combo_timer: 0.0,
             special_attack_cooldown: 0.0,
             ultimate_cooldown: 0.0,
+            special_damage_multiplier: BASE_SPECIAL_MULTIPLIER,
+            special_cooldown_time: BASE_SPECIAL_COOLDOWN,
+            ultimate_damage_multiplier: BASE_ULTIMATE_MULTIPLIER,
+            ultimate_cooldown_time: BASE_ULTIMATE_COOLDOWN,
         }
     }
 }
@@ -118,7 +137,8 @@ fn perform_player_attack(
     let mut nearest_distance = f32::MAX;
 
     for (enemy_entity, enemy_pos) in enemy_data.iter() {
-        let distance = player_pos.distance(*enemy_pos);
+        // 攻击范围以地块为单位
+        let distance = player_pos.distance(*enemy_pos) / TILE_SIZE;
         if distance < nearest_distance && distance <= combat.attack.attack_range {
             nearest_distance = distance;
             nearest_enemy = Some(*enemy_entity);
@@ -153,10 +173,10 @@ fn perform_special_attack(
     }
 
     let attack_range = combat.attack.attack_range * 1.5;
-    let special_damage = combat.attack.damage * 2.0;
+    let special_damage = combat.attack.damage * player_combat.special_damage_multiplier;
 
     for (enemy_entity, enemy_pos) in enemy_data.iter() {
-        let distance = player_pos.distance(*enemy_pos);
+        let distance = player_pos.distance(*enemy_pos) / TILE_SIZE;
         if distance <= attack_range {
             commands.trigger(
                 DamageEvent {
@@ -174,7 +194,7 @@ fn perform_special_attack(
         }
     }
 
-    player_combat.special_attack_cooldown = 10.0;
+    player_combat.special_attack_cooldown = player_combat.special_cooldown_time;
 }
 
 fn perform_ultimate_attack(
@@ -190,10 +210,10 @@ fn perform_ultimate_attack(
     }
 
     let attack_range = combat.attack.attack_range * 2.0;
-    let ultimate_damage = combat.attack.damage * 3.0;
+    let ultimate_damage = combat.attack.damage * player_combat.ultimate_damage_multiplier;
 
     for (enemy_entity, enemy_pos) in enemy_data.iter() {
-        let distance = player_pos.distance(*enemy_pos);
+        let distance = player_pos.distance(*enemy_pos) / TILE_SIZE;
         if distance <= attack_range {
             commands.trigger(
                 DamageEvent {
@@ -211,7 +231,7 @@ fn perform_ultimate_attack(
         }
     }
 
-    player_combat.ultimate_cooldown = 30.0;
+    player_combat.ultimate_cooldown = player_combat.ultimate_cooldown_time;
 }
 
 fn update_player_combat(
@@ -0,0 +1,481 @@
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use crate::components::building::{Building, BuildingStatus};
+use crate::components::building_catalog::BuildingCatalog;
+use crate::components::defense::DefenseTower;
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::{describe_amounts, ItemRegistry};
+use crate::components::player::Player;
+use crate::components::power::{
+    cable_cost, Battery, PowerCable, PowerGrid, PowerNetwork, PowerNode, PowerPriority, PowerProfile,
+};
+use crate::resources::world::{MapGrid, TILE_SIZE};
+use crate::systems::headless::HeadlessConfig;
+
+/// 电网插件：发电建筑、蓄电池与用电设备按连接范围或电缆组成电网，每帧结算供需
+pub struct PowerGridPlugin;
+
+impl Plugin for PowerGridPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PowerGrid>()
+            .add_systems(Update, (
+                lay_power_cable,
+                sync_building_power_nodes,
+                attach_cable_nodes,
+                update_power_networks,
+                apply_tower_power,
+                report_brownouts,
+            ).chain().run_if(in_state(crate::states::GameState::InGame)));
+    }
+}
+
+/// 建筑属性变化（建造、升级、目录热重载）后按建筑目录更新电网节点与蓄电池
+fn sync_building_power_nodes(
+    mut commands: Commands,
+    catalog: Res<BuildingCatalog>,
+    mut building_query: Query<(Entity, &Building, Option<&mut PowerNode>, Option<&mut Battery>), Changed<Building>>,
+) {
+    for (entity, building, node, battery) in building_query.iter_mut() {
+        let profile = catalog.power(building.building_type);
+
+        // 不发电也不用电的建筑不接入电网
+        if profile == PowerProfile::default() {
+            if node.is_some() {
+                commands.entity(entity).remove::<(PowerNode, Battery)>();
+            }
+            continue;
+        }
+
+        let multiplier = building.upgrade.stat_multiplier(building.level);
+        let updated = PowerNode::from_profile(&profile, multiplier);
+        match node {
+            Some(mut node) => {
+                // 保留电网结算得到的运行状态
+                *node = PowerNode {
+                    load: node.load,
+                    active: node.active,
+                    network: node.network,
+                    powered: node.powered,
+                    ..updated
+                };
+            }
+            None => {
+                commands.entity(entity).insert(updated);
+            }
+        }
+
+        let capacity = profile.battery_capacity * multiplier;
+        match battery {
+            Some(mut battery) if capacity > 0.0 => {
+                if battery.capacity != capacity {
+                    battery.capacity = capacity;
+                    battery.charge = battery.charge.min(capacity);
+                }
+            }
+            Some(_) => {
+                commands.entity(entity).remove::<Battery>();
+            }
+            None if capacity > 0.0 => {
+                commands.entity(entity).insert(Battery { charge: 0.0, capacity });
+            }
+            None => {}
+        }
+    }
+}
+
+/// 为新铺设或读档恢复的电缆补上电网节点
+fn attach_cable_nodes(
+    mut commands: Commands,
+    cable_query: Query<Entity, (With<PowerCable>, Without<PowerNode>)>,
+) {
+    for entity in cable_query.iter() {
+        commands.entity(entity).insert(PowerNode::cable());
+    }
+}
+
+/// 参与结算的节点快照
+struct NodeSnapshot {
+    entity: Entity,
+    position: Vec2,
+    connection_range: f32,
+    supply: f32,
+    demand: f32,
+    priority: PowerPriority,
+    battery: Option<Battery>,
+    powered: bool,
+}
+
+/// 划分电网并结算供需
+///
+/// 两个节点的距离不超过二者中较大的连接范围即视为连通。
+/// 每个电网先用发电量、再用蓄电池满足需求；不够时按优先级从高到低供电，
+/// 第一个得不到满足的设备及其后（同级或更低优先级）的设备全部断电。
+/// 多余的发电量为蓄电池充电。节点数量不多，每帧重新划分即可。
+fn update_power_networks(
+    time: Res<Time>,
+    mut grid: ResMut<PowerGrid>,
+    mut node_query: Query<(
+        Entity,
+        &Transform,
+        &mut PowerNode,
+        Option<&mut Battery>,
+        Option<(&Building, &BuildingStatus)>,
+    )>,
+) {
+    let delta = time.delta_secs();
+
+    let mut nodes = Vec::new();
+    for (entity, transform, mut node, battery, building) in node_query.iter_mut() {
+        // 建筑在建造完成并启动后才接入电网
+        if let Some((building, status)) = building {
+            let active = !status.is_constructing && building.is_operational;
+            if node.active != active {
+                node.active = active;
+            }
+        }
+
+        if !node.active {
+            node.network = None;
+            node.powered = false;
+            node.load = 0.0;
+            continue;
+        }
+
+        nodes.push(NodeSnapshot {
+            entity,
+            position: transform.translation.truncate(),
+            connection_range: node.connection_range,
+            supply: node.supply(),
+            demand: node.demand(),
+            priority: node.priority,
+            battery: battery.map(|battery| battery.clone()),
+            powered: true,
+        });
+    }
+
+    grid.networks.clear();
+    for members in connected_groups(&nodes) {
+        let network_id = grid.networks.len();
+        let network = balance_network(&mut nodes, &members, delta);
+
+        for &index in &members {
+            let snapshot = &nodes[index];
+            let Ok((_, _, mut node, battery, _)) = node_query.get_mut(snapshot.entity) else { continue; };
+            node.network = Some(network_id);
+            node.powered = snapshot.powered;
+            node.load = 0.0;
+            if let (Some(mut battery), Some(updated)) = (battery, &snapshot.battery) {
+                battery.charge = updated.charge;
+            }
+        }
+
+        grid.networks.push(network);
+    }
+}
+
+/// 按连接范围划分连通分量（并查集）
+fn connected_groups(nodes: &[NodeSnapshot]) -> Vec<Vec<usize>> {
+    fn find(parents: &mut [usize], index: usize) -> usize {
+        let mut root = index;
+        while parents[root] != root {
+            root = parents[root];
+        }
+        let mut current = index;
+        while parents[current] != root {
+            let next = parents[current];
+            parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    let mut parents: Vec<usize> = (0..nodes.len()).collect();
+    for i in 0..nodes.len() {
+        for j in (i + 1)..nodes.len() {
+            let range = nodes[i].connection_range.max(nodes[j].connection_range) * TILE_SIZE;
+            if nodes[i].position.distance(nodes[j].position) <= range {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                parents[a] = b;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root = vec![usize::MAX; nodes.len()];
+    for index in 0..nodes.len() {
+        let root = find(&mut parents, index);
+        if group_of_root[root] == usize::MAX {
+            group_of_root[root] = groups.len();
+            groups.push(Vec::new());
+        }
+        groups[group_of_root[root]].push(index);
+    }
+    groups
+}
+
+/// 结算一个电网：标记被断电的设备，并更新节点快照中的蓄电池电量
+fn balance_network(nodes: &mut [NodeSnapshot], members: &[usize], delta: f32) -> PowerNetwork {
+    let supply: f32 = members.iter().map(|&index| nodes[index].supply).sum();
+    let demand: f32 = members.iter().map(|&index| nodes[index].demand).sum();
+    let stored: f32 = members
+        .iter()
+        .filter_map(|&index| nodes[index].battery.as_ref())
+        .map(|battery| battery.charge)
+        .sum();
+
+    // 按优先级从高到低供电
+    let mut consumers: Vec<usize> = members
+        .iter()
+        .copied()
+        .filter(|&index| nodes[index].demand > 0.0)
+        .collect();
+    consumers.sort_by_key(|&index| (Reverse(nodes[index].priority), nodes[index].entity));
+
+    // 留一点余量，避免浮点误差导致供需恰好相等时断电
+    let mut available = supply * delta + stored + f32::EPSILON;
+    let mut consumed = 0.0;
+    let mut shed = 0;
+    for index in consumers {
+        let needed = nodes[index].demand * delta;
+        if shed == 0 && needed <= available {
+            available -= needed;
+            consumed += nodes[index].demand;
+        } else {
+            shed += 1;
+            nodes[index].powered = false;
+        }
+    }
+
+    // 发电量先供给用电设备，不足部分由蓄电池放电补上，剩余部分为蓄电池充电
+    let used = consumed * delta;
+    let generated = supply * delta;
+    let mut deficit = (used - generated).max(0.0);
+    let mut surplus = (generated - used).max(0.0);
+    for &index in members {
+        let Some(battery) = nodes[index].battery.as_mut() else { continue; };
+        if deficit > 0.0 {
+            deficit -= battery.drain(deficit);
+        } else if surplus > 0.0 {
+            surplus -= battery.fill(surplus);
+        }
+    }
+
+    let (stored, capacity) = members
+        .iter()
+        .filter_map(|&index| nodes[index].battery.as_ref())
+        .fold((0.0, 0.0), |(stored, capacity), battery| {
+            (stored + battery.charge, capacity + battery.capacity)
+        });
+
+    PowerNetwork {
+        supply,
+        demand,
+        stored,
+        capacity,
+        shed,
+    }
+}
+
+/// 防御塔只在得到供电时工作
+fn apply_tower_power(mut tower_query: Query<(&mut DefenseTower, &PowerNode)>) {
+    for (mut tower, node) in tower_query.iter_mut() {
+        if tower.is_active != node.powered {
+            tower.is_active = node.powered;
+        }
+    }
+}
+
+/// 断电设备数量变化时记录日志
+fn report_brownouts(grid: Res<PowerGrid>, mut last_shed: Local<usize>) {
+    let shed = grid.total_shed();
+    if shed == *last_shed {
+        return;
+    }
+
+    if shed > *last_shed {
+        warn!(
+            "电力不足: 发电 {:.1}/s, 需求 {:.1}/s，{} 台设备断电",
+            grid.total_supply(), grid.total_demand(), shed
+        );
+    } else if shed == 0 {
+        info!("电力恢复，所有设备正常供电");
+    }
+    *last_shed = shed;
+}
+
+/// 按 L 键在玩家所在地块铺设电缆
+fn lay_power_cable(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    map_grid: Option<Res<MapGrid>>,
+    mut base_query: Query<&mut Inventory, With<Base>>,
+    registry: Res<ItemRegistry>,
+    player_query: Query<&Transform, With<Player>>,
+    cable_query: Query<&PowerCable>,
+    headless: Option<Res<HeadlessConfig>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyL) {
+        return;
+    }
+    let Some(map_grid) = map_grid else { return; };
+    let Ok(player_transform) = player_query.single() else { return; };
+    let Some(tile) = map_grid.world_to_grid(player_transform.translation.truncate()) else { return; };
+    let Ok(mut inventory) = base_query.single_mut() else { return; };
+
+    match place_power_cable(&mut commands, tile.x, tile.y, &map_grid, &mut inventory, &registry, &cable_query, headless.is_none()) {
+        Ok(_) => info!("铺设电缆 ({}, {})", tile.x, tile.y),
+        Err(e) => info!("无法铺设电缆: {}", e),
+    }
+}
+
+/// 铺设电缆（`rendering` 为 false 时不带外观）
+pub fn place_power_cable(
+    commands: &mut Commands,
+    tile_x: u32,
+    tile_y: u32,
+    map_grid: &MapGrid,
+    inventory: &mut Inventory,
+    registry: &ItemRegistry,
+    cable_query: &Query<&PowerCable>,
+    rendering: bool,
+) -> Result<Entity, String> {
+    let Some(tile) = map_grid.get(tile_x, tile_y) else {
+        return Err("无效的位置".to_string());
+    };
+    if !tile.tile_type.is_walkable() {
+        return Err("地形不可铺设".to_string());
+    }
+    if cable_query.iter().any(|cable| cable.tile_x == tile_x && cable.tile_y == tile_y) {
+        return Err("该位置已有电缆".to_string());
+    }
+
+    let cost = cable_cost();
+    if !inventory.remove_all(&cost) {
+        return Err(format!("资源不足，需要 {}", describe_amounts(&cost, registry)));
+    }
+
+    let world_pos = map_grid.grid_to_world(UVec2::new(tile_x, tile_y));
+    let mut entity = commands.spawn((
+        Transform::from_xyz(world_pos.x, world_pos.y, 1.5),
+        PowerCable { tile_x, tile_y },
+    ));
+    if rendering {
+        entity.insert(cable_sprite());
+    }
+    Ok(entity.id())
+}
+
+/// 电缆外观
+pub fn cable_sprite() -> Sprite {
+    Sprite {
+        color: Color::srgb(0.9, 0.7, 0.1),
+        custom_size: Some(Vec2::splat(TILE_SIZE * 0.3)),
+        ..default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(index: u32, tile: (f32, f32), connection_range: f32) -> NodeSnapshot {
+        NodeSnapshot {
+            entity: Entity::from_raw_u32(index).unwrap(),
+            position: Vec2::new(tile.0, tile.1) * TILE_SIZE,
+            connection_range,
+            supply: 0.0,
+            demand: 0.0,
+            priority: PowerPriority::Normal,
+            battery: None,
+            powered: true,
+        }
+    }
+
+    fn consumer(index: u32, demand: f32, priority: PowerPriority) -> NodeSnapshot {
+        NodeSnapshot {
+            demand,
+            priority,
+            ..node(index, (0.0, 0.0), 1.5)
+        }
+    }
+
+    #[test]
+    fn groups_nodes_within_the_larger_connection_range() {
+        let nodes = [
+            node(0, (0.0, 0.0), 1.5),
+            node(1, (1.0, 0.0), 1.5),
+            node(2, (5.0, 0.0), 1.5),
+            // 与 2 相距 4 格，只在较大的连接范围内
+            node(3, (9.0, 0.0), 4.0),
+            node(4, (0.0, 3.0), 1.5),
+        ];
+
+        assert_eq!(connected_groups(&nodes), vec![vec![0, 1], vec![2, 3], vec![4]]);
+    }
+
+    #[test]
+    fn groups_are_transitive() {
+        let nodes = [
+            node(0, (0.0, 0.0), 1.5),
+            node(1, (2.0, 0.0), 1.5),
+            node(2, (1.0, 0.0), 1.5),
+        ];
+
+        assert_eq!(connected_groups(&nodes), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn brownout_sheds_from_the_first_unmet_priority_down() {
+        let mut nodes = vec![
+            consumer(0, 1.0, PowerPriority::Low),
+            consumer(1, 1.0, PowerPriority::Normal),
+            consumer(2, 2.0, PowerPriority::High),
+            consumer(3, 3.0, PowerPriority::Critical),
+            NodeSnapshot { supply: 4.0, ..node(4, (0.0, 0.0), 1.5) },
+        ];
+
+        let network = balance_network(&mut nodes, &[0, 1, 2, 3, 4], 1.0);
+
+        // 关键设备先供电；高优先级不够时，即使普通设备还能满足也一并断电
+        let powered: Vec<bool> = nodes.iter().map(|node| node.powered).collect();
+        assert_eq!(powered, vec![false, false, false, true, true]);
+        assert_eq!(network.shed, 3);
+        assert_eq!((network.supply, network.demand), (4.0, 7.0));
+    }
+
+    #[test]
+    fn same_priority_is_shed_by_entity_order() {
+        let mut nodes = vec![
+            consumer(1, 2.0, PowerPriority::High),
+            consumer(0, 2.0, PowerPriority::High),
+            NodeSnapshot { supply: 3.0, ..node(2, (0.0, 0.0), 1.5) },
+        ];
+
+        let network = balance_network(&mut nodes, &[0, 1, 2], 1.0);
+
+        let first = if nodes[0].entity < nodes[1].entity { 0 } else { 1 };
+        assert!(nodes[first].powered);
+        assert!(!nodes[1 - first].powered);
+        assert_eq!(network.shed, 1);
+    }
+
+    #[test]
+    fn batteries_cover_deficits_and_store_surplus() {
+        let mut nodes = vec![
+            consumer(0, 4.0, PowerPriority::Normal),
+            NodeSnapshot {
+                battery: Some(Battery { charge: 10.0, capacity: 50.0 }),
+                ..node(1, (0.0, 0.0), 1.5)
+            },
+        ];
+        let network = balance_network(&mut nodes, &[0, 1], 1.0);
+        assert!(nodes[0].powered);
+        assert_eq!(network.shed, 0);
+        assert_eq!(nodes[1].battery.as_ref().unwrap().charge, 6.0);
+
+        nodes[1].supply = 10.0;
+        let network = balance_network(&mut nodes, &[0, 1], 1.0);
+        assert_eq!(network.stored, 12.0);
+        assert_eq!(network.capacity, 50.0);
+    }
+}
@@ -1,12 +1,14 @@
 use bevy::prelude::*;
 use crate::components::quest::{Quest, QuestObjectiveType, QuestLog};
+use crate::systems::enemy_spawn::WaveClearedEvent;
 
 pub struct QuestEventsPlugin;
 
 impl Plugin for QuestEventsPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<QuestProgressEvent>()
-            .add_observer(handle_quest_progress);
+            .add_observer(handle_quest_progress)
+            .add_observer(handle_wave_cleared);
     }
 }
 
@@ -58,6 +60,21 @@ fn handle_quest_progress(
     }
 }
 
+/// 击退敌人波次计入防御任务
+fn handle_wave_cleared(
+    event: On<WaveClearedEvent>,
+    mut commands: Commands,
+) {
+    let wave_event = event.event();
+    debug!("波次 {} 已清除，更新防御任务", wave_event.wave);
+
+    commands.trigger(QuestProgressEvent {
+        objective_type: QuestObjectiveType::Defend,
+        target_id: Some("enemy_wave".to_string()),
+        amount: 1,
+    });
+}
+
 pub fn send_kill_event(
     mut commands: Commands,
     enemy_id: String,
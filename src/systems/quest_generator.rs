@@ -1,12 +1,13 @@
 use bevy::prelude::*;
-use crate::components::quest::{Quest, QuestType, QuestObjectiveType, QuestReward};
+use crate::components::quest::{Quest, QuestLog, QuestType, QuestObjectiveType, QuestReward};
 
 /// 任务生成系统插件
 pub struct QuestGeneratorPlugin;
 
 impl Plugin for QuestGeneratorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, initialize_main_quests)
+        // 新游戏与重新开局时生成；读档回到游戏时任务已随存档恢复
+        app.add_systems(OnEnter(crate::states::GameState::InGame), initialize_main_quests)
             .add_systems(Update, (
                 generate_daily_quests.run_if(in_state(crate::states::GameState::InGame)),
                 generate_event_quests.run_if(in_state(crate::states::GameState::InGame)),
@@ -15,7 +16,20 @@ impl Plugin for QuestGeneratorPlugin {
 }
 
 /// 初始化主线任务
-fn initialize_main_quests(mut commands: Commands) {
+fn initialize_main_quests(
+    mut commands: Commands,
+    quest_query: Query<(), With<Quest>>,
+    quest_log_query: Query<(), With<QuestLog>>,
+) {
+    // 任务日志记录进行中与已完成的任务，等级与前置条件据此判断（旧存档可能没有）
+    if quest_log_query.is_empty() {
+        commands.spawn(QuestLog::default());
+    }
+
+    if !quest_query.is_empty() {
+        return;
+    }
+
     // 主线任务1：建立基地
     let quest1 = Quest::new(
         "main_1".to_string(),
@@ -1,17 +1,24 @@
 use bevy::prelude::*;
-use crate::components::quest::{Quest, QuestLog, QuestStatus};
+use crate::components::quest::{
+    next_available_quest, AcceptQuestEvent, Quest, QuestLog, QuestStatus, TurnInQuestEvent,
+};
 use crate::components::player::Player;
+use crate::components::experience::{ExperienceGainEvent, ExperienceSource};
 
 /// 任务管理系统插件
 pub struct QuestManagerPlugin;
 
 impl Plugin for QuestManagerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (
-            update_quest_timers.run_if(in_state(crate::states::GameState::InGame)),
-            check_quest_completion.run_if(in_state(crate::states::GameState::InGame)),
-            update_quest_progress.run_if(in_state(crate::states::GameState::InGame)),
-        ));
+        app.add_message::<AcceptQuestEvent>()
+            .add_message::<TurnInQuestEvent>()
+            .add_systems(Update, (
+                update_quest_timers.run_if(in_state(crate::states::GameState::InGame)),
+                quest_input.run_if(in_state(crate::states::GameState::InGame)),
+                handle_quest_requests.run_if(in_state(crate::states::GameState::InGame)),
+                check_quest_completion.run_if(in_state(crate::states::GameState::InGame)),
+                update_quest_progress.run_if(in_state(crate::states::GameState::InGame)),
+            ).chain());
     }
 }
 
@@ -27,24 +34,74 @@ fn update_quest_timers(
     }
 }
 
-/// 检查任务完成
+/// J 键：交付目标已达成的任务，没有可交付的任务时接受下一个可接受的任务
+fn quest_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    quest_query: Query<&Quest>,
+    quest_log_query: Query<&QuestLog>,
+    player_query: Query<&Player>,
+    mut accept_events: MessageWriter<AcceptQuestEvent>,
+    mut turn_in_events: MessageWriter<TurnInQuestEvent>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyJ) {
+        return;
+    }
+    let Ok(player) = player_query.single() else { return; };
+    let Ok(quest_log) = quest_log_query.single() else { return; };
+
+    if let Some(quest) = quest_query.iter().find(|quest| quest.can_turn_in()) {
+        turn_in_events.write(TurnInQuestEvent { quest_id: quest.id.clone() });
+    } else if let Some(quest) = next_available_quest(&quest_query, player.level, &quest_log.completed_quests) {
+        accept_events.write(AcceptQuestEvent { quest_id: quest.id.clone() });
+    }
+}
+
+/// 处理玩家接受与交付任务的请求
+fn handle_quest_requests(
+    mut commands: Commands,
+    mut accept_events: MessageReader<AcceptQuestEvent>,
+    mut turn_in_events: MessageReader<TurnInQuestEvent>,
+    mut quest_query: Query<&mut Quest>,
+    mut quest_log_query: Query<&mut QuestLog>,
+    player_query: Query<&Player>,
+) {
+    let Ok(mut quest_log) = quest_log_query.single_mut() else { return; };
+    let player_level = player_query.single().map_or(1, |player| player.level);
+
+    for event in accept_events.read() {
+        let Some(mut quest) = quest_query.iter_mut().find(|quest| quest.id == event.quest_id) else {
+            warn!("接受任务失败: 任务 {} 不存在", event.quest_id);
+            continue;
+        };
+        if let Err(e) = accept_quest(&mut quest, &mut quest_log, player_level) {
+            warn!("接受任务 {} 失败: {}", event.quest_id, e);
+        }
+    }
+
+    for event in turn_in_events.read() {
+        let Some(mut quest) = quest_query.iter_mut().find(|quest| quest.id == event.quest_id) else {
+            warn!("交付任务失败: 任务 {} 不存在", event.quest_id);
+            continue;
+        };
+        if !quest.can_turn_in() {
+            warn!("交付任务 {} 失败: 目标尚未完成", event.quest_id);
+            continue;
+        }
+        complete_quest(&mut commands, &mut quest, &mut quest_log);
+    }
+}
+
+/// 自动完成目标已达成的任务
 fn check_quest_completion(
-    _commands: Commands,
-    mut quest_query: Query<(Entity, &mut Quest)>,
+    mut commands: Commands,
+    mut quest_query: Query<&mut Quest>,
     mut quest_log_query: Query<&mut QuestLog>,
 ) {
-    for (_entity, mut quest) in quest_query.iter_mut() {
-        if quest.status == QuestStatus::InProgress && quest.is_completed() {
-            if quest.auto_complete {
-                // 自动完成任务
-                quest.complete();
-
-                // 更新任务日志
-                let Ok(mut quest_log) = quest_log_query.single_mut() else { continue; };
-                quest_log.complete_quest(&quest.id);
-
-                info!("任务自动完成: {}", quest.title);
-            }
+    let Ok(mut quest_log) = quest_log_query.single_mut() else { return; };
+
+    for mut quest in quest_query.iter_mut() {
+        if quest.auto_complete && quest.status == QuestStatus::InProgress && quest.is_completed() {
+            complete_quest(&mut commands, &mut quest, &mut quest_log);
         }
     }
 }
@@ -80,46 +137,37 @@ fn update_quest_progress(
 
 /// 接受任务
 pub fn accept_quest(
-    commands: &mut Commands,
-    quest: Quest,
+    quest: &mut Quest,
     quest_log: &mut QuestLog,
     player_level: u32,
 ) -> Result<(), String> {
     // 检查是否可以接受任务
     if !quest.can_accept(player_level, &quest_log.completed_quests) {
-        return Err("无法接受该任务".to_string());
+        return Err("未满足等级或前置任务要求".to_string());
     }
 
-    // 添加任务到游戏世界
-    commands.spawn(quest.clone());
-
-    // 更新任务日志
+    quest.start();
     quest_log.add_quest(quest.id.clone());
 
     info!("接受任务: {}", quest.title);
     Ok(())
 }
 
-/// 完成任务
+/// 完成任务并发放经验（自动完成与玩家交付都经过这里）
 pub fn complete_quest(
-    _commands: &mut Commands,
-    quest_id: &str,
+    commands: &mut Commands,
+    quest: &mut Quest,
     quest_log: &mut QuestLog,
-    quest_stats: &mut crate::components::quest::QuestStats,
-) -> Result<(), String> {
-    // 检查任务是否在进行中
-    if !quest_log.is_quest_active(quest_id) {
-        return Err("任务未在进行中".to_string());
-    }
-
-    // 更新任务日志
-    quest_log.complete_quest(quest_id);
+) {
+    quest.complete();
+    quest_log.complete_quest(&quest.id);
 
-    // 更新任务统计
-    quest_stats.total_quests_completed += 1;
+    commands.trigger(ExperienceGainEvent {
+        amount: quest.rewards.experience,
+        source: ExperienceSource::Quest,
+    });
 
-    info!("完成任务: {}", quest_id);
-    Ok(())
+    info!("完成任务: {}", quest.title);
 }
 
 /// 放弃任务
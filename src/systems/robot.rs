@@ -1,16 +1,38 @@
 use bevy::prelude::*;
-use crate::components::robot::{Robot, RobotType, RobotTask, RobotAI, RobotInventory};
+use crate::components::robot::{
+    DefenderOrder, Robot, RobotAI, RobotHealth, RobotTask, RobotType, DEFENDER_LEASH_RADIUS, DEFENDER_MAX_HEALTH,
+    DEFENDER_RETREAT_ENERGY, DEFENDER_STATION_RADIUS,
+};
 use crate::components::plant::{Plant, Plantable, Harvestable};
-use crate::components::resource::{ResourceType, Inventory};
+use crate::components::combat::{AttackAttributes, Combat, DamageEvent, DamageType, DefenseAttributes};
+use crate::components::enemy::Enemy;
+use crate::components::building::{repair_cost, Building, BuildingStatus, ConstructionSite, REPAIR_AMOUNT};
+use crate::components::defense::DefenseWall;
+use crate::components::inventory::{Base, Inventory, InventoryOwner, TransferItemsEvent};
+use crate::components::item::{amounts, describe_amounts, ItemAmounts, ItemId, ItemRegistry};
+use crate::components::logistics::{Courier, Delivery, DeliveryStage, JobBoard, BASE_DOCK_RADIUS, DOCK_RADIUS};
 use crate::components::player::Player;
-use crate::resources::world::WorldMap;
+use crate::components::power::{PowerNode, FALLBACK_CHARGE_RATE};
+use crate::components::skill::SkillBonuses;
+use crate::components::pathfinding::{PathCache, NavObstacles};
+use crate::resources::world::{MapGrid, TILE_SIZE};
+use crate::systems::defense_wall::repair_defense_wall;
+use crate::systems::headless::HeadlessConfig;
+use crate::systems::pathfinding::step_along_path;
 use crate::systems::time::{GameTime, DayPhase};
 
 pub struct RobotPlugin;
 
 impl Plugin for RobotPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (spawn_robot, robot_ai_system));
+        // 搬运任务板由 LogisticsPlugin 提供
+        app.add_systems(Update, (
+                spawn_robot,
+                command_defenders,
+                builder_robot_system,
+                defender_robot_system,
+                robot_ai_system,
+            ).chain());
     }
 }
 
@@ -18,32 +40,35 @@ impl Plugin for RobotPlugin {
 pub fn spawn_robot(
     mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    headless: Option<Res<HeadlessConfig>>,
     player_query: Query<&Transform, (With<Player>, Without<Robot>)>,
 ) {
+    let rendering = headless.is_none();
     if keyboard_input.just_pressed(KeyCode::Digit1) {
         let Ok(player_transform) = player_query.single() else { return; };
         let position = player_transform.translation;
-        spawn_robot_entity(&mut commands, RobotType::Harvester, position);
+        spawn_robot_entity(&mut commands, RobotType::Harvester, position, rendering);
         info!("生成了采集机器人");
     } else if keyboard_input.just_pressed(KeyCode::Digit2) {
         let Ok(player_transform) = player_query.single() else { return; };
         let position = player_transform.translation;
-        spawn_robot_entity(&mut commands, RobotType::Builder, position);
+        spawn_robot_entity(&mut commands, RobotType::Builder, position, rendering);
         info!("生成了建造机器人");
     } else if keyboard_input.just_pressed(KeyCode::Digit3) {
         let Ok(player_transform) = player_query.single() else { return; };
         let position = player_transform.translation;
-        spawn_robot_entity(&mut commands, RobotType::Defender, position);
+        spawn_robot_entity(&mut commands, RobotType::Defender, position, rendering);
         info!("生成了防御机器人");
     } else if keyboard_input.just_pressed(KeyCode::Digit4) {
         let Ok(player_transform) = player_query.single() else { return; };
         let position = player_transform.translation;
-        spawn_robot_entity(&mut commands, RobotType::Scout, position);
+        spawn_robot_entity(&mut commands, RobotType::Scout, position, rendering);
         info!("生成了侦察机器人");
     }
 }
 
-fn spawn_robot_entity(commands: &mut Commands, robot_type: RobotType, position: Vec3) {
+/// 生成机器人实体（`rendering` 为 false 时不带外观，供无头模式使用）
+pub fn spawn_robot_entity(commands: &mut Commands, robot_type: RobotType, position: Vec3, rendering: bool) -> Entity {
     let robot = Robot::new(robot_type);
     let ai = RobotAI::default();
     let inventory_capacity = match robot_type {
@@ -53,61 +78,146 @@ fn spawn_robot_entity(commands: &mut Commands, robot_type: RobotType, position:
         RobotType::Scout => 10,
     };
 
-    commands.spawn((
-        Sprite {
-            color: robot_type.color(),
-            custom_size: Some(Vec2::splat(24.0)),
-            ..default()
-        },
+    let mut entity_commands = commands.spawn((
         Transform::from_xyz(position.x, position.y, 2.0),
         robot,
         ai,
-        RobotInventory::new(inventory_capacity),
+        Inventory::with_capacity(inventory_capacity),
+        PathCache::default(),
     ));
+    if rendering {
+        entity_commands.insert(robot_sprite(robot_type));
+    }
+    if robot_type == RobotType::Defender {
+        entity_commands.insert((defender_combat(), RobotHealth::new(DEFENDER_MAX_HEALTH), DefenderOrder::default()));
+    }
+    entity_commands.id()
+}
+
+/// 防御机器人的战斗属性（远程能量射击，带少量物理抗性）
+pub fn defender_combat() -> Combat {
+    Combat {
+        attack: AttackAttributes {
+            damage: 12.0,
+            damage_type: DamageType::Energy,
+            attack_range: 3.0,
+            attack_speed: 1.5,
+            ..default()
+        },
+        defense: DefenseAttributes {
+            physical_resistance: 0.2,
+            ..default()
+        },
+        ..Combat::new()
+    }
+}
+
+/// 切换防御机器人的指令：护送玩家的改为守卫玩家当前位置，守卫的改回护送
+pub fn command_defenders(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player_query: Query<&Transform, (With<Player>, Without<Robot>)>,
+    mut defender_query: Query<&mut DefenderOrder>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyG) || defender_query.is_empty() {
+        return;
+    }
+    let Ok(player_transform) = player_query.single() else { return; };
+
+    let escorting = defender_query.iter().any(|order| *order == DefenderOrder::Escort);
+    let new_order = if escorting {
+        DefenderOrder::Guard(player_transform.translation.truncate())
+    } else {
+        DefenderOrder::Escort
+    };
+    for mut order in defender_query.iter_mut() {
+        *order = new_order;
+    }
+    info!("防御机器人指令: {:?}", new_order);
+}
+
+/// 机器人外观
+pub fn robot_sprite(robot_type: RobotType) -> Sprite {
+    Sprite {
+        color: robot_type.color(),
+        custom_size: Some(Vec2::splat(24.0)),
+        ..default()
+    }
 }
 
 /// 机器人 AI 系统
 pub fn robot_ai_system(
     time: Res<Time>,
     game_time: Res<GameTime>,
-    _world_map: Option<Res<WorldMap>>,
-    mut query: Query<(Entity, &mut Robot, &RobotAI, &mut Transform, &mut RobotInventory), Without<Player>>,
+    map_grid: Option<Res<MapGrid>>,
+    obstacles: Res<NavObstacles>,
+    mut query: Query<(Entity, &mut Robot, &RobotAI, &mut Transform, &mut Inventory, &mut PathCache, &mut Courier), Without<Player>>,
     plant_query: Query<(Entity, &Plant, &Transform), (With<Plantable>, Without<Harvestable>, Without<Robot>)>,
     player_query: Query<&Transform, (With<Player>, Without<Robot>)>,
-    mut player_inventory: ResMut<Inventory>,
+    mut charger_query: Query<(Entity, &Transform, &mut PowerNode), Without<Robot>>,
+    building_query: Query<&Transform, (With<Building>, Without<Robot>)>,
+    mut board: ResMut<JobBoard>,
+    mut transfer_events: MessageWriter<TransferItemsEvent>,
+    registry: Res<ItemRegistry>,
+    skill_bonuses: Option<Res<SkillBonuses>>,
     mut commands: Commands,
 ) {
+    let Some(map_grid) = map_grid else { return; };
+
+    // 机器人技能降低能耗
+    let energy_multiplier = 1.0 - skill_bonuses.map_or(0.0, |bonuses| bonuses.robot_energy_consumption);
+
     // 夜晚机器人效率降低
     let night_multiplier = match game_time.current_phase {
         DayPhase::Night => 0.5,
         _ => 1.0,
     };
 
-    for (_entity, mut robot, ai, mut transform, mut inventory) in query.iter_mut() {
+    for (entity, mut robot, ai, mut transform, mut inventory, mut path, mut courier) in query.iter_mut() {
         // 消耗能量
-        let energy_cost = robot.robot_type.energy_consumption() * time.delta_secs();
+        let energy_cost = robot.robot_type.energy_consumption() * energy_multiplier * time.delta_secs();
         robot.consume_energy(energy_cost);
 
-        // 如果能量不足，返回基地
-        if robot.energy <= 0.0 {
+        // 如果能量不足，放下手头的运送去充电
+        if robot.energy <= 0.0 && robot.current_task != RobotTask::ReturnToBase {
+            abort_delivery(&mut courier, &mut board);
             robot.current_task = RobotTask::ReturnToBase;
+            path.clear();
         }
 
+        let position = transform.translation.truncate();
+
         match robot.current_task {
             RobotTask::Idle => {
                 // 根据机器人类型分配任务
                 match robot.robot_type {
-                    RobotType::Harvester => {
-                        // 寻找可采集的植物
-                        if let Some((_plant_entity, plant_pos)) = find_nearest_plant(&plant_query, transform.translation, ai.detection_radius) {
+                    RobotType::Harvester | RobotType::Builder => {
+                        // 采集机器人优先寻找可采集的植物
+                        let plant = if robot.robot_type == RobotType::Harvester && !inventory.is_full(&registry) {
+                            find_nearest_plant(&plant_query, transform.translation, ai.detection_radius * TILE_SIZE)
+                        } else {
+                            None
+                        };
+
+                        if let Some((_plant_entity, plant_pos)) = plant {
                             robot.target_position = Some(plant_pos);
                             robot.current_task = RobotTask::Harvest;
+                        } else if !inventory.is_empty() {
+                            // 先把身上的货物卸到最近的仓库，没有仓库时送回基地
+                            courier.delivery = Some(unload_cargo(&board, position, &inventory));
+                            robot.current_task = RobotTask::Haul;
+                        } else {
+                            // 承接任务板上的搬运任务
+                            let space = inventory.capacity.map_or(u32::MAX, |capacity| capacity.saturating_sub(inventory.total()));
+                            if let Some(job) = board.claim(entity, position, space) {
+                                courier.delivery = Some(Delivery::from_job(&job));
+                                robot.current_task = RobotTask::Haul;
+                            }
                         }
                     }
                     RobotType::Scout => {
                         // 随机巡逻
                         if robot.target_position.is_none() {
-                            let random_pos = generate_random_position(transform.translation, ai.patrol_radius);
+                            let random_pos = generate_random_position(transform.translation, ai.patrol_radius * TILE_SIZE);
                             robot.target_position = Some(random_pos);
                             robot.current_task = RobotTask::Patrol;
                         }
@@ -118,62 +228,159 @@ pub fn robot_ai_system(
             RobotTask::Harvest => {
                 // 移动到目标位置
                 if let Some(target) = robot.target_position {
-                    move_towards_target(&mut transform, target, robot.robot_type.movement_speed() * night_multiplier * time.delta_secs());
+                    let speed = travel_distance(&robot, night_multiplier, &time);
+                    if !step_along_path(&map_grid, &obstacles, &mut path, &mut transform, target, speed) {
+                        // 目标不可达，放弃任务
+                        robot.current_task = RobotTask::Idle;
+                        robot.target_position = None;
+                        path.clear();
+                        continue;
+                    }
 
                     // 检查是否到达目标
                     if transform.translation.truncate().distance(target) < 32.0 {
                         // 采集植物
+                        let mut harvested = true;
                         for (plant_entity, plant, plant_transform) in plant_query.iter() {
                             if plant_transform.translation.truncate().distance(target) < 32.0 {
                                 if plant.is_harvestable() {
                                     let reward = plant.calculate_harvest_reward();
-                                    if inventory.add(reward, ResourceType::Energy) {
+                                    let space = inventory.space_for(ItemId::ENERGY, &registry);
+                                    if reward > space && !inventory.is_empty() {
+                                        // 货舱装不下整份收获，视同装满，先去卸货
+                                        harvested = false;
+                                    } else {
+                                        // 空货舱仍装不下时只收能装下的部分
+                                        let added = inventory.add(ItemId::ENERGY, reward, &registry);
                                         commands.entity(plant_entity).despawn();
-                                        info!("机器人采集了 {:?}，获得 {} 能源", plant.plant_type, reward);
+                                        info!("机器人采集了 {:?}，获得 {} 能源", plant.plant_type, added);
                                     }
                                 }
                                 break;
                             }
                         }
 
-                        // 如果背包已满，返回基地
-                        if inventory.is_full() {
-                            robot.current_task = RobotTask::ReturnToBase;
-                        } else {
+                        robot.target_position = None;
+                        path.clear();
+                        if harvested {
+                            // 空闲时再决定继续采集还是卸货
                             robot.current_task = RobotTask::Idle;
-                            robot.target_position = None;
+                        } else {
+                            courier.delivery = Some(unload_cargo(&board, position, &inventory));
+                            robot.current_task = RobotTask::Haul;
                         }
                     }
                 }
             }
             RobotTask::Patrol => {
                 if let Some(target) = robot.target_position {
-                    move_towards_target(&mut transform, target, robot.robot_type.movement_speed() * night_multiplier * time.delta_secs());
+                    let speed = travel_distance(&robot, night_multiplier, &time);
+                    let reachable = step_along_path(&map_grid, &obstacles, &mut path, &mut transform, target, speed);
 
-                    if transform.translation.truncate().distance(target) < 10.0 {
+                    if !reachable || transform.translation.truncate().distance(target) < 10.0 {
                         robot.current_task = RobotTask::Idle;
                         robot.target_position = None;
+                        path.clear();
+                    }
+                }
+            }
+            RobotTask::Haul => {
+                // 读档后运送状态不保留，重新接单
+                let Some(delivery) = courier.delivery.as_mut() else {
+                    robot.current_task = RobotTask::Idle;
+                    path.clear();
+                    continue;
+                };
+
+                // 当前阶段的目的地；建筑已被拆除或玩家不在时放弃
+                let stop = match delivery.stage {
+                    DeliveryStage::Pickup => delivery.from,
+                    DeliveryStage::Dropoff => Some(delivery.to),
+                };
+                let dock = match stop {
+                    Some(InventoryOwner::Entity(building)) => building_query
+                        .get(building)
+                        .ok()
+                        .map(|building_transform| (building_transform.translation.truncate(), DOCK_RADIUS)),
+                    Some(InventoryOwner::Base) => player_query
+                        .single()
+                        .ok()
+                        .map(|player_transform| (player_transform.translation.truncate(), BASE_DOCK_RADIUS)),
+                    None => None,
+                };
+                let Some((dock_pos, dock_radius)) = dock else {
+                    abort_delivery(&mut courier, &mut board);
+                    robot.current_task = RobotTask::Idle;
+                    path.clear();
+                    continue;
+                };
+
+                let speed = travel_distance(&robot, night_multiplier, &time);
+                if !step_along_path(&map_grid, &obstacles, &mut path, &mut transform, dock_pos, speed) {
+                    abort_delivery(&mut courier, &mut board);
+                    robot.current_task = RobotTask::Idle;
+                    path.clear();
+                    continue;
+                }
+
+                if transform.translation.truncate().distance(dock_pos) < dock_radius {
+                    match delivery.stage {
+                        DeliveryStage::Pickup => {
+                            if let Some(from) = delivery.from {
+                                transfer_events.write(TransferItemsEvent {
+                                    from,
+                                    to: InventoryOwner::Entity(entity),
+                                    items: delivery.items.clone(),
+                                });
+                            }
+                            delivery.stage = DeliveryStage::Dropoff;
+                        }
+                        DeliveryStage::Dropoff => {
+                            // 送不下的部分留在货舱，空闲时再卸到别处
+                            transfer_events.write(TransferItemsEvent {
+                                from: InventoryOwner::Entity(entity),
+                                to: delivery.to,
+                                items: delivery.items.clone(),
+                            });
+                            debug!("机器人送货 {:?}: {}", delivery.to, describe_amounts(&delivery.items, &registry));
+                            if let Some(job) = delivery.job {
+                                board.finish(job);
+                            }
+                            courier.delivery = None;
+                            robot.current_task = RobotTask::Idle;
+                        }
                     }
+                    path.clear();
                 }
             }
             RobotTask::ReturnToBase => {
-                // 返回玩家位置
-                let Ok(player_transform) = player_query.single() else { continue; };
-                let player_pos = player_transform.translation.truncate();
-                move_towards_target(&mut transform, player_pos, robot.robot_type.movement_speed() * time.delta_secs());
-
-                if transform.translation.truncate().distance(player_pos) < 50.0 {
-                    // 卸载资源
-                    let amount = inventory.clear();
-                    if amount > 0 {
-                        player_inventory.energy += amount;
-                        info!("机器人返回基地，卸载了 {} 能源", amount);
+                // 优先前往最近的供电充电桩，没有时返回玩家位置
+                let charger = find_nearest_charger(&charger_query, transform.translation);
+                let (dock_pos, dock_radius) = match charger {
+                    Some((_, charger_pos)) => (charger_pos, DOCK_RADIUS),
+                    None => {
+                        let Ok(player_transform) = player_query.single() else { continue; };
+                        (player_transform.translation.truncate(), BASE_DOCK_RADIUS)
                     }
+                };
+                step_along_path(&map_grid, &obstacles, &mut path, &mut transform, dock_pos, travel_distance(&robot, 1.0, &time));
 
-                    // 充能
-                    robot.energy = robot.max_energy;
-                    robot.current_task = RobotTask::Idle;
-                    robot.target_position = None;
+                if transform.translation.truncate().distance(dock_pos) < dock_radius {
+                    // 充电桩从电网取电充能，计入充电桩下一次结算的负载
+                    let charge_rate = match charger.and_then(|(entity, _)| charger_query.get_mut(entity).ok()) {
+                        Some((_, _, mut node)) => {
+                            node.load += node.charge_rate;
+                            node.charge_rate
+                        }
+                        None => FALLBACK_CHARGE_RATE,
+                    };
+                    robot.recharge(charge_rate * time.delta_secs());
+
+                    if robot.energy >= robot.max_energy {
+                        robot.current_task = RobotTask::Idle;
+                        robot.target_position = None;
+                        path.clear();
+                    }
                 }
             }
             _ => {}
@@ -184,6 +391,317 @@ pub fn robot_ai_system(
     }
 }
 
+/// 建造机器人施工与维修
+///
+/// 空闲的建造机器人优先前往材料已齐的工地施工，其次修理受损的建筑和防御墙（花费从基地库存扣除），
+/// 都没有时由 `robot_ai_system` 安排搬运。每个在场的机器人都会推进施工进度，多台同时施工更快。
+pub fn builder_robot_system(
+    time: Res<Time>,
+    game_time: Res<GameTime>,
+    map_grid: Option<Res<MapGrid>>,
+    obstacles: Res<NavObstacles>,
+    mut base_query: Query<&mut Inventory, (With<Base>, Without<Robot>, Without<Building>)>,
+    mut query: Query<(&mut Robot, &mut Transform, &Inventory, &mut PathCache, &Courier), Without<Player>>,
+    mut building_query: Query<
+        (&Building, &mut BuildingStatus, &Transform, Option<&ConstructionSite>, Option<&Inventory>),
+        Without<Robot>,
+    >,
+    mut wall_query: Query<(&mut DefenseWall, &Transform), (Without<Robot>, Without<Building>)>,
+    registry: Res<ItemRegistry>,
+) {
+    let Some(map_grid) = map_grid else { return; };
+    let Ok(mut base_inventory) = base_query.single_mut() else { return; };
+
+    // 夜晚机器人效率降低
+    let night_multiplier = match game_time.current_phase {
+        DayPhase::Night => 0.5,
+        _ => 1.0,
+    };
+
+    for (mut robot, mut transform, inventory, mut path, courier) in query.iter_mut() {
+        if robot.robot_type != RobotType::Builder {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+
+        match robot.current_task {
+            RobotTask::Idle => {
+                // 身上有货或正在运送时交给搬运逻辑
+                if !inventory.is_empty() || courier.delivery.is_some() {
+                    continue;
+                }
+
+                let site = building_query
+                    .iter()
+                    .filter(|(_, status, _, site, site_inventory)| {
+                        status.is_constructing && site.is_none_or(|site| site.is_supplied(*site_inventory))
+                    })
+                    .map(|(_, _, building_transform, _, _)| building_transform.translation.truncate())
+                    .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+                if let Some(site_pos) = site {
+                    robot.target_position = Some(site_pos);
+                    robot.current_task = RobotTask::Build;
+                    path.clear();
+                    continue;
+                }
+
+                // 只接付得起一次修理的活
+                let amount = REPAIR_AMOUNT * robot.efficiency;
+                let damaged_buildings = building_query
+                    .iter()
+                    .filter(|(building, status, ..)| {
+                        !status.is_constructing
+                            && status.health < status.max_health
+                            && base_inventory.has_all(&repair_cost(&building.full_repair_cost(), amount, status.max_health))
+                    })
+                    .map(|(_, _, building_transform, _, _)| building_transform.translation.truncate());
+                let damaged_walls = wall_query
+                    .iter()
+                    .filter(|(wall, _)| {
+                        !wall.is_destroyed()
+                            && wall.health < wall.max_health
+                            && base_inventory.has_all(&repair_cost(&wall_repair_cost(wall), amount, wall.max_health))
+                    })
+                    .map(|(_, wall_transform)| wall_transform.translation.truncate());
+                let damaged = damaged_buildings
+                    .chain(damaged_walls)
+                    .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+                if let Some(damaged_pos) = damaged {
+                    robot.target_position = Some(damaged_pos);
+                    robot.current_task = RobotTask::Repair;
+                    robot.task_timer.reset();
+                    path.clear();
+                }
+            }
+            RobotTask::Build => {
+                let Some(target) = robot.target_position else {
+                    robot.current_task = RobotTask::Idle;
+                    continue;
+                };
+
+                // 工地已完工、被拆除或材料被取走时收工
+                let site = building_query
+                    .iter_mut()
+                    .find(|(_, status, building_transform, _, _)| {
+                        status.is_constructing && building_transform.translation.truncate().distance(target) < 1.0
+                    })
+                    .filter(|(_, _, _, site, site_inventory)| site.is_none_or(|site| site.is_supplied(*site_inventory)));
+                let Some((building, mut status, ..)) = site else {
+                    robot.current_task = RobotTask::Idle;
+                    robot.target_position = None;
+                    path.clear();
+                    continue;
+                };
+
+                let speed = travel_distance(&robot, night_multiplier, &time);
+                if !step_along_path(&map_grid, &obstacles, &mut path, &mut transform, target, speed) {
+                    robot.current_task = RobotTask::Idle;
+                    robot.target_position = None;
+                    path.clear();
+                    continue;
+                }
+
+                if transform.translation.truncate().distance(target) < DOCK_RADIUS {
+                    // 进度满后由 update_construction 完工
+                    let work = time.delta_secs() * robot.efficiency * night_multiplier / building.stats.build_time;
+                    status.construction_progress = (status.construction_progress + work).min(1.0);
+                }
+            }
+            RobotTask::Repair => {
+                let Some(target) = robot.target_position else {
+                    robot.current_task = RobotTask::Idle;
+                    continue;
+                };
+
+                let speed = travel_distance(&robot, night_multiplier, &time);
+                let reachable = step_along_path(&map_grid, &obstacles, &mut path, &mut transform, target, speed);
+                let in_range = transform.translation.truncate().distance(target) < DOCK_RADIUS;
+
+                // 每次计时结束修理一下
+                let mut finished = !reachable;
+                if reachable && in_range && robot.task_timer.is_finished() {
+                    robot.task_timer.reset();
+                    let amount = REPAIR_AMOUNT * robot.efficiency * night_multiplier;
+
+                    let building = building_query
+                        .iter_mut()
+                        .find(|(_, status, building_transform, _, _)| {
+                            !status.is_constructing && building_transform.translation.truncate().distance(target) < 1.0
+                        });
+                    let wall = wall_query
+                        .iter_mut()
+                        .find(|(_, wall_transform)| wall_transform.translation.truncate().distance(target) < 1.0);
+
+                    finished = match (building, wall) {
+                        (Some((building, mut status, ..)), _) => {
+                            let amount = amount.min(status.max_health - status.health);
+                            let cost = repair_cost(&building.full_repair_cost(), amount, status.max_health);
+                            if amount > 0.0 && base_inventory.remove_all(&cost) {
+                                status.health = (status.health + amount).min(status.max_health);
+                                status.is_damaged = status.health < status.max_health;
+                                debug!("机器人修理 {:?}，花费 {}", building.building_type, describe_amounts(&cost, &registry));
+                            } else if amount > 0.0 {
+                                info!("修理 {:?} 资源不足，需要 {}", building.building_type, describe_amounts(&cost, &registry));
+                            }
+                            !status.is_damaged || !base_inventory.has_all(&cost)
+                        }
+                        (None, Some((mut wall, _))) if !wall.is_destroyed() => {
+                            let amount = amount.min(wall.max_health - wall.health);
+                            let cost = repair_cost(&wall_repair_cost(&wall), amount, wall.max_health);
+                            if amount > 0.0 && base_inventory.remove_all(&cost) {
+                                repair_defense_wall(&mut wall, amount);
+                            } else if amount > 0.0 {
+                                info!("修理防御墙资源不足，需要 {}", describe_amounts(&cost, &registry));
+                            }
+                            wall.health >= wall.max_health || !base_inventory.has_all(&cost)
+                        }
+                        // 目标已不存在
+                        _ => true,
+                    };
+                }
+
+                if finished {
+                    robot.current_task = RobotTask::Idle;
+                    robot.target_position = None;
+                    path.clear();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 防御机器人守卫与交战
+///
+/// 防御机器人守在指定地点或跟随玩家，与侦测范围内的敌人交战（追击不超出守卫范围），
+/// 伤害通过 `DamageEvent` 结算。能量不足时脱离战斗，交给 `robot_ai_system` 返回充电。
+pub fn defender_robot_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_time: Res<GameTime>,
+    map_grid: Option<Res<MapGrid>>,
+    obstacles: Res<NavObstacles>,
+    mut query: Query<
+        (Entity, &mut Robot, &RobotAI, &DefenderOrder, &mut Combat, &mut Transform, &mut PathCache),
+        Without<Player>,
+    >,
+    enemy_query: Query<(Entity, &Enemy, &Transform), Without<Robot>>,
+    player_query: Query<&Transform, (With<Player>, Without<Robot>)>,
+) {
+    let Some(map_grid) = map_grid else { return; };
+
+    // 夜晚机器人效率降低
+    let night_multiplier = match game_time.current_phase {
+        DayPhase::Night => 0.5,
+        _ => 1.0,
+    };
+    let player_pos = player_query.single().ok().map(|player_transform| player_transform.translation.truncate());
+
+    for (entity, mut robot, ai, order, mut combat, mut transform, mut path) in query.iter_mut() {
+        if robot.current_task == RobotTask::ReturnToBase {
+            continue;
+        }
+
+        // 能量不足时撤回充电
+        if robot.energy < robot.max_energy * DEFENDER_RETREAT_ENERGY {
+            combat.target = None;
+            combat.is_attacking = false;
+            robot.current_task = RobotTask::ReturnToBase;
+            robot.target_position = None;
+            path.clear();
+            continue;
+        }
+
+        let anchor = match *order {
+            DefenderOrder::Guard(point) => Some(point),
+            DefenderOrder::Escort => player_pos,
+        };
+        let Some(anchor) = anchor else { continue; };
+        let position = transform.translation.truncate();
+
+        // 保持当前目标，目标死亡或跑出守卫范围时另找侦测范围内最近的敌人
+        let in_reach = |enemy: &Enemy, enemy_pos: Vec2| !enemy.is_dead() && enemy_pos.distance(anchor) <= DEFENDER_LEASH_RADIUS * TILE_SIZE;
+        let target = combat
+            .target
+            .and_then(|target| enemy_query.get(target).ok())
+            .map(|(enemy_entity, enemy, enemy_transform)| (enemy_entity, enemy, enemy_transform.translation.truncate()))
+            .filter(|&(_, enemy, enemy_pos)| in_reach(enemy, enemy_pos))
+            .or_else(|| {
+                enemy_query
+                    .iter()
+                    .map(|(enemy_entity, enemy, enemy_transform)| (enemy_entity, enemy, enemy_transform.translation.truncate()))
+                    .filter(|&(_, enemy, enemy_pos)| in_reach(enemy, enemy_pos) && enemy_pos.distance(position) <= ai.detection_radius * TILE_SIZE)
+                    .min_by(|a, b| a.2.distance(position).total_cmp(&b.2.distance(position)))
+            })
+            .map(|(enemy_entity, _, enemy_pos)| (enemy_entity, enemy_pos));
+        combat.target = target.map(|(enemy_entity, _)| enemy_entity);
+
+        let speed = travel_distance(&robot, night_multiplier, &time);
+        match target {
+            Some((enemy_entity, enemy_pos)) => {
+                robot.current_task = RobotTask::Engage;
+                robot.target_position = Some(enemy_pos);
+
+                // 攻击范围以地块为单位
+                if position.distance(enemy_pos) / TILE_SIZE > combat.attack.attack_range {
+                    combat.is_attacking = false;
+                    step_along_path(&map_grid, &obstacles, &mut path, &mut transform, enemy_pos, speed);
+                } else if combat.can_attack() {
+                    let is_critical = combat.is_critical();
+                    let damage = if is_critical { combat.get_critical_damage() } else { combat.attack.damage };
+                    commands.trigger(DamageEvent {
+                        source: entity,
+                        target: enemy_entity,
+                        damage,
+                        damage_type: combat.attack.damage_type,
+                        is_critical,
+                    });
+                    combat.attack_cooldown = 1.0 / combat.attack.attack_speed;
+                    combat.is_attacking = true;
+                }
+            }
+            None => {
+                // 没有敌人时回到守卫点或玩家身边待命
+                combat.is_attacking = false;
+                if robot.current_task == RobotTask::Engage {
+                    robot.current_task = RobotTask::Idle;
+                    robot.target_position = None;
+                    path.clear();
+                }
+                if position.distance(anchor) > DEFENDER_STATION_RADIUS * TILE_SIZE {
+                    step_along_path(&map_grid, &obstacles, &mut path, &mut transform, anchor, speed);
+                }
+            }
+        }
+    }
+}
+
+/// 防御墙修满所需的花费（金属）
+fn wall_repair_cost(wall: &DefenseWall) -> ItemAmounts {
+    amounts(&[(ItemId::METAL, wall.repair_cost.ceil() as u32)])
+}
+
+/// 本帧移动的距离（像素）：机器人速度以格/秒计，`multiplier` 为夜晚等效率修正
+fn travel_distance(robot: &Robot, multiplier: f32, time: &Time) -> f32 {
+    robot.robot_type.movement_speed() * TILE_SIZE * multiplier * time.delta_secs()
+}
+
+/// 放弃当前运送，撤下对应的任务（已取的货留在货舱）
+fn abort_delivery(courier: &mut Courier, board: &mut JobBoard) {
+    if let Some(job) = courier.delivery.take().and_then(|delivery| delivery.job) {
+        board.finish(job);
+    }
+}
+
+/// 把货舱里的全部货物卸到最近的仓库，没有仓库时送回基地
+fn unload_cargo(board: &JobBoard, position: Vec2, inventory: &Inventory) -> Delivery {
+    let to = board
+        .nearest_hub(position)
+        .map_or(InventoryOwner::Base, |hub| InventoryOwner::Entity(hub.entity));
+    Delivery::unload(inventory.items.clone(), to)
+}
+
 /// 寻找最近的植物
 fn find_nearest_plant(
     plant_query: &Query<(Entity, &Plant, &Transform), (With<Plantable>, Without<Harvestable>, Without<Robot>)>,
@@ -204,6 +722,20 @@ fn find_nearest_plant(
     nearest.map(|(entity, pos, _)| (entity, pos))
 }
 
+/// 寻找最近的供电充电桩
+fn find_nearest_charger(
+    charger_query: &Query<(Entity, &Transform, &mut PowerNode), Without<Robot>>,
+    position: Vec3,
+) -> Option<(Entity, Vec2)> {
+    charger_query
+        .iter()
+        .filter(|(_, _, node)| node.can_charge())
+        .map(|(entity, transform, _)| (entity, transform.translation.truncate()))
+        .min_by(|(_, a), (_, b)| {
+            a.distance(position.truncate()).total_cmp(&b.distance(position.truncate()))
+        })
+}
+
 /// 生成随机位置
 fn generate_random_position(center: Vec3, radius: f32) -> Vec2 {
     use rand::Rng;
@@ -217,12 +749,54 @@ fn generate_random_position(center: Vec3, radius: f32) -> Vec2 {
     )
 }
 
-/// 向目标移动
-fn move_towards_target(transform: &mut Transform, target: Vec2, speed: f32) {
-    let current = transform.translation.truncate();
-    let direction = (target - current).normalize();
-    let new_pos = current + direction * speed;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use std::time::Duration;
+    use crate::components::enemy::EnemyType;
+
+    #[test]
+    fn defender_closes_in_on_a_retreating_enemy() {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs_f32(0.5));
+        world.insert_resource(time);
+        world.insert_resource(GameTime::default());
+        let map_grid = MapGrid::new(32, 32, 0);
+        let anchor = map_grid.tile_to_world(IVec2::new(10, 10));
+        world.insert_resource(map_grid);
+        world.init_resource::<NavObstacles>();
 
-    transform.translation.x = new_pos.x;
-    transform.translation.y = new_pos.y;
+        let defender = world
+            .spawn((
+                Transform::from_translation(anchor.extend(2.0)),
+                Robot::new(RobotType::Defender),
+                RobotAI::default(),
+                PathCache::default(),
+                defender_combat(),
+                DefenderOrder::Guard(anchor),
+            ))
+            .id();
+        let enemy = world
+            .spawn((Enemy::new(EnemyType::ScoutBot, 1), Transform::from_translation((anchor + Vec2::X * 2.5 * TILE_SIZE).extend(1.0))))
+            .id();
+        world.run_system_once(defender_robot_system).unwrap();
+        assert_eq!(world.get::<Combat>(defender).unwrap().target, Some(enemy));
+
+        // 敌人以 1 格/秒 后撤，仍在守卫范围内
+        let gap = |world: &World| {
+            world.get::<Transform>(enemy).unwrap().translation.truncate()
+                .distance(world.get::<Transform>(defender).unwrap().translation.truncate())
+        };
+        world.get_mut::<Transform>(enemy).unwrap().translation = (anchor + Vec2::X * 4.0 * TILE_SIZE).extend(1.0);
+        let initial_gap = gap(&world);
+        for step in 1..=3 {
+            world.run_system_once(defender_robot_system).unwrap();
+            world.get_mut::<Transform>(enemy).unwrap().translation = (anchor + Vec2::X * (4.0 + 0.5 * step as f32) * TILE_SIZE).extend(1.0);
+        }
+
+        assert!(gap(&world) < initial_gap - TILE_SIZE / 2.0, "gap {} did not close from {}", gap(&world), initial_gap);
+        assert_eq!(world.get::<Robot>(defender).unwrap().current_task, RobotTask::Engage);
+    }
 }
@@ -0,0 +1,216 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use ruzstd::decoding::StreamingDecoder;
+use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+use serde::Serialize;
+use serde_json::Value;
+use crate::components::save::SaveError;
+
+// 存档文件容器
+//
+// 文件头（小端序，共 20 字节）：
+// | 魔数 4B | 容器版本 u16 | 标志 u8 | 保留 u8 | 负载长度 u64 | CRC32 u32 |
+// 之后是负载：`SaveData` 的 JSON，按标志决定是否经过 zstd 压缩。
+//
+// 解码只还原到 JSON 值，由 `save_migration` 升级到当前结构后再转为 `SaveData`。
+
+/// 文件魔数
+pub const SAVE_MAGIC: [u8; 4] = *b"DFSV";
+/// 当前容器格式版本
+pub const SAVE_FORMAT_VERSION: u16 = 1;
+/// 存档文件扩展名
+pub const SAVE_EXTENSION: &str = "sav";
+/// 容器格式之前的存档扩展名（裸 JSON）
+pub const LEGACY_SAVE_EXTENSION: &str = "json";
+
+const HEADER_LEN: usize = 20;
+const FLAG_COMPRESSED: u8 = 0b0000_0001;
+
+/// 把存档数据编码为容器字节
+pub fn encode<T: Serialize>(save_data: &T, compress: bool) -> Result<Vec<u8>, SaveError> {
+    let json = serde_json::to_vec(save_data).map_err(|e| SaveError::Encode(e.to_string()))?;
+
+    let (flags, payload) = if compress {
+        (FLAG_COMPRESSED, compress_to_vec(json.as_slice(), CompressionLevel::Fastest))
+    } else {
+        (0, json)
+    };
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.extend_from_slice(&SAVE_MAGIC);
+    bytes.extend_from_slice(&SAVE_FORMAT_VERSION.to_le_bytes());
+    bytes.push(flags);
+    bytes.push(0);
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+
+    Ok(bytes)
+}
+
+/// 校验并解码容器字节
+///
+/// 容器格式之前的存档是裸 JSON，同样接受。
+pub fn decode(bytes: &[u8]) -> Result<Value, SaveError> {
+    if bytes.first() == Some(&b'{') {
+        return serde_json::from_slice(bytes).map_err(|e| SaveError::Decode(e.to_string()));
+    }
+
+    if bytes.len() < HEADER_LEN {
+        return Err(SaveError::Truncated { expected: HEADER_LEN, actual: bytes.len() });
+    }
+
+    let (header, payload) = bytes.split_at(HEADER_LEN);
+    if header[0..4] != SAVE_MAGIC {
+        return Err(SaveError::BadMagic);
+    }
+
+    let format_version = u16::from_le_bytes([header[4], header[5]]);
+    if format_version != SAVE_FORMAT_VERSION {
+        return Err(SaveError::UnsupportedFormat(format_version));
+    }
+
+    let flags = header[6];
+    let payload_len = u64::from_le_bytes(header[8..16].try_into().unwrap()) as usize;
+    let checksum = u32::from_le_bytes(header[16..20].try_into().unwrap());
+
+    if payload.len() < payload_len {
+        return Err(SaveError::Truncated {
+            expected: HEADER_LEN + payload_len,
+            actual: bytes.len(),
+        });
+    }
+    let payload = &payload[..payload_len];
+
+    let actual = crc32fast::hash(payload);
+    if actual != checksum {
+        return Err(SaveError::ChecksumMismatch { expected: checksum, actual });
+    }
+
+    let json = if flags & FLAG_COMPRESSED != 0 {
+        let mut decoder = StreamingDecoder::new(payload)
+            .map_err(|e| SaveError::Decompress(e.to_string()))?;
+        let mut json = Vec::new();
+        decoder
+            .read_to_end(&mut json)
+            .map_err(|e| SaveError::Decompress(e.to_string()))?;
+        json
+    } else {
+        payload.to_vec()
+    };
+
+    serde_json::from_slice(&json).map_err(|e| SaveError::Decode(e.to_string()))
+}
+
+/// 原子写入：先写临时文件并落盘，再重命名覆盖
+///
+/// 写入中途崩溃只会留下临时文件，原有存档保持完整。
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), SaveError> {
+    let tmp_path = path.with_extension(format!("{}.tmp", SAVE_EXTENSION));
+
+    let result = (|| {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(SaveError::Io(e));
+    }
+
+    Ok(())
+}
+
+/// 读取并解码存档文件
+pub fn read(path: &Path) -> Result<Value, SaveError> {
+    let bytes = fs::read(path)?;
+    decode(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({ "version": "0.12.0", "player": { "level": 3 } })
+    }
+
+    #[test]
+    fn round_trips_compressed_and_plain() {
+        for compress in [true, false] {
+            let bytes = encode(&sample(), compress).unwrap();
+            assert_eq!(decode(&bytes).unwrap(), sample());
+        }
+    }
+
+    #[test]
+    fn accepts_legacy_json() {
+        let bytes = serde_json::to_vec(&sample()).unwrap();
+        assert_eq!(decode(&bytes).unwrap(), sample());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let bytes = encode(&sample(), true).unwrap();
+        let result = decode(&bytes[..HEADER_LEN - 1]);
+        assert!(matches!(
+            result,
+            Err(SaveError::Truncated { expected: HEADER_LEN, actual }) if actual == HEADER_LEN - 1
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_payload() {
+        let bytes = encode(&sample(), true).unwrap();
+        let result = decode(&bytes[..bytes.len() - 1]);
+        assert!(matches!(
+            result,
+            Err(SaveError::Truncated { expected, actual }) if expected == bytes.len() && actual == bytes.len() - 1
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let mut bytes = encode(&sample(), false).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        assert!(matches!(decode(&bytes), Err(SaveError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = encode(&sample(), true).unwrap();
+        bytes[0] = b'X';
+        assert!(matches!(decode(&bytes), Err(SaveError::BadMagic)));
+    }
+
+    #[test]
+    fn rejects_unsupported_format_version() {
+        let mut bytes = encode(&sample(), true).unwrap();
+        bytes[4..6].copy_from_slice(&(SAVE_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            decode(&bytes),
+            Err(SaveError::UnsupportedFormat(version)) if version == SAVE_FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn failed_replace_keeps_target_and_removes_temp_file() {
+        let dir = std::env::temp_dir().join(format!("dark-forest-save-file-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        // 目标是非空目录，重命名必然失败
+        let path = dir.join("slot.sav");
+        fs::create_dir_all(path.join("occupied")).unwrap();
+
+        let result = write_atomic(&path, &encode(&sample(), true).unwrap());
+
+        assert!(matches!(result, Err(SaveError::Io(_))));
+        assert!(path.join("occupied").is_dir());
+        assert!(!path.with_extension(format!("{}.tmp", SAVE_EXTENSION)).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
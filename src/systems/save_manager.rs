@@ -1,68 +1,347 @@
 use bevy::prelude::*;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::reflect::{GetTypeRegistration, TypeRegistry};
+use bevy::scene::serde::SceneDeserializer;
+use bevy::scene::{DynamicScene, DynamicSceneBuilder};
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+use serde::de::DeserializeSeed;
+use std::path::Path;
+use crate::components::achievement::{Achievement, AchievementLog};
+use crate::components::building::{Building, BuildingPosition, BuildingStatus, ConstructionSite};
+use crate::components::building_catalog::{BuildingCatalog, BuildingDefinition};
+use crate::components::enemy::{Enemy, EnemyBase, EnemyPosition, EnemyStatus, EnemyType, EnemyWaveConfig, EnemyWaveState, WaveMember};
+use crate::components::equipment::{Equipment, EquipmentBar};
+use crate::components::pathfinding::PathCache;
+use crate::components::plant::{Growable, Harvestable, Plant, Plantable};
+use crate::components::plant_upgrade::PlantUpgrade;
+use crate::components::experience::PlayerExperience;
+use crate::components::game_over::RunStats;
+use crate::components::skill::PlayerSkills;
+use crate::components::player::{Player, PlayerVitals};
+use crate::components::quest::{Quest, QuestLog};
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::ItemId;
+use crate::components::power::{Battery, PowerCable};
+use crate::components::robot::{DefenderOrder, Robot, RobotAI, RobotHealth, RobotType, DEFENDER_MAX_HEALTH};
 use crate::components::save::{
-    SaveManager, SaveType, SaveInfo, SaveData,
-    PlayerData, WorldData, QuestData, AchievementData, EquipmentData
+    SaveManager, SaveRegistry, SaveType, SaveInfo, SaveData, SaveError, Saveable, SAVE_VERSION
 };
+use crate::resources::world::MapGrid;
+use crate::states::GameState;
+use crate::systems::building::building_sprite;
+use crate::systems::enemy_spawn::EnemyRenderAssets;
+use crate::systems::equipment::equipment_sprite;
+use crate::systems::game_over::restarting;
+use crate::systems::headless::HeadlessConfig;
+use crate::systems::plant::plant_sprite;
+use crate::systems::power::cable_sprite;
+use crate::systems::player::PlayerRenderAssets;
+use crate::systems::robot::{defender_combat, robot_sprite};
+use crate::systems::save_file::{self, LEGACY_SAVE_EXTENSION, SAVE_EXTENSION};
+use crate::systems::save_migration;
+use crate::systems::save_ui::get_save_type_name;
+use crate::systems::time::GameTime;
 
 /// 存档管理系统插件
 pub struct SaveManagerPlugin;
 
 impl Plugin for SaveManagerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (
-            handle_auto_save.run_if(in_state(crate::states::GameState::InGame)),
-        ));
+        app.init_resource::<SaveManager>()
+            .init_resource::<SaveRegistry>()
+            // 实体组件
+            .register_saveable::<Saveable>()
+            .register_saveable::<Transform>()
+            .register_saveable::<Player>()
+            .register_saveable::<PlayerVitals>()
+            .register_saveable::<PlayerExperience>()
+            .register_saveable::<PlayerSkills>()
+            .register_saveable::<Inventory>()
+            .register_saveable::<Base>()
+            .register_saveable::<EquipmentBar>()
+            .register_saveable::<Equipment>()
+            .register_saveable::<Building>()
+            .register_saveable::<BuildingPosition>()
+            .register_saveable::<BuildingStatus>()
+            .register_saveable::<ConstructionSite>()
+            .register_saveable::<Battery>()
+            .register_saveable::<PowerCable>()
+            .register_saveable::<Plant>()
+            .register_saveable::<Growable>()
+            .register_saveable::<PlantUpgrade>()
+            .register_saveable::<Harvestable>()
+            .register_saveable::<Robot>()
+            .register_saveable::<RobotHealth>()
+            .register_saveable::<DefenderOrder>()
+            .register_saveable::<Enemy>()
+            .register_saveable::<EnemyPosition>()
+            .register_saveable::<WaveMember>()
+            .register_saveable::<EnemyBase>()
+            .register_saveable::<Quest>()
+            .register_saveable::<QuestLog>()
+            .register_saveable::<Achievement>()
+            .register_saveable::<AchievementLog>()
+            // 全局资源
+            .register_saveable_resource::<MapGrid>()
+            .register_saveable_resource::<GameTime>()
+            .register_saveable_resource::<EnemyWaveConfig>()
+            .register_saveable_resource::<EnemyWaveState>()
+            .register_saveable_resource::<RunStats>()
+            // 映射键类型不会随组件自动注册
+            .register_type::<ItemId>()
+            .init_resource::<PendingSaves>()
+            .add_message::<SaveGameEvent>()
+            .add_message::<LoadGameEvent>()
+            .add_message::<SaveCompletedEvent>()
+            .add_systems(Update, (
+                handle_auto_save,
+                handle_day_checkpoint,
+            ).run_if(in_state(GameState::InGame)))
+            .add_systems(OnEnter(GameState::InGame), reset_run_tracking.run_if(restarting))
+            // 读档在帧首完成，本帧的逻辑与变换传播都基于新世界；结算界面同样可以读档
+            .add_systems(PreUpdate, process_save_requests.run_if(
+                in_state(GameState::InGame).or(in_state(GameState::GameOver))
+            ))
+            .add_systems(Update, poll_pending_saves);
+    }
+}
+
+/// 存档注册扩展
+///
+/// 组件派生 `Reflect` 并标注 `#[reflect(Component)]` 后，注册一行即可随存档保存与恢复。
+pub trait SaveAppExt {
+    /// 注册可存档组件（仅保存带 `Saveable` 标记的实体上的该组件）
+    fn register_saveable<T: Component + GetTypeRegistration>(&mut self) -> &mut Self;
+
+    /// 注册可存档资源（需标注 `#[reflect(Resource)]`）
+    fn register_saveable_resource<T: Resource + GetTypeRegistration>(&mut self) -> &mut Self;
+}
+
+impl SaveAppExt for App {
+    fn register_saveable<T: Component + GetTypeRegistration>(&mut self) -> &mut Self {
+        self.register_type::<T>();
+        self.world_mut().get_resource_or_init::<SaveRegistry>().allow_component::<T>();
+        self
+    }
+
+    fn register_saveable_resource<T: Resource + GetTypeRegistration>(&mut self) -> &mut Self {
+        self.register_type::<T>();
+        self.world_mut().get_resource_or_init::<SaveRegistry>().allow_resource::<T>();
+        self
+    }
+}
+
+/// 存档请求事件
+#[derive(Event, Message, Debug, Clone)]
+pub struct SaveGameEvent {
+    pub save_type: SaveType,
+    pub description: Option<String>,
+    pub slot: Option<String>,     // 覆盖的存档ID（为空时新建存档）
+}
+
+/// 存档写盘完成事件
+#[derive(Event, Message, Debug, Clone)]
+pub struct SaveCompletedEvent {
+    pub save_id: String,
+}
+
+/// 读档请求事件
+#[derive(Event, Message, Debug, Clone)]
+pub struct LoadGameEvent {
+    pub save_id: String,
+}
+
+/// 后台写盘中的存档任务
+#[derive(Resource, Default)]
+pub struct PendingSaves {
+    tasks: Vec<Task<Result<String, SaveError>>>,
+}
+
+impl PendingSaves {
+    /// 是否有存档正在写盘
+    pub fn is_busy(&self) -> bool {
+        !self.tasks.is_empty()
+    }
+}
+
+/// 存档快照
+///
+/// 场景在主线程从世界中提取；序列化、压缩与写盘不再需要世界，可以放到后台任务中完成。
+pub struct SaveSnapshot {
+    pub info: SaveInfo,
+    pub scene: DynamicScene,
+}
+
+impl SaveSnapshot {
+    /// 序列化为存档数据
+    pub fn into_save_data(self, type_registry: &TypeRegistry) -> Result<SaveData, SaveError> {
+        let scene = self
+            .scene
+            .serialize(type_registry)
+            .map_err(|e| SaveError::Encode(format!("场景序列化失败: {}", e)))?;
+
+        Ok(SaveData { info: self.info, scene })
     }
 }
 
 /// 处理自动存档
 fn handle_auto_save(
     time: Res<Time>,
-    mut save_manager_query: Query<&mut SaveManager>,
+    mut save_manager: ResMut<SaveManager>,
+    mut save_events: MessageWriter<SaveGameEvent>,
 ) {
-    if let Ok(save_manager) = save_manager_query.single_mut() {
-        let current_time = time.elapsed_secs();
+    if save_manager.update_auto_save_timer(time.elapsed_secs()) {
+        info!("触发自动存档");
+        save_events.write(SaveGameEvent {
+            save_type: SaveType::Auto,
+            description: None,
+            slot: None,
+        });
+    }
+}
+
+/// 新的一天开始时写入检查点存档
+fn handle_day_checkpoint(
+    game_time: Option<Res<GameTime>>,
+    mut save_manager: ResMut<SaveManager>,
+    mut save_events: MessageWriter<SaveGameEvent>,
+) {
+    let Some(game_time) = game_time else { return; };
+    let day = game_time.day;
+
+    if save_manager.last_checkpoint_day == Some(day) {
+        return;
+    }
+
+    // 首次进入游戏或读档回到更早的天数时只记录，不存档
+    if save_manager.last_checkpoint_day.is_some_and(|last| day > last) {
+        info!("触发第 {} 天检查点存档", day);
+        save_events.write(SaveGameEvent {
+            save_type: SaveType::Checkpoint,
+            description: Some(format!("第 {} 天", day)),
+            slot: None,
+        });
+    }
+    save_manager.last_checkpoint_day = Some(day);
+}
 
-        if save_manager.should_auto_save(current_time) {
-            info!("触发自动存档");
-            // 实际存档操作需要在主线程中执行
-            // 这里只是触发存档事件
+/// 重新开局：自动存档与检查点从新的一局重新计时，新存档不再覆盖旧局的存档
+fn reset_run_tracking(mut save_manager: ResMut<SaveManager>) {
+    save_manager.last_auto_save = None;
+    save_manager.last_checkpoint_day = None;
+    save_manager.current_save_id = None;
+}
+
+/// 处理存档/读档请求
+///
+/// 需要独占访问世界：存档在这里提取快照后交给后台任务，读档直接替换世界。
+fn process_save_requests(world: &mut World) {
+    let loads: Vec<LoadGameEvent> = world.resource_mut::<Messages<LoadGameEvent>>().drain().collect();
+    let saves: Vec<SaveGameEvent> = world.resource_mut::<Messages<SaveGameEvent>>().drain().collect();
+
+    // 读档会替换整个世界，同一帧的存档请求随之作废
+    if let Some(request) = loads.last() {
+        match load_game(world, &request.save_id) {
+            // 在结算界面读档后回到游戏
+            Ok(_) if *world.resource::<State<GameState>>().get() == GameState::GameOver => {
+                world.resource_mut::<NextState<GameState>>().set(GameState::InGame);
+            }
+            Ok(_) => {}
+            Err(e) => error!("加载存档 {} 失败: {}", request.save_id, e),
+        }
+        return;
+    }
+
+    for request in saves {
+        let save_id = match (request.slot, world.get_resource_mut::<SaveManager>()) {
+            (Some(slot), _) => slot,
+            (None, Some(mut save_manager)) => save_manager.generate_save_id(request.save_type),
+            (None, None) => {
+                error!("{}失败: 存档管理器不存在", get_save_type_name(request.save_type));
+                continue;
+            }
+        };
+        match start_save(world, save_id, request.save_type, request.description) {
+            Ok(task) => world.resource_mut::<PendingSaves>().tasks.push(task),
+            Err(e) => error!("{}失败: {}", get_save_type_name(request.save_type), e),
         }
     }
 }
 
-/// 保存游戏
-pub fn save_game(
+/// 收取完成的后台存档任务
+fn poll_pending_saves(
+    mut pending: ResMut<PendingSaves>,
+    mut save_manager: ResMut<SaveManager>,
+    mut completed_events: MessageWriter<SaveCompletedEvent>,
+) {
+    if !pending.is_busy() {
+        return;
+    }
+
+    pending.tasks.retain_mut(|task| match check_ready(task) {
+        Some(Ok(save_id)) => {
+            save_manager.current_save_id = Some(save_id.clone());
+            completed_events.write(SaveCompletedEvent { save_id });
+            false
+        }
+        Some(Err(e)) => {
+            error!("存档写入失败: {}", e);
+            false
+        }
+        None => true,
+    });
+}
+
+/// 提取快照并在后台写盘
+///
+/// 后台任务在退出时被丢弃也不会损坏已有存档（见 `save_file::write_atomic`）。
+fn start_save(
     world: &World,
+    save_id: String,
     save_type: SaveType,
     description: Option<String>,
-) -> Result<String, String> {
-    // 获取存档管理器
+) -> Result<Task<Result<String, SaveError>>, SaveError> {
     let save_manager = world.get_resource::<SaveManager>()
-        .ok_or("存档管理器不存在")?;
+        .ok_or_else(|| SaveError::World("存档管理器不存在".to_string()))?
+        .clone();
 
-    // 生成存档ID
-    let save_id = save_manager.generate_save_id(save_type);
+    let snapshot = snapshot_world(world, &save_id, save_type, description)?;
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
 
-    // 收集游戏数据
-    let save_data = collect_save_data(world, &save_id, save_type, description)?;
+    Ok(IoTaskPool::get().spawn(async move {
+        write_snapshot(snapshot, &type_registry.read(), &save_manager)
+    }))
+}
+
+/// 序列化快照并写入存档文件，然后清理超出上限的旧存档
+fn write_snapshot(
+    snapshot: SaveSnapshot,
+    type_registry: &TypeRegistry,
+    save_manager: &SaveManager,
+) -> Result<String, SaveError> {
+    let save_id = snapshot.info.save_id.clone();
+    let save_type = snapshot.info.save_type;
+    let save_data = snapshot.into_save_data(type_registry)?;
 
-    // 序列化数据
-    let serialized = serde_json::to_string(&save_data)
-        .map_err(|e| format!("序列化失败: {}", e))?;
+    // 编码为存档容器（按设置压缩）
+    let bytes = save_file::encode(&save_data, save_manager.settings.compress_saves)?;
 
     // 获取存档路径
     let save_path = save_manager.get_save_path(&save_id);
 
     // 确保存档目录存在
-    std::fs::create_dir_all(&save_manager.settings.save_location)
-        .map_err(|e| format!("创建存档目录失败: {}", e))?;
+    std::fs::create_dir_all(&save_manager.settings.save_location)?;
 
-    // 写入文件
-    std::fs::write(&save_path, serialized)
-        .map_err(|e| format!("写入存档文件失败: {}", e))?;
+    // 原子写入，崩溃时不会破坏同名旧存档
+    save_file::write_atomic(Path::new(&save_path), &bytes)?;
+
+    info!("游戏保存成功: {} ({} 字节)", save_id, bytes.len());
+
+    if let Err(e) = prune_saves(save_manager, save_type) {
+        warn!("清理旧存档失败: {}", e);
+    }
 
-    info!("游戏保存成功: {}", save_id);
     Ok(save_id)
 }
 
@@ -70,233 +349,358 @@ pub fn save_game(
 pub fn load_game(
     world: &mut World,
     save_id: &str,
-) -> Result<SaveData, String> {
+) -> Result<SaveData, SaveError> {
     // 获取存档管理器
     let save_manager = world.get_resource::<SaveManager>()
-        .ok_or("存档管理器不存在")?;
-
-    // 获取存档路径
-    let save_path = save_manager.get_save_path(save_id);
+        .ok_or_else(|| SaveError::World("存档管理器不存在".to_string()))?;
 
-    // 读取文件
-    let serialized = std::fs::read_to_string(&save_path)
-        .map_err(|e| format!("读取存档文件失败: {}", e))?;
+    // 读取并校验存档文件
+    let value = save_file::read(Path::new(&existing_save_path(save_manager, save_id)))?;
 
-    // 反序列化数据
-    let save_data: SaveData = serde_json::from_str(&serialized)
-        .map_err(|e| format!("反序列化失败: {}", e))?;
-
-    // 检查版本兼容性
-    check_version_compatibility(&save_data.info.version)?;
+    // 旧版本存档逐步迁移到当前版本
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let save_data = save_migration::migrate(value, &type_registry.read())?;
 
     // 应用存档数据
     apply_save_data(world, &save_data)?;
 
+    // 读档后从存档的天数重新计算检查点
+    let day = world.get_resource::<GameTime>().map(|game_time| game_time.day);
+    if let Some(mut save_manager) = world.get_resource_mut::<SaveManager>() {
+        save_manager.last_checkpoint_day = day;
+        save_manager.current_save_id = Some(save_id.to_string());
+    }
+
     info!("游戏加载成功: {}", save_id);
     Ok(save_data)
 }
 
 /// 收集存档数据
-fn collect_save_data(
+pub(crate) fn collect_save_data(
     world: &World,
     save_id: &str,
     save_type: SaveType,
     description: Option<String>,
-) -> Result<SaveData, String> {
-    // 收集玩家数据
-    let player_data = collect_player_data(world)?;
-
-    // 收集世界数据
-    let world_data = collect_world_data(world)?;
-
-    // 收集任务数据
-    let quest_data = collect_quest_data(world)?;
+) -> Result<SaveData, SaveError> {
+    snapshot_world(world, save_id, save_type, description)?
+        .into_save_data(&world.resource::<AppTypeRegistry>().read())
+}
 
-    // 收集成就数据
-    let achievement_data = collect_achievement_data(world)?;
+/// 提取存档快照
+pub fn snapshot_world(
+    world: &World,
+    save_id: &str,
+    save_type: SaveType,
+    description: Option<String>,
+) -> Result<SaveSnapshot, SaveError> {
+    let mut query = world
+        .try_query::<(&Player, &Transform)>()
+        .ok_or_else(|| SaveError::World("玩家不存在".to_string()))?;
+    let (player, transform) = query
+        .single(world)
+        .map_err(|e| SaveError::World(format!("获取玩家失败: {}", e)))?;
+    let game_time = world.get_resource::<GameTime>()
+        .ok_or_else(|| SaveError::World("游戏时间不存在".to_string()))?;
+
+    // 玩家所在地形作为存档位置
+    let location = world
+        .get_resource::<MapGrid>()
+        .and_then(|map_grid| {
+            let tile = map_grid.world_to_grid(transform.translation.truncate())?;
+            map_grid.get(tile.x, tile.y)
+        })
+        .map(|tile| format!("{:?}", tile.tile_type))
+        .unwrap_or_else(|| "Unknown".to_string());
 
     // 创建存档信息
     let mut info = SaveInfo::new(
         save_id.to_string(),
         save_type,
-        "Player".to_string(), // 实际应该从玩家系统获取
-        player_data.level,
-        world_data.time,
-        "Forest".to_string(), // 实际应该从地图系统获取
-        "0.8.0".to_string(), // 游戏版本
+        player.name.clone(),
+        player.level,
+        ((game_time.day - 1) as f32 + game_time.hour / 24.0) * game_time.day_length,
+        location,
+        SAVE_VERSION.to_string(),
     );
 
     if let Some(desc) = description {
         info = info.with_description(desc);
     }
 
-    Ok(SaveData {
+    Ok(SaveSnapshot {
         info,
-        player_data,
-        world_data,
-        quest_data,
-        achievement_data,
+        scene: extract_scene(world)?,
     })
 }
 
-/// 收集玩家数据
-fn collect_player_data(_world: &World) -> Result<PlayerData, String> {
-    // 实际实现需要从玩家系统获取数据
-    Ok(PlayerData {
-        level: 1,
-        experience: 0,
-        health: 100.0,
-        max_health: 100.0,
-        position: (0.0, 0.0),
-        inventory: Vec::new(),
-        equipment: EquipmentData {
-            weapon: None,
-            armor: None,
-            accessory: None,
-        },
-    })
+/// 提取带 `Saveable` 标记的实体和已注册资源
+fn extract_scene(world: &World) -> Result<DynamicScene, SaveError> {
+    let registry = world.get_resource::<SaveRegistry>()
+        .ok_or_else(|| SaveError::World("存档注册表不存在".to_string()))?;
+
+    let entities: Vec<Entity> = world
+        .try_query_filtered::<Entity, With<Saveable>>()
+        .map(|mut query| query.iter(world).collect())
+        .unwrap_or_default();
+
+    Ok(DynamicSceneBuilder::from_world(world)
+        .with_component_filter(registry.components.clone())
+        .with_resource_filter(registry.resources.clone())
+        .extract_entities(entities.into_iter())
+        .extract_resources()
+        .build())
 }
 
-/// 收集世界数据
-fn collect_world_data(_world: &World) -> Result<WorldData, String> {
-    // 实际实现需要从世界系统获取数据
-    Ok(WorldData {
-        time: 0.0,
-        day: 1,
-        explored_areas: Vec::new(),
-        buildings: Vec::new(),
-        resources: Vec::new(),
-    })
-}
+/// 解析场景文本
+fn parse_scene(world: &World, scene: &str) -> Result<DynamicScene, SaveError> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut deserializer = ron::de::Deserializer::from_str(scene)
+        .map_err(|e| SaveError::Decode(format!("场景解析失败: {}", e)))?;
 
-/// 收集任务数据
-fn collect_quest_data(_world: &World) -> Result<QuestData, String> {
-    // 实际实现需要从任务系统获取数据
-    Ok(QuestData {
-        active_quests: Vec::new(),
-        completed_quests: Vec::new(),
-        failed_quests: Vec::new(),
-        current_quest: None,
-    })
-}
-
-/// 收集成就数据
-fn collect_achievement_data(_world: &World) -> Result<AchievementData, String> {
-    // 实际实现需要从成就系统获取数据
-    Ok(AchievementData {
-        unlocked_achievements: Vec::new(),
-        total_points: 0,
-    })
+    SceneDeserializer { type_registry: &type_registry }
+        .deserialize(&mut deserializer)
+        .map_err(|e| SaveError::Decode(format!("场景解析失败: {}", e)))
 }
 
-/// 应用存档数据
-fn apply_save_data(
+/// 应用存档数据：清除当前世界中的存档实体，再按场景重建
+pub(crate) fn apply_save_data(
     world: &mut World,
     save_data: &SaveData,
-) -> Result<(), String> {
-    // 应用玩家数据
-    apply_player_data(world, &save_data.player_data)?;
-    apply_world_data(world, &save_data.world_data)?;
-    apply_quest_data(world, &save_data.quest_data)?;
-    apply_achievement_data(world, &save_data.achievement_data)?;
-    Ok(())
-}
+) -> Result<(), SaveError> {
+    // 先解析场景，数据损坏时不改动当前世界
+    let scene = parse_scene(world, &save_data.scene)?;
+
+    let saved: Vec<Entity> = world
+        .query_filtered::<Entity, With<Saveable>>()
+        .iter(world)
+        .collect();
+    for entity in saved {
+        world.despawn(entity);
+    }
 
-/// 应用玩家数据
-fn apply_player_data(
-    _world: &mut World,
-    player_data: &PlayerData,
-) -> Result<(), String> {
-    // 实际实现需要应用到玩家系统
-    info!("应用玩家数据: 等级={}, 位置={:?}", player_data.level, player_data.position);
-    Ok(())
-}
+    // 场景内的实体引用（如装备栏）会映射到新生成的实体
+    let mut entity_map = EntityHashMap::default();
+    scene
+        .write_to_world(world, &mut entity_map)
+        .map_err(|e| SaveError::World(format!("还原场景失败: {}", e)))?;
 
-/// 应用世界数据
-fn apply_world_data(
-    _world: &mut World,
-    world_data: &WorldData,
-) -> Result<(), String> {
-    // 实际实现需要应用到世界系统
-    info!("应用世界数据: 时间={}, 天数={}", world_data.time, world_data.day);
-    Ok(())
-}
+    world
+        .run_system_once(restore_runtime_components)
+        .map_err(|e| SaveError::World(format!("还原运行时组件失败: {}", e)))?;
 
-/// 应用任务数据
-fn apply_quest_data(
-    _world: &mut World,
-    quest_data: &QuestData,
-) -> Result<(), String> {
-    // 实际实现需要应用到任务系统
-    info!("应用任务数据: 进行中={}, 已完成={}",
-        quest_data.active_quests.len(),
-        quest_data.completed_quests.len());
+    info!("应用存档数据: 实体={}, 资源={}", entity_map.len(), scene.resources.len());
     Ok(())
 }
 
-/// 应用成就数据
-fn apply_achievement_data(
-    _world: &mut World,
-    achievement_data: &AchievementData,
-) -> Result<(), String> {
-    // 实际实现需要应用到成就系统
-    info!("应用成就数据: 解锁={}, 点数={}",
-        achievement_data.unlocked_achievements.len(),
-        achievement_data.total_points);
-    Ok(())
-}
+/// 补回不写入存档的运行时组件（渲染、寻路缓存、AI 状态）
+///
+/// 无头模式下不插入任何 Sprite/Mesh2d。
+fn restore_runtime_components(
+    mut commands: Commands,
+    headless: Option<Res<HeadlessConfig>>,
+    player_assets: Option<Res<PlayerRenderAssets>>,
+    enemy_assets: Option<Res<EnemyRenderAssets>>,
+    building_catalog: Option<Res<BuildingCatalog>>,
+    player_query: Query<Entity, (With<Player>, Without<Mesh2d>)>,
+    enemy_query: Query<Entity, (With<Enemy>, Without<EnemyStatus>)>,
+    base_query: Query<(Entity, &EnemyBase), (Without<Enemy>, Without<Mesh2d>)>,
+    building_query: Query<(Entity, &Building), Without<Sprite>>,
+    cable_query: Query<Entity, (With<PowerCable>, Without<Sprite>)>,
+    plant_query: Query<(Entity, &Plant), Without<Sprite>>,
+    robot_query: Query<(Entity, &Robot), Without<RobotAI>>,
+    equipment_query: Query<(Entity, &Equipment), Without<Sprite>>,
+) {
+    let rendering = headless.is_none();
+
+    for entity in player_query.iter() {
+        if let Some(assets) = &player_assets {
+            commands.entity(entity).insert((
+                Mesh2d(assets.player_mesh.clone()),
+                MeshMaterial2d(assets.player_material.clone()),
+            ));
+        }
+    }
 
-/// 检查版本兼容性
-fn check_version_compatibility(version: &str) -> Result<(), String> {
-    let current_version = "0.8.0";
+    for entity in enemy_query.iter() {
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert((EnemyStatus::default(), PathCache::default()));
+        if let Some(assets) = &enemy_assets {
+            entity_commands.insert((
+                Mesh2d(assets.enemy_mesh.clone()),
+                MeshMaterial2d(assets.enemy_material.clone()),
+            ));
+        }
+    }
 
-    // 简单版本检查
-    if version != current_version {
-        return Err(format!("存档版本({})不兼容当前版本({})", version, current_version));
+    if let Some(assets) = &enemy_assets {
+        for (entity, base) in base_query.iter() {
+            let material = match base.base_type {
+                EnemyType::AIMotherBase => &assets.mother_base_material,
+                _ => &assets.fortress_material,
+            };
+            commands.entity(entity).insert((
+                Mesh2d(assets.base_mesh.clone()),
+                MeshMaterial2d(material.clone()),
+            ));
+        }
     }
 
-    Ok(())
+    for (entity, building) in building_query.iter().filter(|_| rendering) {
+        let color = building_catalog
+            .as_ref()
+            .and_then(|catalog| catalog.get(building.building_type))
+            .map(|definition| definition.color())
+            .unwrap_or_else(|| BuildingDefinition::builtin(building.building_type).color());
+        commands.entity(entity).insert(building_sprite(color));
+    }
+
+    for entity in cable_query.iter().filter(|_| rendering) {
+        commands.entity(entity).insert(cable_sprite());
+    }
+
+    for (entity, plant) in plant_query.iter() {
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert(Plantable);
+        if rendering {
+            entity_commands.insert(plant_sprite(plant.plant_type));
+        }
+    }
+
+    for (entity, robot) in robot_query.iter() {
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.insert((RobotAI::default(), PathCache::default()));
+        if rendering {
+            entity_commands.insert(robot_sprite(robot.robot_type));
+        }
+        // 旧存档中的防御机器人没有机体与指令，按新生成的补上
+        if robot.robot_type == RobotType::Defender {
+            entity_commands
+                .insert(defender_combat())
+                .insert_if_new((RobotHealth::new(DEFENDER_MAX_HEALTH), DefenderOrder::default()));
+        }
+    }
+
+    for (entity, equipment) in equipment_query.iter().filter(|_| rendering) {
+        commands.entity(entity).insert(equipment_sprite(equipment));
+    }
 }
 
 /// 获取存档列表
-pub fn get_save_list(save_manager: &SaveManager) -> Result<Vec<SaveInfo>, String> {
+///
+/// 旧版本存档同样列出（载入时迁移），损坏的存档文件会被跳过并记录警告。
+pub fn get_save_list(save_manager: &SaveManager) -> Result<Vec<SaveInfo>, SaveError> {
     let save_dir = &save_manager.settings.save_location;
 
     // 读取存档目录
-    let entries = std::fs::read_dir(save_dir)
-        .map_err(|e| format!("读取存档目录失败: {}", e))?;
+    let entries = std::fs::read_dir(save_dir)?;
 
     let mut saves = Vec::new();
 
     for entry in entries {
-        let entry = entry.map_err(|e| format!("读取存档条目失败: {}", e))?;
-        let path = entry.path();
+        let path = entry?.path();
+
+        if path.extension().is_some_and(|ext| ext == SAVE_EXTENSION || ext == LEGACY_SAVE_EXTENSION) {
+            let info = save_file::read(&path).and_then(|mut value| {
+                serde_json::from_value::<SaveInfo>(value["info"].take())
+                    .map_err(|e| SaveError::Decode(e.to_string()))
+            });
+            match info {
+                Ok(info) => saves.push(info),
+                Err(e) => warn!("跳过存档 {}: {}", path.display(), e),
+            }
+        }
+    }
 
-        if path.extension().map_or(false, |ext| ext == "json") {
-            // 读取存档文件
-            let content = std::fs::read_to_string(&path)
-                .map_err(|e| format!("读取存档文件失败: {}", e))?;
+    // 按时间排序
+    saves.sort_by(|a, b| b.real_time.total_cmp(&a.real_time));
 
-            // 解析存档信息
-            let save_data: SaveData = serde_json::from_str(&content)
-                .map_err(|e| format!("解析存档文件失败: {}", e))?;
+    Ok(saves)
+}
+
+/// 列出某类存档的ID，按时间从新到旧排列
+///
+/// 只按文件名判断类型，不读取存档内容。
+pub fn list_save_ids(save_manager: &SaveManager, save_type: SaveType) -> Result<Vec<String>, SaveError> {
+    let mut saves = Vec::new();
+
+    for entry in std::fs::read_dir(&save_manager.settings.save_location)? {
+        let path = entry?.path();
+        if !path.extension().is_some_and(|ext| ext == SAVE_EXTENSION || ext == LEGACY_SAVE_EXTENSION) {
+            continue;
+        }
 
-            saves.push(save_data.info);
+        let Some(save_id) = path.file_stem().and_then(|stem| stem.to_str()) else { continue; };
+        if let Some((found_type, timestamp)) = SaveType::parse_save_id(save_id) {
+            if found_type == save_type {
+                saves.push((timestamp, save_id.to_string()));
+            }
         }
     }
 
-    // 按时间排序
-    saves.sort_by(|a, b| b.real_time.partial_cmp(&a.real_time).unwrap());
+    saves.sort_by(|a, b| b.cmp(a));
+    saves.dedup_by(|a, b| a.1 == b.1);
 
-    Ok(saves)
+    Ok(saves.into_iter().map(|(_, save_id)| save_id).collect())
 }
 
-/// 删除存档
-pub fn delete_save(save_manager: &SaveManager, save_id: &str) -> Result<(), String> {
+/// 某类存档中最新的一个
+pub fn latest_save_id(save_manager: &SaveManager, save_type: SaveType) -> Option<String> {
+    list_save_ids(save_manager, save_type).ok()?.into_iter().next()
+}
+
+/// 删除超出设置上限的旧存档
+fn prune_saves(save_manager: &SaveManager, save_type: SaveType) -> Result<(), SaveError> {
+    let Some(max_saves) = save_manager.settings.max_saves(save_type) else {
+        return Ok(());
+    };
+
+    for save_id in list_save_ids(save_manager, save_type)?.into_iter().skip(max_saves) {
+        delete_save(save_manager, &save_id)?;
+    }
+
+    Ok(())
+}
+
+/// 存档文件的实际路径（没有容器格式文件时使用旧存档路径）
+fn existing_save_path(save_manager: &SaveManager, save_id: &str) -> String {
+    let save_path = save_manager.get_save_path(save_id);
+    let legacy_path = save_manager.get_legacy_save_path(save_id);
+
+    if !Path::new(&save_path).exists() && Path::new(&legacy_path).exists() {
+        legacy_path
+    } else {
+        save_path
+    }
+}
+
+/// 修改存档描述（存档列表中显示的名称）
+///
+/// 旧格式存档会顺带转存为容器格式。
+pub fn set_save_description(save_manager: &SaveManager, save_id: &str, description: &str) -> Result<(), SaveError> {
+    let source_path = existing_save_path(save_manager, save_id);
+    let mut value = save_file::read(Path::new(&source_path))?;
+
+    value
+        .get_mut("info")
+        .and_then(|info| info.as_object_mut())
+        .ok_or_else(|| SaveError::Decode("存档缺少存档信息".to_string()))?
+        .insert("description".to_string(), description.into());
+
     let save_path = save_manager.get_save_path(save_id);
+    let bytes = save_file::encode(&value, save_manager.settings.compress_saves)?;
+    save_file::write_atomic(Path::new(&save_path), &bytes)?;
+
+    if source_path != save_path {
+        std::fs::remove_file(&source_path)?;
+    }
 
-    std::fs::remove_file(&save_path)
-        .map_err(|e| format!("删除存档文件失败: {}", e))?;
+    info!("存档描述已更新: {}", save_id);
+    Ok(())
+}
+
+/// 删除存档
+pub fn delete_save(save_manager: &SaveManager, save_id: &str) -> Result<(), SaveError> {
+    std::fs::remove_file(existing_save_path(save_manager, save_id))?;
 
     info!("存档删除成功: {}", save_id);
     Ok(())
@@ -0,0 +1,1245 @@
+use bevy::prelude::*;
+use bevy::reflect::{FromReflect, PartialReflect, TypePath, TypeRegistry};
+use bevy::scene::serde::SceneDeserializer;
+use bevy::scene::{DynamicEntity, DynamicScene};
+use serde::de::DeserializeSeed;
+use serde_json::Value;
+use crate::components::building::{Building, BuildingPosition, BuildingType};
+use crate::components::building_catalog::BuildingDefinition;
+use crate::components::enemy::{Enemy, EnemyPosition, WaveMember};
+use crate::components::enemy_catalog::EnemyDefinition;
+use crate::components::equipment::{Equipment, EquipmentBar, EquipmentRarity, EquipmentType};
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::ItemRegistry;
+use crate::components::player::{Player, PLAYER_INVENTORY_SLOTS};
+use crate::components::plant::Harvestable;
+use crate::components::quest::QuestLog;
+use crate::components::achievement::AchievementLog;
+use crate::components::robot::Robot;
+use crate::components::save::{SaveData, SaveError, Saveable, SAVE_VERSION};
+use crate::components::experience::{LevelCurve, PlayerExperience};
+use crate::components::player::PlayerVitals;
+use crate::systems::map::default_map_layout;
+use crate::systems::time::GameTime;
+
+/// 存档迁移步骤：把 `from` 版本的存档数据原地转换为 `to` 版本
+pub struct SaveMigration {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub migrate: fn(&mut Value, &TypeRegistry) -> Result<(), SaveError>,
+}
+
+/// 历史版本迁移链
+///
+/// 按版本顺序排列，最后一步的目标必须是 `SAVE_VERSION`。
+/// 修改存档结构时：提升 `SAVE_VERSION`，在 `legacy` 中冻结旧结构，并在末尾追加一步迁移。
+pub const SAVE_MIGRATIONS: &[SaveMigration] = &[
+    SaveMigration { from: "0.8.0", to: "0.9.0", migrate: migrate_0_8_to_0_9 },
+    SaveMigration { from: "0.9.0", to: "0.10.0", migrate: migrate_0_9_to_0_10 },
+    SaveMigration { from: "0.10.0", to: "0.11.0", migrate: migrate_0_10_to_0_11 },
+    SaveMigration { from: "0.11.0", to: "0.12.0", migrate: migrate_0_11_to_0_12 },
+    SaveMigration { from: "0.12.0", to: "0.13.0", migrate: migrate_0_12_to_0_13 },
+];
+
+/// 把任意历史版本的存档数据逐步升级到当前版本
+pub fn migrate(mut value: Value, registry: &TypeRegistry) -> Result<SaveData, SaveError> {
+    let mut version = save_version(&value)?;
+
+    while version != SAVE_VERSION {
+        let step = SAVE_MIGRATIONS
+            .iter()
+            .find(|step| step.from == version)
+            .ok_or_else(|| SaveError::Incompatible {
+                found: version.clone(),
+                expected: SAVE_VERSION.to_string(),
+            })?;
+
+        (step.migrate)(&mut value, registry)?;
+        value["info"]["version"] = Value::from(step.to);
+        info!("存档已从 {} 迁移到 {}", step.from, step.to);
+
+        version = step.to.to_string();
+    }
+
+    serde_json::from_value(value).map_err(|e| SaveError::Decode(e.to_string()))
+}
+
+/// 读取存档数据中的版本号
+pub fn save_version(value: &Value) -> Result<String, SaveError> {
+    value
+        .pointer("/info/version")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| SaveError::Decode("存档缺少版本号".to_string()))
+}
+
+fn take_as<T: serde::de::DeserializeOwned>(value: &mut Value) -> Result<T, SaveError> {
+    serde_json::from_value(value.take()).map_err(|e| SaveError::Decode(e.to_string()))
+}
+
+fn put<T: serde::Serialize>(value: &mut Value, data: &T) -> Result<(), SaveError> {
+    *value = serde_json::to_value(data).map_err(|e| SaveError::Encode(e.to_string()))?;
+    Ok(())
+}
+
+/// 按 serde 名称解析枚举（旧存档中以字符串 ID 记录的类型）
+fn parse_id<T: serde::de::DeserializeOwned>(id: &str) -> Option<T> {
+    serde_json::from_value(Value::from(id)).ok()
+}
+
+/// 0.8.0 → 0.9.0
+///
+/// 0.8.0 只记录了玩家等级、位置、资源与任务/成就 ID，没有地图和实体状态：
+/// 迁移后不含地图（载入时沿用当前地图），装备与建筑按 ID 重建，其余实体列表为空。
+fn migrate_0_8_to_0_9(value: &mut Value, _registry: &TypeRegistry) -> Result<(), SaveError> {
+    use legacy::{v0_8, v0_9};
+
+    let old: v0_8::SaveData = take_as(value)?;
+
+    let equipment = |id: &Option<String>| {
+        let id = id.as_deref()?;
+        let equipment_type = parse_id::<EquipmentType>(id);
+        if equipment_type.is_none() {
+            warn!("迁移存档时忽略未知装备: {}", id);
+        }
+        equipment_type.map(|t| Equipment::new(t, EquipmentRarity::Common))
+    };
+
+    let player_data = v0_9::PlayerData {
+        name: old.info.player_name.clone(),
+        level: old.player_data.level,
+        position: old.player_data.position,
+        inventory: old.player_data.inventory,
+        equipment: v0_9::EquipmentData {
+            weapon: equipment(&old.player_data.equipment.weapon),
+            armor: equipment(&old.player_data.equipment.armor),
+            accessory: equipment(&old.player_data.equipment.accessory),
+        },
+        experience: Some(old.player_data.experience),
+        health: Some((old.player_data.health, old.player_data.max_health)),
+    };
+
+    // 0.8.0 没有记录地图，建筑坐标按默认尺寸的地图换算为地块
+    let layout = default_map_layout();
+    let buildings = old
+        .world_data
+        .buildings
+        .iter()
+        .filter_map(|data| {
+            let Some(building_type) = parse_id::<BuildingType>(&data.building_id) else {
+                warn!("迁移存档时忽略未知建筑: {}", data.building_id);
+                return None;
+            };
+            let Some(tile) = layout.world_to_grid(Vec2::new(data.position.0, data.position.1)) else {
+                warn!("迁移存档时跳过地图外的建筑: {}", data.building_id);
+                return None;
+            };
+
+            let mut status = v0_9::BuildingStatus::default();
+            status.health = data.health.min(status.max_health);
+            status.is_damaged = status.health < status.max_health;
+
+            Some(v0_9::BuildingData {
+                building_type,
+                tile: (tile.x, tile.y),
+                level: data.level,
+                current_storage: 0,
+                production_progress: 0.0,
+                is_operational: data.health > 0.0,
+                status,
+            })
+        })
+        .collect();
+
+    let world_data = v0_9::WorldData {
+        game_time: GameTime {
+            day: old.world_data.day.max(1),
+            ..default()
+        },
+        map: None,
+        resources: old.world_data.resources,
+        buildings,
+        plants: Vec::new(),
+        robots: Vec::new(),
+        enemies: Vec::new(),
+        enemy_bases: Vec::new(),
+        dropped_equipment: Vec::new(),
+        wave_config: default(),
+        wave_state: default(),
+    };
+
+    let quest_data = v0_9::QuestData {
+        quests: Vec::new(),
+        quest_log: Some(QuestLog {
+            active_quests: old.quest_data.active_quests,
+            completed_quests: old.quest_data.completed_quests,
+            failed_quests: old.quest_data.failed_quests,
+            current_quest: old.quest_data.current_quest,
+        }),
+    };
+
+    let achievement_data = v0_9::AchievementData {
+        achievements: Vec::new(),
+        achievement_log: Some(AchievementLog {
+            unlocked_achievements: old.achievement_data.unlocked_achievements,
+            total_points: old.achievement_data.total_points,
+            current_streak: 0,
+            max_streak: 0,
+        }),
+    };
+
+    put(value, &v0_9::SaveData {
+        info: old.info,
+        player_data,
+        world_data,
+        quest_data,
+        achievement_data,
+    })
+}
+
+/// 0.9.0 → 0.10.0：逐系统的存档结构 → 反射场景
+///
+/// 按 0.9.0 载入时的规则重建组件，直接拼装为场景；属性取内置定义，
+/// 之后数据文件热重载会再套用当前数值。
+fn migrate_0_9_to_0_10(value: &mut Value, registry: &TypeRegistry) -> Result<(), SaveError> {
+    use legacy::{v0_9, v0_10, v0_11};
+
+    let old: v0_9::SaveData = take_as(value)?;
+    let map_grid = old.world_data.map.as_ref().map(|map| map.to_grid()).transpose().map_err(SaveError::Decode)?;
+
+    // 没有地图时（0.8.0 迁移而来）按默认尺寸的地图换算
+    let layout = default_map_layout();
+    let grid = map_grid.as_ref().unwrap_or(&layout);
+    let tile_position = |tile: (u32, u32)| grid.grid_to_world(UVec2::new(tile.0, tile.1));
+    let position_tile = |position: Vec2| grid.world_to_grid(position).map(|tile| (tile.x, tile.y));
+
+    let mut scene = SceneAssembler::default();
+
+    // 玩家与装备栏
+    let player_data = &old.player_data;
+    let player_position = Vec2::new(player_data.position.0, player_data.position.1);
+    let mut equip = |item: &Option<Equipment>| {
+        item.clone().map(|equipment| {
+            scene.spawn(vec![
+                boxed(equipment),
+                boxed(Transform::from_translation(player_position.extend(1.0))),
+            ])
+        })
+    };
+    let equipment_bar = EquipmentBar {
+        weapon: equip(&player_data.equipment.weapon),
+        armor: equip(&player_data.equipment.armor),
+        accessory: equip(&player_data.equipment.accessory),
+    };
+
+    let mut inventory = v0_10::PlayerInventory::default();
+    for item in &player_data.inventory {
+        match item.item_id.as_str() {
+            "metal" => inventory.metal = item.quantity,
+            "soil" => inventory.soil = item.quantity,
+            "energy" => inventory.energy = item.quantity,
+            other => warn!("迁移存档时忽略未知物品: {}", other),
+        }
+    }
+
+    let mut player_components = vec![
+        boxed(Player {
+            id: 0,
+            name: player_data.name.clone(),
+            level: player_data.level,
+        }),
+        boxed(Transform::from_translation(player_position.extend(1.0))),
+        boxed(inventory),
+        boxed(equipment_bar),
+    ];
+    // 0.8.0 的经验是当前等级内的进度，累计经验按默认曲线补上之前各级所需
+    if let Some(current) = player_data.experience {
+        let curve = LevelCurve::default();
+        let earlier: u32 = (1..player_data.level).filter_map(|level| curve.xp_to_next(level)).sum();
+        player_components.push(boxed(PlayerExperience { current, total: earlier + current }));
+    }
+    if let Some((health, max_health)) = player_data.health {
+        player_components.push(boxed(PlayerVitals {
+            health: health.min(max_health),
+            max_health,
+            ..default()
+        }));
+    }
+    scene.spawn(player_components);
+
+    let world_data = &old.world_data;
+
+    for data in &world_data.buildings {
+        let definition = BuildingDefinition::builtin(data.building_type);
+        let mut building = Building::from_definition(&definition);
+        building.level = data.level;
+        building.apply_definition(&definition, 0.0);
+        building.production_progress = data.production_progress;
+        building.is_operational = data.is_operational;
+        let current_storage = data.current_storage.min(building.stats.storage_capacity);
+        let building = v0_11::Building::new(building, current_storage);
+
+        let position = tile_position(data.tile);
+        scene.spawn(vec![
+            boxed(building),
+            boxed(BuildingPosition { tile_x: data.tile.0, tile_y: data.tile.1 }),
+            boxed(data.status.clone()),
+            boxed(Transform::from_xyz(position.x, position.y, 2.0)),
+        ]);
+    }
+
+    for data in &world_data.plants {
+        let mut components = vec![
+            boxed(data.plant.clone()),
+            boxed(data.growable.clone()),
+            boxed(Transform::from_xyz(data.position.0, data.position.1, 1.0).with_scale(Vec3::splat(data.scale))),
+        ];
+        if let Some(upgrade) = &data.upgrade {
+            components.push(boxed(upgrade.clone()));
+        }
+        if data.harvestable {
+            components.push(boxed(Harvestable));
+        }
+        scene.spawn(components);
+    }
+
+    for data in &world_data.robots {
+        let mut robot = Robot::new(data.robot_type);
+        robot.current_task = data.current_task;
+        robot.energy = data.energy.min(robot.max_energy);
+        robot.efficiency = data.efficiency;
+        robot.target_position = data.target_position.map(|(x, y)| Vec2::new(x, y));
+
+        scene.spawn(vec![
+            boxed(robot),
+            boxed(data.inventory.clone()),
+            boxed(Transform::from_xyz(data.position.0, data.position.1, 2.0)),
+        ]);
+    }
+
+    for data in &world_data.enemies {
+        let position = Vec2::new(data.position.0, data.position.1);
+        let Some((tile_x, tile_y)) = position_tile(position) else {
+            warn!("迁移存档时跳过地图外的敌人: {}", data.archetype);
+            continue;
+        };
+
+        let mut enemy = Enemy::from_definition(&EnemyDefinition::builtin(data.enemy_type), data.level);
+        enemy.archetype = data.archetype.clone();
+        enemy.current_health = data.current_health.min(enemy.stats.health);
+        enemy.ai_state = data.ai_state;
+
+        let mut components = vec![
+            boxed(enemy),
+            boxed(EnemyPosition { tile_x, tile_y }),
+            boxed(Transform::from_xyz(position.x, position.y, 1.0)),
+        ];
+        if let Some(wave) = data.wave {
+            components.push(boxed(WaveMember { wave }));
+        }
+        scene.spawn(components);
+    }
+
+    for data in &world_data.enemy_bases {
+        let position = tile_position(data.tile);
+        scene.spawn(vec![
+            boxed(data.base.clone()),
+            boxed(EnemyPosition { tile_x: data.tile.0, tile_y: data.tile.1 }),
+            boxed(Transform::from_xyz(position.x, position.y, 0.5)),
+        ]);
+    }
+
+    for data in &world_data.dropped_equipment {
+        scene.spawn(vec![
+            boxed(data.equipment.clone()),
+            boxed(Transform::from_xyz(data.position.0, data.position.1, 1.0)),
+        ]);
+    }
+
+    for quest in &old.quest_data.quests {
+        scene.spawn(vec![boxed(quest.clone())]);
+    }
+    if let Some(quest_log) = &old.quest_data.quest_log {
+        scene.spawn(vec![boxed(quest_log.clone())]);
+    }
+
+    for achievement in &old.achievement_data.achievements {
+        scene.spawn(vec![boxed(achievement.clone())]);
+    }
+    if let Some(achievement_log) = &old.achievement_data.achievement_log {
+        scene.spawn(vec![boxed(achievement_log.clone())]);
+    }
+
+    // 全局资源
+    let mut base_inventory = v0_10::BaseInventory::default();
+    for resource in &world_data.resources {
+        match resource.resource_id.as_str() {
+            "energy" => base_inventory.energy = resource.amount,
+            "metal" => base_inventory.metal = resource.amount,
+            "crystal" => base_inventory.crystal = resource.amount,
+            "organic" => base_inventory.organic = resource.amount,
+            other => warn!("迁移存档时忽略未知资源: {}", other),
+        }
+    }
+
+    if let Some(map_grid) = map_grid {
+        scene.resource(map_grid);
+    }
+    scene.resource(world_data.game_time.clone());
+    scene.resource(base_inventory);
+    scene.resource(world_data.wave_config.clone());
+    scene.resource(world_data.wave_state.clone());
+
+    let scene = scene
+        .build()
+        .serialize(&v0_10::type_registry(registry))
+        .map_err(|e| SaveError::Encode(format!("场景序列化失败: {}", e)))?;
+
+    put(value, &v0_10::SaveData { info: old.info, scene })
+}
+
+/// 0.10.0 → 0.11.0：统一库存
+///
+/// 玩家背包、机器人货舱和两份全局库存改为同一种按物品 ID 记录的 `Inventory`。
+/// 旧的建筑库存与全局资源库存合并为基地库存，玩家背包放不下的部分也转入基地库存。
+fn migrate_0_10_to_0_11(value: &mut Value, registry: &TypeRegistry) -> Result<(), SaveError> {
+    use legacy::{v0_10, v0_11};
+
+    let old: v0_10::SaveData = take_as(value)?;
+    let mut scene = parse_scene(&old.scene, &v0_10::type_registry(registry))?;
+
+    let items = ItemRegistry::default();
+    let mut base_inventory = Inventory::default();
+
+    for entity in &mut scene.entities {
+        for component in &mut entity.components {
+            if let Some(old_inventory) = reflect_as::<v0_10::PlayerInventory>(component.as_ref())? {
+                let mut inventory = Inventory::with_slots(PLAYER_INVENTORY_SLOTS);
+                for (item, amount) in old_inventory.items() {
+                    let added = inventory.add(item, amount, &items);
+                    base_inventory.add(item, amount - added, &items);
+                }
+                *component = boxed(inventory);
+            } else if let Some(old_inventory) = reflect_as::<v0_10::RobotInventory>(component.as_ref())? {
+                let mut inventory = Inventory::with_capacity(old_inventory.capacity);
+                if let Some(resource_type) = old_inventory.resource_type.filter(|_| old_inventory.current > 0) {
+                    inventory.items.insert(resource_type.item(), old_inventory.current);
+                }
+                *component = boxed(inventory);
+            }
+        }
+    }
+
+    let mut resources = Vec::new();
+    for resource in std::mem::take(&mut scene.resources) {
+        let old_items = if let Some(old_inventory) = reflect_as::<v0_10::BaseInventory>(resource.as_ref())? {
+            old_inventory.items()
+        } else if let Some(old_inventory) = reflect_as::<v0_10::PlayerInventory>(resource.as_ref())? {
+            old_inventory.items()
+        } else {
+            resources.push(resource);
+            continue;
+        };
+        for (item, amount) in old_items {
+            base_inventory.add(item, amount, &items);
+        }
+    }
+    resources.push(boxed(base_inventory));
+    scene.resources = resources;
+
+    let scene = scene
+        .serialize(&v0_11::type_registry(registry))
+        .map_err(|e| SaveError::Encode(format!("场景序列化失败: {}", e)))?;
+
+    put(value, &SaveData { info: old.info, scene })
+}
+
+/// 0.11.0 → 0.12.0：建筑本地库存与生产状态
+///
+/// 建筑的库存数量改为本地 `Inventory`，记为第一种产出（没有产出的建筑丢弃旧数量）；
+/// 生产状态从空闲开始，由生产系统在下一帧更新。
+fn migrate_0_11_to_0_12(value: &mut Value, registry: &TypeRegistry) -> Result<(), SaveError> {
+    use legacy::v0_11;
+
+    let old: SaveData = take_as(value)?;
+    let mut scene = parse_scene(&old.scene, &v0_11::type_registry(registry))?;
+
+    let items = ItemRegistry::default();
+
+    for entity in &mut scene.entities {
+        let mut storage = None;
+        for component in &mut entity.components {
+            if let Some(old_building) = reflect_as::<v0_11::Building>(component.as_ref())? {
+                let capacity = old_building.stats.storage_capacity;
+                if capacity > 0 {
+                    let mut inventory = Inventory::with_capacity(capacity);
+                    if let Some(&item) = old_building.stats.outputs.keys().next() {
+                        inventory.add(item, old_building.current_storage, &items);
+                    }
+                    storage = Some(inventory);
+                }
+                *component = boxed(old_building.into_current());
+            } else if let Some(old_status) = reflect_as::<v0_11::BuildingStatus>(component.as_ref())? {
+                *component = boxed(old_status.into_current());
+            }
+        }
+
+        // 仓库建筑原本就有库存
+        let has_inventory = entity.components.iter().any(|component| {
+            component
+                .get_represented_type_info()
+                .is_some_and(|info| info.type_path() == Inventory::type_path())
+        });
+        if let Some(inventory) = storage.filter(|_| !has_inventory) {
+            entity.components.push(boxed(inventory));
+        }
+    }
+
+    let scene = scene
+        .serialize(registry)
+        .map_err(|e| SaveError::Encode(format!("场景序列化失败: {}", e)))?;
+
+    put(value, &SaveData { info: old.info, scene })
+}
+
+/// 0.12.0 → 0.13.0：基地库存从全局资源移到基地实体上
+///
+/// 旧存档没有基地库存资源时生成空库存的基地。
+fn migrate_0_12_to_0_13(value: &mut Value, registry: &TypeRegistry) -> Result<(), SaveError> {
+    let old: SaveData = take_as(value)?;
+    let mut scene = parse_scene(&old.scene, registry)?;
+
+    let mut base_inventory = Inventory::default();
+    let mut resources = Vec::new();
+    for resource in std::mem::take(&mut scene.resources) {
+        match reflect_as::<Inventory>(resource.as_ref())? {
+            Some(inventory) => base_inventory = inventory,
+            None => resources.push(resource),
+        }
+    }
+    scene.resources = resources;
+
+    // 取一个场景中未使用的实体 ID
+    let entity = (0..)
+        .filter_map(Entity::from_raw_u32)
+        .find(|entity| scene.entities.iter().all(|existing| existing.entity.index() != entity.index()))
+        .ok_or_else(|| SaveError::Decode("场景实体过多".to_string()))?;
+    scene.entities.push(DynamicEntity {
+        entity,
+        components: vec![boxed(Base), boxed(base_inventory), boxed(Saveable)],
+    });
+
+    let scene = scene
+        .serialize(registry)
+        .map_err(|e| SaveError::Encode(format!("场景序列化失败: {}", e)))?;
+
+    put(value, &SaveData { info: old.info, scene })
+}
+
+/// 按指定类型注册表解析场景（旧版本场景含有已移除的类型）
+fn parse_scene(scene: &str, registry: &TypeRegistry) -> Result<DynamicScene, SaveError> {
+    let mut deserializer = ron::de::Deserializer::from_str(scene)
+        .map_err(|e| SaveError::Decode(format!("场景解析失败: {}", e)))?;
+
+    SceneDeserializer { type_registry: registry }
+        .deserialize(&mut deserializer)
+        .map_err(|e| SaveError::Decode(format!("场景解析失败: {}", e)))
+}
+
+/// 场景中的组件或资源是 `T` 时还原为具体类型
+fn reflect_as<T: FromReflect + TypePath>(value: &dyn PartialReflect) -> Result<Option<T>, SaveError> {
+    let is_type = value
+        .get_represented_type_info()
+        .is_some_and(|info| info.type_path() == T::type_path());
+    if !is_type {
+        return Ok(None);
+    }
+
+    T::from_reflect(value)
+        .map(Some)
+        .ok_or_else(|| SaveError::Decode(format!("{} 数据损坏", T::type_path())))
+}
+
+fn boxed<T: PartialReflect>(value: T) -> Box<dyn PartialReflect> {
+    Box::new(value)
+}
+
+/// 迁移时直接拼装场景（不经过 World）
+#[derive(Default)]
+struct SceneAssembler {
+    entities: Vec<DynamicEntity>,
+    resources: Vec<Box<dyn PartialReflect>>,
+}
+
+impl SceneAssembler {
+    /// 添加一个可存档实体，返回场景内的实体 ID（供装备栏等引用）
+    fn spawn(&mut self, mut components: Vec<Box<dyn PartialReflect>>) -> Entity {
+        let entity = Entity::from_raw_u32(self.entities.len() as u32).unwrap();
+        components.push(boxed(Saveable));
+        self.entities.push(DynamicEntity { entity, components });
+        entity
+    }
+
+    fn resource<T: Reflect>(&mut self, resource: T) {
+        self.resources.push(boxed(resource));
+    }
+
+    fn build(self) -> DynamicScene {
+        DynamicScene {
+            resources: self.resources,
+            entities: self.entities,
+        }
+    }
+}
+
+/// 冻结的历史存档结构（只用于迁移，不要随当前结构修改）
+mod legacy {
+    /// 0.8.0：占位存档结构
+    pub mod v0_8 {
+        use serde::Deserialize;
+        use crate::components::save::SaveInfo;
+
+        #[derive(Deserialize)]
+        pub struct SaveData {
+            pub info: SaveInfo,
+            pub player_data: PlayerData,
+            pub world_data: WorldData,
+            pub quest_data: QuestData,
+            pub achievement_data: AchievementData,
+        }
+
+        #[derive(Deserialize)]
+        pub struct PlayerData {
+            pub level: u32,
+            pub experience: u32,
+            pub health: f32,
+            pub max_health: f32,
+            pub position: (f32, f32),
+            pub inventory: Vec<super::v0_9::ItemData>,
+            pub equipment: EquipmentData,
+        }
+
+        #[derive(Deserialize)]
+        pub struct EquipmentData {
+            pub weapon: Option<String>,
+            pub armor: Option<String>,
+            pub accessory: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        pub struct WorldData {
+            pub day: u32,
+            pub buildings: Vec<BuildingData>,
+            pub resources: Vec<super::v0_9::ResourceData>,
+        }
+
+        #[derive(Deserialize)]
+        pub struct BuildingData {
+            pub building_id: String,
+            pub position: (f32, f32),
+            pub level: u32,
+            pub health: f32,
+        }
+
+        #[derive(Deserialize)]
+        pub struct QuestData {
+            pub active_quests: Vec<String>,
+            pub completed_quests: Vec<String>,
+            pub failed_quests: Vec<String>,
+            pub current_quest: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        pub struct AchievementData {
+            pub unlocked_achievements: Vec<String>,
+            pub total_points: u32,
+        }
+    }
+
+    /// 0.9.0：逐系统的存档结构
+    pub mod v0_9 {
+        use serde::{Serialize, Deserialize};
+        use crate::components::achievement::{Achievement, AchievementLog};
+        use crate::components::building::BuildingType;
+        use crate::components::enemy::{AIState, EnemyBase, EnemyType, EnemyWaveConfig, EnemyWaveState};
+        use crate::components::equipment::Equipment;
+        use crate::components::plant::{Growable, Plant};
+        use crate::components::plant_upgrade::PlantUpgrade;
+        use crate::components::quest::{Quest, QuestLog};
+        use crate::components::robot::{RobotTask, RobotType};
+        use crate::components::save::SaveInfo;
+        use crate::resources::world::{MapGrid, TileType};
+        use crate::systems::time::GameTime;
+
+        #[derive(Serialize, Deserialize)]
+        pub struct SaveData {
+            pub info: SaveInfo,
+            pub player_data: PlayerData,
+            pub world_data: WorldData,
+            pub quest_data: QuestData,
+            pub achievement_data: AchievementData,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct PlayerData {
+            pub name: String,
+            pub level: u32,
+            pub position: (f32, f32),
+            pub inventory: Vec<ItemData>,
+            pub equipment: EquipmentData,
+            // 0.9.0 存档不记录以下两项，只在从 0.8.0 迁移时中转
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub experience: Option<u32>,        // 当前等级内的经验
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub health: Option<(f32, f32)>,     // 当前生命与上限
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct ItemData {
+            pub item_id: String,
+            pub quantity: u32,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct EquipmentData {
+            pub weapon: Option<Equipment>,
+            pub armor: Option<Equipment>,
+            pub accessory: Option<Equipment>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct WorldData {
+            pub game_time: GameTime,
+            pub map: Option<MapData>,       // 0.8.0 迁移而来的存档没有地图
+            pub resources: Vec<ResourceData>,
+            pub buildings: Vec<BuildingData>,
+            pub plants: Vec<PlantData>,
+            pub robots: Vec<RobotData>,
+            pub enemies: Vec<EnemyData>,
+            pub enemy_bases: Vec<EnemyBaseData>,
+            pub dropped_equipment: Vec<DroppedEquipmentData>,
+            pub wave_config: EnemyWaveConfig,
+            pub wave_state: EnemyWaveState,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct MapData {
+            pub width: u32,
+            pub height: u32,
+            pub seed: u64,
+            pub fog_of_war: bool,
+            pub tiles: Vec<TileType>,
+            pub explored: Vec<bool>,
+        }
+
+        impl MapData {
+            pub fn to_grid(&self) -> Result<MapGrid, String> {
+                let tile_count = (self.width * self.height) as usize;
+                if self.tiles.len() != tile_count || self.explored.len() != tile_count {
+                    return Err(format!(
+                        "地图数据损坏: {}x{} 地图需要 {} 个地块，实际 {} 个",
+                        self.width, self.height, tile_count, self.tiles.len()
+                    ));
+                }
+
+                let mut map_grid = MapGrid::new(self.width, self.height, self.seed);
+                map_grid.fog_of_war = self.fog_of_war;
+                for (tile, (&tile_type, &explored)) in map_grid.tiles.iter_mut().zip(self.tiles.iter().zip(&self.explored)) {
+                    tile.tile_type = tile_type;
+                    tile.explored = explored;
+                }
+
+                Ok(map_grid)
+            }
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct BuildingData {
+            pub building_type: BuildingType,
+            pub tile: (u32, u32),
+            pub level: u32,
+            pub current_storage: u32,
+            pub production_progress: f32,
+            pub is_operational: bool,
+            pub status: BuildingStatus,
+        }
+
+        pub use super::v0_11::BuildingStatus;
+
+        #[derive(Serialize, Deserialize)]
+        pub struct ResourceData {
+            pub resource_id: String,
+            pub amount: u32,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct PlantData {
+            pub position: (f32, f32),
+            pub scale: f32,
+            pub plant: Plant,
+            pub growable: Growable,
+            pub upgrade: Option<PlantUpgrade>,
+            pub harvestable: bool,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct RobotData {
+            pub robot_type: RobotType,
+            pub position: (f32, f32),
+            pub current_task: RobotTask,
+            pub energy: f32,
+            pub efficiency: f32,
+            pub target_position: Option<(f32, f32)>,
+            pub inventory: super::v0_10::RobotInventory,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct EnemyData {
+            pub archetype: String,
+            pub enemy_type: EnemyType,
+            pub level: u32,
+            pub current_health: f32,
+            pub ai_state: AIState,
+            pub position: (f32, f32),
+            pub wave: Option<u32>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct EnemyBaseData {
+            pub base: EnemyBase,
+            pub tile: (u32, u32),
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct DroppedEquipmentData {
+            pub equipment: Equipment,
+            pub position: (f32, f32),
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct QuestData {
+            pub quests: Vec<Quest>,
+            pub quest_log: Option<QuestLog>,
+        }
+
+        #[derive(Serialize, Deserialize)]
+        pub struct AchievementData {
+            pub achievements: Vec<Achievement>,
+            pub achievement_log: Option<AchievementLog>,
+        }
+    }
+
+    /// 0.10.0：反射场景存档，库存按资源字段记录
+    pub mod v0_10 {
+        use bevy::prelude::*;
+        use bevy::reflect::TypeRegistry;
+        use serde::{Serialize, Deserialize};
+        use crate::components::item::{amounts, ItemAmounts, ItemId};
+        use crate::components::save::SaveInfo;
+
+        #[derive(Serialize, Deserialize)]
+        pub struct SaveData {
+            pub info: SaveInfo,
+            pub scene: String,
+        }
+
+        /// `components::resource::Inventory`：玩家背包，同时注册为全局资源
+        #[derive(Reflect, Default)]
+        #[type_path = "dark_forest::components::resource"]
+        #[type_name = "Inventory"]
+        pub struct PlayerInventory {
+            pub metal: u32,
+            pub soil: u32,
+            pub energy: u32,
+        }
+
+        impl PlayerInventory {
+            pub fn items(&self) -> ItemAmounts {
+                amounts(&[(ItemId::METAL, self.metal), (ItemId::SOIL, self.soil), (ItemId::ENERGY, self.energy)])
+            }
+        }
+
+        /// `components::building::Inventory`：建筑使用的全局资源
+        #[derive(Reflect, Default)]
+        #[type_path = "dark_forest::components::building"]
+        #[type_name = "Inventory"]
+        pub struct BaseInventory {
+            pub energy: u32,
+            pub metal: u32,
+            pub crystal: u32,
+            pub organic: u32,
+        }
+
+        impl BaseInventory {
+            pub fn items(&self) -> ItemAmounts {
+                amounts(&[
+                    (ItemId::ENERGY, self.energy),
+                    (ItemId::METAL, self.metal),
+                    (ItemId::CRYSTAL, self.crystal),
+                    (ItemId::ORGANIC, self.organic),
+                ])
+            }
+        }
+
+        /// `components::robot::RobotInventory`：机器人货舱（0.9.0 也按此结构记录）
+        #[derive(Reflect, Clone, Serialize, Deserialize)]
+        #[type_path = "dark_forest::components::robot"]
+        pub struct RobotInventory {
+            pub capacity: u32,
+            pub current: u32,
+            pub resource_type: Option<ResourceType>,
+        }
+
+        /// `components::resource::ResourceType`
+        #[derive(Reflect, Clone, Copy, Serialize, Deserialize)]
+        #[type_path = "dark_forest::components::resource"]
+        pub enum ResourceType {
+            Metal,
+            Soil,
+            Energy,
+        }
+
+        impl ResourceType {
+            pub fn item(self) -> ItemId {
+                match self {
+                    ResourceType::Metal => ItemId::METAL,
+                    ResourceType::Soil => ItemId::SOIL,
+                    ResourceType::Energy => ItemId::ENERGY,
+                }
+            }
+        }
+
+        /// 读写 0.10.0 场景用的类型注册表：0.11.0 的类型加上已移除的旧类型
+        pub fn type_registry(registry: &TypeRegistry) -> TypeRegistry {
+            let mut legacy = super::v0_11::type_registry(registry);
+            legacy.register::<PlayerInventory>();
+            legacy.register::<BaseInventory>();
+            legacy.register::<RobotInventory>();
+            legacy
+        }
+    }
+
+    /// 0.11.0：建筑只记录库存数量，没有本地库存与生产状态
+    pub mod v0_11 {
+        use std::any::TypeId;
+        use bevy::prelude::*;
+        use bevy::reflect::TypeRegistry;
+        use serde::{Serialize, Deserialize};
+        use crate::components::building::{self, BuildingStats, BuildingType, ProductionState, UpgradeCurve};
+
+        /// `components::building::Building`
+        #[derive(Reflect, Clone)]
+        #[type_path = "dark_forest::components::building"]
+        pub struct Building {
+            pub building_type: BuildingType,
+            pub level: u32,
+            pub base_stats: BuildingStats,
+            pub stats: BuildingStats,
+            pub upgrade: UpgradeCurve,
+            pub current_storage: u32,
+            pub production_progress: f32,
+            pub is_operational: bool,
+        }
+
+        impl Building {
+            pub fn new(building: building::Building, current_storage: u32) -> Self {
+                Self {
+                    building_type: building.building_type,
+                    level: building.level,
+                    base_stats: building.base_stats,
+                    stats: building.stats,
+                    upgrade: building.upgrade,
+                    current_storage,
+                    production_progress: building.production_progress,
+                    is_operational: building.is_operational,
+                }
+            }
+
+            pub fn into_current(self) -> building::Building {
+                building::Building {
+                    building_type: self.building_type,
+                    level: self.level,
+                    base_stats: self.base_stats,
+                    stats: self.stats,
+                    upgrade: self.upgrade,
+                    production_progress: self.production_progress,
+                    is_operational: self.is_operational,
+                }
+            }
+        }
+
+        /// `components::building::BuildingStatus`（0.9.0 也按此结构记录）
+        #[derive(Reflect, Clone, Serialize, Deserialize)]
+        #[type_path = "dark_forest::components::building"]
+        pub struct BuildingStatus {
+            pub is_constructing: bool,
+            pub construction_progress: f32,
+            pub is_damaged: bool,
+            pub health: f32,
+            pub max_health: f32,
+        }
+
+        impl Default for BuildingStatus {
+            fn default() -> Self {
+                Self {
+                    is_constructing: true,
+                    construction_progress: 0.0,
+                    is_damaged: false,
+                    health: 100.0,
+                    max_health: 100.0,
+                }
+            }
+        }
+
+        impl BuildingStatus {
+            pub fn into_current(self) -> building::BuildingStatus {
+                building::BuildingStatus {
+                    is_constructing: self.is_constructing,
+                    construction_progress: self.construction_progress,
+                    is_damaged: self.is_damaged,
+                    health: self.health,
+                    max_health: self.max_health,
+                    production: ProductionState::Idle,
+                }
+            }
+        }
+
+        /// 读写 0.11.0 场景用的类型注册表：当前类型中的建筑与建筑状态换成旧结构
+        pub fn type_registry(registry: &TypeRegistry) -> TypeRegistry {
+            let replaced = [TypeId::of::<building::Building>(), TypeId::of::<building::BuildingStatus>()];
+            let mut legacy = TypeRegistry::empty();
+            for registration in registry.iter() {
+                if !replaced.contains(&registration.type_id()) {
+                    legacy.add_registration(registration.clone());
+                }
+            }
+            legacy.register::<Building>();
+            legacy.register::<BuildingStatus>();
+            legacy
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::building::BuildingStatus;
+    use crate::components::enemy::EnemyBase;
+    use crate::components::item::ItemId;
+    use crate::resources::world::MapGrid;
+    use crate::systems::save_file;
+    use crate::systems::save_manager::{apply_save_data, SaveManagerPlugin};
+
+    /// 读取历史版本的存档样本
+    fn fixture(version: &str) -> Value {
+        let path = format!("{}/tests/fixtures/saves/{}.json", env!("CARGO_MANIFEST_DIR"), version);
+        let bytes = std::fs::read(&path).unwrap_or_else(|e| panic!("读取 {} 失败: {}", path, e));
+        save_file::decode(&bytes).unwrap()
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(SaveManagerPlugin);
+        app
+    }
+
+    /// 迁移样本并载入到新世界
+    fn load_fixture(version: &str) -> App {
+        let mut app = test_app();
+        let world = app.world_mut();
+
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let save_data = migrate(fixture(version), &type_registry.read()).unwrap();
+        assert_eq!(save_data.info.version, SAVE_VERSION);
+
+        apply_save_data(world, &save_data).unwrap();
+        app
+    }
+
+    fn count<C: Component>(world: &mut World) -> usize {
+        world.query::<&C>().iter(world).count()
+    }
+
+    fn base_inventory(world: &mut World) -> Inventory {
+        world.query_filtered::<&Inventory, With<Base>>().single(world).unwrap().clone()
+    }
+
+    /// 0.9.0 起的样本都包含完整的世界状态
+    fn assert_full_world(world: &mut World) {
+        let (player, inventory, equipment_bar) = world
+            .query::<(&Player, &Inventory, &EquipmentBar)>()
+            .single(world)
+            .unwrap();
+        assert_eq!(player.name, "Ranger");
+        assert_eq!(player.level, 4);
+        assert_eq!(inventory.count(ItemId::METAL), 25);
+        assert_eq!(inventory.slots, Some(PLAYER_INVENTORY_SLOTS));
+        assert!(equipment_bar.armor.is_none());
+
+        // 装备栏引用映射到新生成的装备实体
+        let weapon = equipment_bar.weapon.expect("武器未还原");
+        let weapon = world.get::<Equipment>(weapon).expect("装备栏指向的实体不是装备");
+        assert_eq!(weapon.equipment_type, EquipmentType::Railgun);
+        assert_eq!(count::<Equipment>(world), 2);
+
+        let map_grid = world.resource::<MapGrid>();
+        assert_eq!((map_grid.width(), map_grid.height()), (4, 4));
+        assert_eq!(world.resource::<GameTime>().day, 5);
+        assert_eq!(base_inventory(world).count(ItemId::CRYSTAL), 30);
+
+        let (building, position) = world.query::<(&Building, &BuildingPosition)>().single(world).unwrap();
+        assert_eq!(building.building_type, BuildingType::MetalMine);
+        assert_eq!(building.level, 2);
+        assert_eq!((position.tile_x, position.tile_y), (1, 2));
+        let building_inventory = world.query_filtered::<&Inventory, With<Building>>().single(world).unwrap();
+        assert_eq!(building_inventory.count(ItemId::METAL), 12);
+
+        let (enemy, position) = world.query::<(&Enemy, &EnemyPosition)>().single(world).unwrap();
+        assert_eq!(enemy.archetype, "ScoutBot");
+        assert_eq!((position.tile_x, position.tile_y), (2, 1));
+
+        assert_eq!(count::<EnemyBase>(world), 1);
+        assert_eq!(count::<crate::components::plant::Plant>(world), 1);
+        let robot_inventory = world.query_filtered::<&Inventory, With<Robot>>().single(world).unwrap();
+        assert_eq!(robot_inventory.capacity, Some(20));
+        assert_eq!(count::<crate::components::quest::Quest>(world), 1);
+        assert_eq!(count::<crate::components::achievement::Achievement>(world), 1);
+        assert_eq!(world.query::<&QuestLog>().single(world).unwrap().current_quest.as_deref(), Some("first_steps"));
+    }
+
+    #[test]
+    fn migration_chain_reaches_current_version() {
+        for pair in SAVE_MIGRATIONS.windows(2) {
+            assert_eq!(pair[0].to, pair[1].from, "迁移链在 {} 处断开", pair[0].to);
+        }
+        assert_eq!(SAVE_MIGRATIONS.last().unwrap().to, SAVE_VERSION);
+        assert!(SAVE_MIGRATIONS.iter().all(|step| step.from != SAVE_VERSION));
+    }
+
+    #[test]
+    fn migrates_0_8_0() {
+        let mut app = load_fixture("0.8.0");
+        let world = app.world_mut();
+
+        let (player, inventory, equipment_bar) = world
+            .query::<(&Player, &Inventory, &EquipmentBar)>()
+            .single(world)
+            .unwrap();
+        assert_eq!(player.name, "Player");
+        assert_eq!(player.level, 3);
+        assert_eq!((inventory.count(ItemId::METAL), inventory.count(ItemId::ENERGY)), (40, 15));
+        // 未知装备 ID 被忽略
+        assert!(equipment_bar.accessory.is_none());
+        let weapon = world.get::<Equipment>(equipment_bar.weapon.unwrap()).unwrap();
+        assert_eq!(weapon.equipment_type, EquipmentType::LaserGun);
+
+        // 经验与生命带入新组件
+        let (experience, vitals) = world
+            .query_filtered::<(&PlayerExperience, &PlayerVitals), With<Player>>()
+            .single(world)
+            .unwrap();
+        assert_eq!((experience.current, experience.total), (250, 100 + 150 + 250));
+        assert_eq!((vitals.health, vitals.max_health), (80.0, 100.0));
+
+        // 未知建筑 ID 被跳过，血量为 0 的建筑停止运转
+        let mut buildings: Vec<_> = world
+            .query::<(&Building, &BuildingStatus, &BuildingPosition, &Transform)>()
+            .iter(world)
+            .map(|(building, status, position, transform)| {
+                (building.building_type, building.level, building.is_operational, status.health, (position.tile_x, position.tile_y), transform.translation.truncate())
+            })
+            .collect();
+        buildings.sort_by_key(|(building_type, ..)| format!("{:?}", building_type));
+        assert_eq!(buildings.len(), 2);
+        assert_eq!(buildings[0].0, BuildingType::MetalMine);
+        assert_eq!(buildings[0].1, 2);
+        assert_eq!(buildings[1].0, BuildingType::Turret);
+        assert!(!buildings[1].2);
+        // 坐标按默认地图（20x20，以世界原点为中心）换算，地块中心与地图一致
+        let layout = default_map_layout();
+        for (.., tile, translation) in &buildings {
+            assert_eq!(layout.world_to_grid(*translation), Some(UVec2::new(tile.0, tile.1)));
+            assert_eq!(*translation, layout.grid_to_world(UVec2::new(tile.0, tile.1)));
+        }
+        assert_eq!(buildings[0].4, (13, 12));
+
+        assert_eq!(world.resource::<GameTime>().day, 2);
+        assert_eq!(base_inventory(world).count(ItemId::ENERGY), 120);
+        assert!(world.get_resource::<MapGrid>().is_none());
+
+        let quest_log = world.query::<&QuestLog>().single(world).unwrap();
+        assert_eq!(quest_log.completed_quests, vec!["wake_up".to_string()]);
+        let achievement_log = world.query::<&AchievementLog>().single(world).unwrap();
+        assert_eq!(achievement_log.total_points, 10);
+    }
+
+    #[test]
+    fn migrates_0_9_0() {
+        let mut app = load_fixture("0.9.0");
+        assert_full_world(app.world_mut());
+    }
+
+    #[test]
+    fn migrates_0_10_0() {
+        let mut app = load_fixture("0.10.0");
+        assert_full_world(app.world_mut());
+    }
+
+    #[test]
+    fn migrates_0_11_0() {
+        let mut app = load_fixture("0.11.0");
+        assert_full_world(app.world_mut());
+    }
+
+    #[test]
+    fn migrates_0_12_0() {
+        let mut app = load_fixture("0.12.0");
+        assert_full_world(app.world_mut());
+    }
+
+    #[test]
+    fn loads_0_13_0() {
+        let mut app = load_fixture("0.13.0");
+        assert_full_world(app.world_mut());
+    }
+
+    #[test]
+    fn current_save_needs_no_migration() {
+        let mut app = load_fixture("0.13.0");
+        let world = app.world_mut();
+        let save_data = crate::systems::save_manager::collect_save_data(world, "roundtrip", crate::components::save::SaveType::Manual, None).unwrap();
+
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let value = serde_json::to_value(&save_data).unwrap();
+        let migrated = migrate(value, &type_registry.read()).unwrap();
+        assert_eq!(migrated.scene, save_data.scene);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let app = test_app();
+        let type_registry = app.world().resource::<AppTypeRegistry>().clone();
+
+        for version in ["0.7.0", "99.0.0"] {
+            let mut value = fixture("0.8.0");
+            value["info"]["version"] = Value::from(version);
+            match migrate(value, &type_registry.read()) {
+                Err(SaveError::Incompatible { found, expected }) => {
+                    assert_eq!(found, version);
+                    assert_eq!(expected, SAVE_VERSION);
+                }
+                other => panic!("{} 应当不兼容: {:?}", version, other.map(|data| data.info.version)),
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_missing_version() {
+        let app = test_app();
+        let type_registry = app.world().resource::<AppTypeRegistry>().clone();
+
+        let mut value = fixture("0.8.0");
+        value["info"].as_object_mut().unwrap().remove("version");
+        assert!(matches!(migrate(value, &type_registry.read()), Err(SaveError::Decode(_))));
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use crate::components::save::{SaveInfo, SaveType};
+use crate::components::save::{SaveError, SaveInfo, SaveManager, SaveType};
+use crate::systems::save_manager::{get_save_list, latest_save_id, LoadGameEvent, SaveGameEvent};
 
 /// 存档UI系统插件
 pub struct SaveUIPlugin;
@@ -20,32 +21,64 @@ pub struct SaveUIState {
     pub show_load_menu: bool,
     pub selected_save_id: Option<String>,
     pub save_list: Vec<SaveInfo>,
+    pub pending_delete: Option<String>,         // 等待确认删除的存档ID
+    pub editing_description: Option<String>,    // 正在编辑的选中存档描述
 }
 
 /// 更新存档UI
 fn update_save_ui(
     mut ui_state: ResMut<SaveUIState>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    save_manager: Res<SaveManager>,
+    mut save_events: MessageWriter<SaveGameEvent>,
+    mut load_events: MessageWriter<LoadGameEvent>,
 ) {
-    // ESC键关闭存档菜单
+    // ESC键先取消删除确认，再关闭存档菜单（编辑描述时由输入框处理）
     if keyboard_input.just_pressed(KeyCode::Escape) {
-        if ui_state.show_save_menu || ui_state.show_load_menu {
-            ui_state.show_save_menu = false;
-            ui_state.show_load_menu = false;
-            ui_state.selected_save_id = None;
+        if ui_state.pending_delete.is_some() {
+            ui_state.pending_delete = None;
+        } else if ui_state.show_save_menu || ui_state.show_load_menu {
+            hide_save_menu(&mut ui_state);
+        }
+    }
+
+    // F6键打开存档菜单，F7键打开读档菜单（再按一次关闭）
+    if keyboard_input.just_pressed(KeyCode::F6) {
+        if ui_state.show_save_menu {
+            hide_save_menu(&mut ui_state);
+        } else {
+            show_save_menu(&mut ui_state);
+            refresh_save_list(&mut ui_state, &save_manager);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::F7) {
+        if ui_state.show_load_menu {
+            hide_save_menu(&mut ui_state);
+        } else {
+            show_load_menu(&mut ui_state);
+            refresh_save_list(&mut ui_state, &save_manager);
         }
     }
 
     // F5键快速存档
     if keyboard_input.just_pressed(KeyCode::F5) {
-        // 触发快速存档
         info!("触发快速存档");
+        save_events.write(SaveGameEvent {
+            save_type: SaveType::Quick,
+            description: None,
+            slot: None,
+        });
     }
 
-    // F9键快速加载
+    // F9键加载最新的快速存档
     if keyboard_input.just_pressed(KeyCode::F9) {
-        // 触发快速加载
-        info!("触发快速加载");
+        match latest_save_id(&save_manager, SaveType::Quick) {
+            Some(save_id) => {
+                info!("触发快速加载: {}", save_id);
+                load_events.write(LoadGameEvent { save_id });
+            }
+            None => info!("没有可加载的快速存档"),
+        }
     }
 }
 
@@ -66,11 +99,45 @@ pub fn hide_save_menu(ui_state: &mut SaveUIState) {
     ui_state.show_save_menu = false;
     ui_state.show_load_menu = false;
     ui_state.selected_save_id = None;
+    ui_state.pending_delete = None;
+    ui_state.editing_description = None;
+}
+
+/// 重新读取存档列表
+///
+/// 选中的存档已不存在时取消选择；存档目录还不存在时列表为空。
+pub fn refresh_save_list(ui_state: &mut SaveUIState, save_manager: &SaveManager) {
+    ui_state.save_list = match get_save_list(save_manager) {
+        Ok(saves) => saves,
+        Err(e) => {
+            if !matches!(&e, SaveError::Io(io) if io.kind() == std::io::ErrorKind::NotFound) {
+                warn!("读取存档列表失败: {}", e);
+            }
+            Vec::new()
+        }
+    };
+
+    let selected_exists = ui_state
+        .selected_save_id
+        .as_ref()
+        .is_some_and(|id| ui_state.save_list.iter().any(|info| &info.save_id == id));
+    if !selected_exists {
+        ui_state.selected_save_id = None;
+        ui_state.editing_description = None;
+    }
+}
+
+/// 获取选中的存档信息
+pub fn get_selected_save(ui_state: &SaveUIState) -> Option<&SaveInfo> {
+    let save_id = ui_state.selected_save_id.as_ref()?;
+    ui_state.save_list.iter().find(|info| &info.save_id == save_id)
 }
 
 /// 选择存档
 pub fn select_save(ui_state: &mut SaveUIState, save_id: String) {
     ui_state.selected_save_id = Some(save_id);
+    ui_state.pending_delete = None;
+    ui_state.editing_description = None;
 }
 
 /// 获取选中的存档ID
@@ -78,15 +145,31 @@ pub fn get_selected_save_id(ui_state: &SaveUIState) -> Option<&String> {
     ui_state.selected_save_id.as_ref()
 }
 
-/// 格式化存档时间
+/// 格式化存档时间（UTC，`YYYY-MM-DD HH:MM`）
 pub fn format_save_time(real_time: f64) -> String {
-    use std::time::{UNIX_EPOCH, Duration};
-
-    let datetime = UNIX_EPOCH + Duration::from_secs_f64(real_time);
-
-    // 使用chrono库格式化时间
-    // 这里简化处理
-    format!("{:?}", datetime)
+    let seconds = real_time.max(0.0) as i64;
+    let days = seconds.div_euclid(86_400);
+    let seconds_of_day = seconds.rem_euclid(86_400);
+
+    // 公历日期换算（Howard Hinnant 的 civil_from_days 算法）
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60
+    )
 }
 
 /// 格式化游戏时间
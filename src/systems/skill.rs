@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use crate::components::player::Player;
+use crate::components::skill::{PlayerSkills, SkillBonuses, SkillTree, SkillTreeAsset, UnlockSkillEvent};
+use crate::systems::json_catalog::JsonCatalogPlugin;
+use crate::systems::player_combat::{
+    PlayerCombat, BASE_SPECIAL_COOLDOWN, BASE_SPECIAL_MULTIPLIER, BASE_ULTIMATE_COOLDOWN,
+    BASE_ULTIMATE_MULTIPLIER,
+};
+
+/// 技能树插件：加载技能数据，处理技能点消费并汇总技能加成
+pub struct SkillTreePlugin;
+
+impl Plugin for SkillTreePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonCatalogPlugin::<SkillTreeAsset>::default())
+            .init_resource::<SkillBonuses>()
+            .add_message::<UnlockSkillEvent>()
+            .add_systems(Update, (
+                handle_skill_unlocks,
+                update_skill_bonuses,
+                apply_combat_skills,
+            ).chain().run_if(in_state(crate::states::GameState::InGame)));
+    }
+}
+
+/// 处理技能解锁请求
+fn handle_skill_unlocks(
+    mut unlock_events: MessageReader<UnlockSkillEvent>,
+    tree: Res<SkillTree>,
+    mut player_query: Query<(&Player, &mut PlayerSkills)>,
+) {
+    let Ok((player, mut skills)) = player_query.single_mut() else { return; };
+
+    for event in unlock_events.read() {
+        let Some(definition) = tree.get(&event.skill_id) else {
+            warn!("未知技能: {}", event.skill_id);
+            continue;
+        };
+
+        match skills.unlock(definition, player.level) {
+            Ok(()) => info!("解锁技能: {} ({})", definition.name, definition.describe_effects()),
+            Err(e) => warn!("无法解锁技能 {}: {}", definition.name, e),
+        }
+    }
+}
+
+/// 技能或技能树变化后重新汇总加成
+fn update_skill_bonuses(
+    tree: Res<SkillTree>,
+    player_query: Query<Ref<PlayerSkills>, With<Player>>,
+    mut bonuses: ResMut<SkillBonuses>,
+) {
+    // 重新开局时玩家实体被替换，新实体的组件同样视为变化
+    let Ok(skills) = player_query.single() else { return; };
+    if !skills.is_changed() && !tree.is_changed() {
+        return;
+    }
+
+    bonuses.set_if_neq(SkillBonuses::from_skills(&skills, &tree));
+}
+
+/// 把战斗技能加成套用到玩家的特殊与终极攻击
+fn apply_combat_skills(
+    bonuses: Res<SkillBonuses>,
+    mut player_query: Query<&mut PlayerCombat, With<Player>>,
+) {
+    let Ok(mut player_combat) = player_query.single_mut() else { return; };
+    if !bonuses.is_changed() && !player_combat.is_added() {
+        return;
+    }
+
+    player_combat.special_damage_multiplier = BASE_SPECIAL_MULTIPLIER + bonuses.special_damage;
+    player_combat.special_cooldown_time = BASE_SPECIAL_COOLDOWN * (1.0 - bonuses.special_cooldown);
+    player_combat.ultimate_damage_multiplier = BASE_ULTIMATE_MULTIPLIER + bonuses.ultimate_damage;
+    player_combat.ultimate_cooldown_time = BASE_ULTIMATE_COOLDOWN * (1.0 - bonuses.ultimate_cooldown);
+}
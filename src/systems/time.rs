@@ -1,7 +1,9 @@
 use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
 
 /// 游戏时间资源
-#[derive(Resource, Clone, Debug)]
+#[derive(Resource, Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
 pub struct GameTime {
     pub day: u32,                    // 当前天数（1-15）
     pub hour: f32,                   // 当前小时（0-24）
@@ -12,7 +14,7 @@ pub struct GameTime {
 }
 
 /// 昼夜阶段
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum DayPhase {
     Dawn,    // 黎明（5-7点）
     Day,     // 白天（7-18点）
@@ -21,7 +23,7 @@ pub enum DayPhase {
 }
 
 /// 月相类型（15日周期）
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
 pub enum MoonPhase {
     NewMoon,        // 新月（第1日）
     WaxingCrescent, // 峨眉月（第2-3日）
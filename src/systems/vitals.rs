@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use crate::components::equipment::{Equipment, EquipmentBar};
+use crate::components::player::{
+    Player, PlayerStats, PlayerVitals, ARMOR_PER_LEVEL, BASE_MAX_ENERGY, BASE_MAX_HEALTH,
+    BASE_MOVE_SPEED, ENERGY_PER_LEVEL, HEALTH_PER_LEVEL, MOVE_SPEED_PER_LEVEL,
+};
+
+/// 装备减速后的最低移动速度倍率
+const MIN_MOVE_SPEED_MULTIPLIER: f32 = 0.5;
+
+/// 玩家生命与能量插件
+pub struct PlayerVitalsPlugin;
+
+impl Plugin for PlayerVitalsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+                apply_player_stats,
+                regenerate_player_vitals,
+            ).chain().run_if(in_state(crate::states::GameState::InGame)));
+    }
+}
+
+/// 按等级与装备重新计算玩家上限、护甲、抗性和移动速度
+fn apply_player_stats(
+    equipment_query: Query<&Equipment>,
+    mut player_query: Query<(&Player, &EquipmentBar, &mut PlayerStats, &mut PlayerVitals)>,
+) {
+    for (player, equipment_bar, mut stats, mut vitals) in player_query.iter_mut() {
+        let equipment = equipment_bar.total_stats(&equipment_query);
+
+        let levels = player.level.saturating_sub(1) as f32;
+        let max_health = BASE_MAX_HEALTH + HEALTH_PER_LEVEL * levels;
+        let max_energy = BASE_MAX_ENERGY + ENERGY_PER_LEVEL * levels + equipment.energy_bonus;
+        let armor = ARMOR_PER_LEVEL * levels + equipment.defense;
+        let move_speed = (BASE_MOVE_SPEED + MOVE_SPEED_PER_LEVEL * levels)
+            * (1.0 + equipment_bar.move_speed_modifier(&equipment_query)).max(MIN_MOVE_SPEED_MULTIPLIER);
+        let defense = equipment_bar.defense_attributes(&equipment_query);
+
+        // 只在数值变化时写入，避免每帧触发变更检测
+        if vitals.max_health != max_health || vitals.max_energy != max_energy {
+            vitals.set_limits(max_health, max_energy);
+        }
+        if stats.armor != armor || stats.move_speed != move_speed || stats.defense != defense {
+            stats.armor = armor;
+            stats.move_speed = move_speed;
+            stats.defense = defense;
+        }
+    }
+}
+
+/// 生命与能量随时间恢复
+fn regenerate_player_vitals(
+    time: Res<Time>,
+    mut player_query: Query<&mut PlayerVitals, With<Player>>,
+) {
+    for mut vitals in player_query.iter_mut() {
+        vitals.regenerate(time.delta_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::components::combat::DefenseAttributes;
+    use crate::components::equipment::{EquipmentRarity, EquipmentType};
+
+    #[test]
+    fn equipment_defense_is_summed_into_player_stats() {
+        let mut world = World::new();
+        let armor = world.spawn(Equipment::new(EquipmentType::HeavyArmor, EquipmentRarity::Common)).id();
+        let shield = world.spawn(Equipment::new(EquipmentType::EnergyShield, EquipmentRarity::Common)).id();
+        let player = world.spawn((
+            Player::new(1, "test".to_string()),
+            EquipmentBar { armor: Some(armor), accessory: Some(shield), ..default() },
+            PlayerStats::default(),
+            PlayerVitals::default(),
+        )).id();
+
+        world.run_system_once(apply_player_stats).unwrap();
+
+        let mut expected = DefenseAttributes::default();
+        expected.stack(&EquipmentType::HeavyArmor.defense_attributes());
+        expected.stack(&EquipmentType::EnergyShield.defense_attributes());
+        let defense = world.get::<PlayerStats>(player).unwrap().defense;
+        assert_eq!(defense, expected);
+        assert!((defense.block_chance - 0.25).abs() < 1e-6);
+        assert_eq!(defense.block_reduction, 0.6);
+
+        // 卸下护甲后抗性随之回落
+        world.get_mut::<EquipmentBar>(player).unwrap().armor = None;
+        world.run_system_once(apply_player_stats).unwrap();
+        let defense = world.get::<PlayerStats>(player).unwrap().defense;
+        assert_eq!(defense.physical_resistance, 0.0);
+        assert_eq!(defense.laser_resistance, 0.25);
+    }
+}
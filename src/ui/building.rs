@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use crate::states::GameState;
-use crate::components::building::{BuildingType, Inventory};
+use crate::components::building::{BuildingPosition, BuildingStatus, BuildingType};
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::ItemRegistry;
 use crate::systems::building::{place_building, upgrade_building, start_building, stop_building};
 
 pub struct BuildingUIPlugin;
@@ -178,7 +180,6 @@ fn spawn_building_panel(commands: &mut Commands) {
 
 fn update_building_panel(
     ui_state: Res<BuildingUIState>,
-    _inventory: Res<Inventory>,
     mut cost_query: Query<&mut Text, With<BuildingCostText>>,
 ) {
     if !ui_state.is_visible {
@@ -194,10 +195,14 @@ fn update_building_panel(
 
 fn handle_place_button(
     ui_state: Res<BuildingUIState>,
-    mut inventory: ResMut<Inventory>,
-    world_map: Res<crate::resources::world::WorldMap>,
+    map_grid: Res<crate::resources::world::MapGrid>,
+    catalog: Res<crate::components::building_catalog::BuildingCatalog>,
+    registry: Res<ItemRegistry>,
+    skill_bonuses: Option<Res<crate::components::skill::SkillBonuses>>,
     mut commands: Commands,
     mut interaction_query: Query<(&Interaction, &BuildingButton), Changed<Interaction>>,
+    building_query: Query<(&BuildingPosition, &BuildingStatus)>,
+    headless: Option<Res<crate::systems::headless::HeadlessConfig>>,
 ) {
     if !ui_state.is_visible {
         return;
@@ -214,8 +219,12 @@ fn handle_place_button(
                 button.building_type,
                 tile_x,
                 tile_y,
-                &world_map,
-                &mut inventory,
+                &map_grid,
+                &catalog,
+                &registry,
+                skill_bonuses.as_ref().map_or(0.0, |bonuses| bonuses.building_cost),
+                &building_query,
+                headless.is_none(),
             ) {
                 Ok(_entity) => {
                     info!("成功放置建筑: {:?}", button.building_type);
@@ -231,7 +240,9 @@ fn handle_place_button(
 
 fn handle_upgrade_button(
     ui_state: Res<BuildingUIState>,
-    mut inventory: ResMut<Inventory>,
+    mut base_query: Query<&mut Inventory, With<Base>>,
+    registry: Res<ItemRegistry>,
+    skill_bonuses: Option<Res<crate::components::skill::SkillBonuses>>,
     mut building_query: Query<&mut crate::components::building::Building>,
     mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<UpgradeButton>)>,
 ) {
@@ -241,14 +252,16 @@ fn handle_upgrade_button(
 
     for interaction in interaction_query.iter_mut() {
         if *interaction == Interaction::Pressed {
-            if let Some(entity) = ui_state.selected_building_entity {
-                match upgrade_building(entity, &mut inventory, &mut building_query) {
-                    Ok(_) => {
-                        info!("建筑升级成功");
-                    }
-                    Err(e) => {
-                        info!("建筑升级失败: {}", e);
-                    }
+            let (Some(entity), Ok(mut inventory)) = (ui_state.selected_building_entity, base_query.single_mut()) else {
+                continue;
+            };
+            let cost_reduction = skill_bonuses.as_ref().map_or(0.0, |bonuses| bonuses.building_cost);
+            match upgrade_building(entity, &mut inventory, &registry, cost_reduction, &mut building_query) {
+                Ok(_) => {
+                    info!("建筑升级成功");
+                }
+                Err(e) => {
+                    info!("建筑升级失败: {}", e);
                 }
             }
         }
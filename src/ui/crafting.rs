@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use crate::states::GameState;
-use crate::components::crafting::{RecipeBook, MaterialType};
+use crate::components::crafting::RecipeBook;
 use crate::components::equipment::{EquipmentType, EquipmentRarity};
-use crate::components::resource::Inventory;
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::{ItemId, ItemRegistry};
 use crate::systems::crafting::start_crafting;
 
 pub struct CraftingUIPlugin;
@@ -33,7 +34,7 @@ struct UpgradeButton;
 
 #[derive(Component)]
 struct MaterialText {
-    material_type: MaterialType,
+    item: ItemId,
 }
 
 #[derive(Resource, Default)]
@@ -109,7 +110,7 @@ fn spawn_crafting_panel(commands: &mut Commands) {
                 },
                 TextColor(Color::srgb(1.0, 1.0, 0.0)),
                 MaterialText {
-                    material_type: MaterialType::Energy,
+                    item: ItemId::ENERGY,
                 },
             ));
 
@@ -122,7 +123,7 @@ fn spawn_crafting_panel(commands: &mut Commands) {
                 },
                 TextColor(Color::srgb(0.8, 0.8, 0.8)),
                 MaterialText {
-                    material_type: MaterialType::Metal,
+                    item: ItemId::METAL,
                 },
             ));
 
@@ -135,7 +136,7 @@ fn spawn_crafting_panel(commands: &mut Commands) {
                 },
                 TextColor(Color::srgb(0.6, 0.4, 0.2)),
                 MaterialText {
-                    material_type: MaterialType::Soil,
+                    item: ItemId::SOIL,
                 },
             ));
 
@@ -148,7 +149,7 @@ fn spawn_crafting_panel(commands: &mut Commands) {
                 },
                 TextColor(Color::srgb(0.3, 0.8, 0.9)),
                 MaterialText {
-                    material_type: MaterialType::Crystal,
+                    item: ItemId::CRYSTAL,
                 },
             ));
 
@@ -161,7 +162,7 @@ fn spawn_crafting_panel(commands: &mut Commands) {
                 },
                 TextColor(Color::srgb(0.3, 0.9, 0.3)),
                 MaterialText {
-                    material_type: MaterialType::Organic,
+                    item: ItemId::ORGANIC,
                 },
             ));
 
@@ -216,24 +217,27 @@ fn spawn_crafting_panel(commands: &mut Commands) {
 
 fn update_crafting_panel(
     ui_state: Res<CraftingUIState>,
-    inventory: Res<Inventory>,
+    base_query: Query<&Inventory, With<Base>>,
+    registry: Res<ItemRegistry>,
     _recipe_book: Res<RecipeBook>,
-    mut material_query: Query<(&mut Text, &MaterialText)>,
+    mut material_query: Query<(&mut Text, &mut TextColor, &MaterialText)>,
 ) {
     if !ui_state.is_visible {
         return;
     }
+    let Ok(inventory) = base_query.single() else { return; };
 
     // 更新材料显示
-    for (mut text, material) in material_query.iter_mut() {
-        let amount = inventory.get_material(material.material_type);
-        text.0 = format!("{}: {}", material.material_type.name(), amount);
+    for (mut text, mut color, material) in material_query.iter_mut() {
+        text.0 = format!("{}: {}", registry.name(material.item), inventory.count(material.item));
+        color.0 = registry.get(material.item).map_or(Color::WHITE, |definition| definition.color());
     }
 }
 
 fn handle_recipe_button(
     ui_state: Res<CraftingUIState>,
-    mut inventory: ResMut<Inventory>,
+    mut base_query: Query<&mut Inventory, With<Base>>,
+    registry: Res<ItemRegistry>,
     recipe_book: Res<RecipeBook>,
     quality_control: Res<crate::components::crafting::QualityControl>,
     _commands: Commands,
@@ -245,11 +249,13 @@ fn handle_recipe_button(
 
     for (interaction, recipe_button) in interaction_query.iter_mut() {
         if *interaction == Interaction::Pressed {
+            let Ok(mut inventory) = base_query.single_mut() else { continue; };
             // 尝试制造装备
             match start_crafting(
                 recipe_button.equipment_type,
                 recipe_button.rarity,
                 &mut inventory,
+                &registry,
                 &recipe_book,
                 &quality_control,
                 &mut rand::thread_rng(),
@@ -273,7 +279,6 @@ fn handle_recipe_button(
 
 fn handle_upgrade_button(
     ui_state: Res<CraftingUIState>,
-    _inventory: ResMut<Inventory>,
     _upgrade_optimization: Res<crate::components::crafting::UpgradeOptimization>,
     _equipment_query: Query<&mut crate::components::equipment::Equipment>,
     mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<UpgradeButton>)>,
@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// 界面输入焦点
+///
+/// 面板打开时登记自己占用的输入，关闭时注销；镜头等世界输入通过运行条件让出。
+#[derive(Resource, Debug, Default)]
+pub struct UiFocus {
+    wheel: HashSet<&'static str>,  // 正在使用滚轮的面板
+}
+
+impl UiFocus {
+    /// 登记或注销面板对滚轮的占用
+    pub fn set_wheel(&mut self, panel: &'static str, captured: bool) {
+        if captured {
+            self.wheel.insert(panel);
+        } else {
+            self.wheel.remove(panel);
+        }
+    }
+
+    /// 是否有面板在使用滚轮
+    pub fn wheel_captured(&self) -> bool {
+        !self.wheel.is_empty()
+    }
+}
+
+/// 运行条件：没有面板占用滚轮
+pub fn wheel_free(focus: Option<Res<UiFocus>>) -> bool {
+    focus.is_none_or(|focus| !focus.wheel_captured())
+}
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use crate::states::GameState;
+use crate::components::game_over::{GameOverSummary, RestartGameEvent};
+use crate::components::save::SaveManager;
+use crate::systems::save_manager::{get_save_list, LoadGameEvent};
+
+/// 结算界面（玩家死亡后显示）
+pub struct GameOverUIPlugin;
+
+impl Plugin for GameOverUIPlugin {
+    fn build(&self, app: &mut App) {
+        // 未启用存档系统的层也需要读档消息，读档按钮此时不会出现
+        app.add_message::<LoadGameEvent>()
+            .add_systems(OnEnter(GameState::GameOver), setup_game_over)
+            .add_systems(Update, handle_game_over_buttons.run_if(in_state(GameState::GameOver)))
+            .add_systems(OnExit(GameState::GameOver), cleanup_game_over);
+    }
+}
+
+#[derive(Component)]
+struct GameOverRoot;
+
+/// 结算界面按钮
+#[derive(Component, Clone)]
+enum GameOverButton {
+    Reload(String),
+    Restart,
+}
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const HOVERED_BUTTON_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+
+fn setup_game_over(
+    mut commands: Commands,
+    summary: Option<Res<GameOverSummary>>,
+    save_manager: Option<Res<SaveManager>>,
+) {
+    // 最近的一个存档（不区分类型）
+    let latest_save = save_manager
+        .and_then(|save_manager| get_save_list(&save_manager).ok())
+        .and_then(|saves| saves.into_iter().next());
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            GlobalZIndex(20),
+            GameOverRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("游戏结束"),
+                TextFont {
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.2, 0.2)),
+            ));
+
+            if let Some(summary) = summary {
+                for line in [
+                    format!("存活天数: {}", summary.days_survived),
+                    format!("击杀敌人: {}", summary.enemies_killed),
+                    format!("建筑数量: {}", summary.buildings),
+                ] {
+                    parent.spawn((
+                        Text::new(line),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                }
+            }
+
+            // 间隔
+            parent.spawn(Node {
+                height: Val::Px(20.0),
+                ..default()
+            });
+
+            if let Some(info) = latest_save {
+                let name = if info.description.is_empty() { info.save_id.clone() } else { info.description.clone() };
+                spawn_button(parent, &format!("读取最近存档 ({})", name), GameOverButton::Reload(info.save_id));
+            }
+            spawn_button(parent, "重新开始", GameOverButton::Restart);
+        });
+}
+
+fn spawn_button(parent: &mut ChildSpawnerCommands, label: &str, button: GameOverButton) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                min_width: Val::Px(240.0),
+                height: Val::Px(50.0),
+                padding: UiRect::horizontal(Val::Px(16.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn handle_game_over_buttons(
+    mut interaction_query: Query<
+        (&Interaction, &GameOverButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut load_events: MessageWriter<LoadGameEvent>,
+    mut restart_events: MessageWriter<RestartGameEvent>,
+) {
+    for (interaction, button, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => match button {
+                GameOverButton::Reload(save_id) => {
+                    load_events.write(LoadGameEvent { save_id: save_id.clone() });
+                }
+                GameOverButton::Restart => {
+                    restart_events.write(RestartGameEvent);
+                }
+            },
+            Interaction::Hovered => {
+                color.0 = HOVERED_BUTTON_COLOR;
+            }
+            Interaction::None => {
+                color.0 = BUTTON_COLOR;
+            }
+        }
+    }
+}
+
+fn cleanup_game_over(mut commands: Commands, query: Query<Entity, With<GameOverRoot>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,22 +1,32 @@
 use bevy::prelude::*;
 use crate::states::GameState;
-use crate::components::resource::Inventory;
-use crate::components::player::Player;
+use crate::components::inventory::Inventory;
+use crate::components::item::ItemId;
+use crate::components::experience::{LevelCurve, PlayerExperience};
+use crate::components::player::{Player, PlayerVitals};
+use crate::components::power::PowerGrid;
+use crate::components::quest::{next_available_quest, Quest, QuestLog};
 use crate::systems::time::{GameTime, DayPhase, MoonPhase};
+use crate::systems::enemy_spawn::{WaveStartedEvent, WaveClearedEvent};
 
 pub struct HUDPlugin;
 
 impl Plugin for HUDPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::InGame), setup_hud)
-           .add_systems(Update, update_hud.run_if(in_state(GameState::InGame)))
-           .add_systems(OnExit(GameState::InGame), cleanup_hud);
+           .add_systems(Update, (update_hud, update_quest_hud).run_if(in_state(GameState::InGame)))
+           .add_systems(OnExit(GameState::InGame), cleanup_hud)
+           .add_observer(on_wave_started)
+           .add_observer(on_wave_cleared);
     }
 }
 
 #[derive(Component)]
 struct HUDRoot;
 
+#[derive(Component)]
+struct VitalsText;
+
 #[derive(Component)]
 struct EnergyText;
 
@@ -26,6 +36,12 @@ struct MetalText;
 #[derive(Component)]
 struct SoilText;
 
+#[derive(Component)]
+struct PowerText;
+
+#[derive(Component)]
+struct QuestText;
+
 #[derive(Component)]
 struct TimeText;
 
@@ -35,6 +51,9 @@ struct DayPhaseText;
 #[derive(Component)]
 struct MoonPhaseText;
 
+#[derive(Component)]
+struct WaveText;
+
 fn setup_hud(mut commands: Commands) {
     // Left HUD - Resources
     commands
@@ -50,6 +69,17 @@ fn setup_hud(mut commands: Commands) {
             HUDRoot,
         ))
         .with_children(|parent| {
+            // Health / Energy
+            parent.spawn((
+                Text::new("HP: 100/100  EN: 100/100"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.3, 0.3)), // Red
+                VitalsText,
+            ));
+
             // Energy
             parent.spawn((
                 Text::new("Energy: 0"),
@@ -82,6 +112,28 @@ fn setup_hud(mut commands: Commands) {
                 TextColor(Color::srgb(0.6, 0.4, 0.2)), // Brown
                 SoilText,
             ));
+
+            // Power grid
+            parent.spawn((
+                Text::new("Power: -"),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.7, 0.1)), // Amber
+                PowerText,
+            ));
+
+            // Quest (J to accept / turn in)
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 0.9, 0.6)), // Light green
+                QuestText,
+            ));
         });
 
     // Right HUD - Time and Moon Phase
@@ -130,11 +182,24 @@ fn setup_hud(mut commands: Commands) {
                 TextColor(Color::srgb(0.7, 0.7, 0.9)), // Light blue
                 MoonPhaseText,
             ));
+
+            // Enemy Wave
+            parent.spawn((
+                Text::new("No Wave"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.4, 0.4)), // Light red
+                WaveText,
+            ));
         });
 }
 
 fn update_hud(
-    player_query: Query<&Inventory, With<Player>>,
+    player_query: Query<(&Player, &Inventory, &PlayerVitals, &PlayerExperience)>,
+    level_curve: Option<Res<LevelCurve>>,
+    power_grid: Option<Res<PowerGrid>>,
     game_time: Res<GameTime>,
     mut text_queries: ParamSet<(
         Query<&mut Text, With<EnergyText>>,
@@ -143,17 +208,41 @@ fn update_hud(
         Query<&mut Text, With<TimeText>>,
         Query<&mut Text, With<DayPhaseText>>,
         Query<&mut Text, With<MoonPhaseText>>,
+        Query<&mut Text, With<VitalsText>>,
+        Query<&mut Text, With<PowerText>>,
     )>,
 ) {
-    if let Some(inventory) = player_query.iter().next() {
+    if let Some((player, inventory, vitals, experience)) = player_query.iter().next() {
+        let progress = level_curve
+            .map(|curve| experience.progress(player.level, &curve))
+            .unwrap_or(0.0);
+        for mut text in text_queries.p6() {
+            text.0 = format!(
+                "Lv {} ({:.0}%)  HP: {:.0}/{:.0}  EN: {:.0}/{:.0}",
+                player.level, progress * 100.0,
+                vitals.health.ceil(), vitals.max_health, vitals.energy.floor(), vitals.max_energy
+            );
+        }
         for mut text in text_queries.p0() {
-            text.0 = format!("Energy: {}", inventory.energy);
+            text.0 = format!("Energy: {}", inventory.count(ItemId::ENERGY));
         }
         for mut text in text_queries.p1() {
-            text.0 = format!("Metal: {}", inventory.metal);
+            text.0 = format!("Metal: {}", inventory.count(ItemId::METAL));
         }
         for mut text in text_queries.p2() {
-            text.0 = format!("Soil: {}", inventory.soil);
+            text.0 = format!("Soil: {}", inventory.count(ItemId::SOIL));
+        }
+    }
+
+    if let Some(grid) = power_grid {
+        let (stored, capacity) = grid.total_storage();
+        let shed = grid.total_shed();
+        for mut text in text_queries.p7() {
+            text.0 = format!(
+                "Power: {:.0}/{:.0}  Battery: {:.0}/{:.0}{}",
+                grid.total_supply(), grid.total_demand(), stored, capacity,
+                if shed > 0 { format!("  ({} offline)", shed) } else { String::new() }
+            );
         }
     }
 
@@ -192,6 +281,57 @@ fn update_hud(
     }
 }
 
+/// 任务栏：待交付的任务、当前追踪任务的进度，或等待玩家接受的任务
+fn update_quest_hud(
+    player_query: Query<&Player>,
+    quest_query: Query<&Quest>,
+    quest_log_query: Query<&QuestLog>,
+    mut text_query: Query<&mut Text, With<QuestText>>,
+) {
+    let (Ok(player), Ok(quest_log)) = (player_query.single(), quest_log_query.single()) else { return; };
+
+    let current = quest_log
+        .current_quest
+        .as_deref()
+        .and_then(|id| quest_query.iter().find(|quest| quest.id == id));
+    let line = if let Some(quest) = quest_query.iter().find(|quest| quest.can_turn_in()) {
+        format!("Quest complete: {}  [J] turn in", quest.title)
+    } else if let Some(quest) = current {
+        let done = quest.objectives.iter().filter(|objective| objective.is_completed()).count();
+        format!("Quest: {} ({}/{})", quest.title, done, quest.objectives.len())
+    } else if let Some(quest) = next_available_quest(&quest_query, player.level, &quest_log.completed_quests) {
+        format!("New quest: {}  [J] accept", quest.title)
+    } else {
+        String::new()
+    };
+
+    for mut text in text_query.iter_mut() {
+        if text.0 != line {
+            text.0 = line.clone();
+        }
+    }
+}
+
+fn on_wave_started(
+    event: On<WaveStartedEvent>,
+    mut query: Query<&mut Text, With<WaveText>>,
+) {
+    let wave_event = event.event();
+    for mut text in query.iter_mut() {
+        text.0 = format!("Wave {}: {} enemies (Lv{})", wave_event.wave, wave_event.enemy_count, wave_event.level);
+    }
+}
+
+fn on_wave_cleared(
+    event: On<WaveClearedEvent>,
+    mut query: Query<&mut Text, With<WaveText>>,
+) {
+    let wave_event = event.event();
+    for mut text in query.iter_mut() {
+        text.0 = format!("Wave {} cleared", wave_event.wave);
+    }
+}
+
 fn cleanup_hud(mut commands: Commands, query: Query<Entity, With<HUDRoot>>) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
@@ -3,3 +3,7 @@ pub mod menu;
 pub mod plant_upgrade;
 pub mod crafting;
 pub mod building;
+pub mod save;
+pub mod game_over;
+pub mod skill_tree;
+pub mod focus;
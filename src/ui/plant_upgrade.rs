@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 use crate::states::GameState;
 use crate::components::plant_upgrade::{PlantUpgrade, PlantVarietyTree, PlantHarvestStats};
-use crate::components::resource::Inventory;
+use crate::components::inventory::{Base, Inventory};
+use crate::components::item::ItemId;
 
 pub struct PlantUpgradeUIPlugin;
 
@@ -185,7 +186,6 @@ fn update_plant_upgrade_panel(
     plant_query: Query<&PlantUpgrade>,
     variety_tree: Res<PlantVarietyTree>,
     harvest_stats: Res<PlantHarvestStats>,
-    _inventory: Res<Inventory>,
     mut text_queries: ParamSet<(
         Query<&mut Text, With<PlantLevelText>>,
         Query<&mut Text, With<PlantVarietyText>>,
@@ -219,7 +219,7 @@ fn update_plant_upgrade_panel(
 
 fn handle_upgrade_button(
     ui_state: Res<PlantUpgradeUIState>,
-    mut inventory: ResMut<Inventory>,
+    mut base_query: Query<&mut Inventory, With<Base>>,
     mut plant_query: Query<&mut PlantUpgrade>,
     mut upgrade_cost_query: Query<&mut Text, With<UpgradeCostText>>,
     mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<UpgradeButton>)>,
@@ -233,8 +233,7 @@ fn handle_upgrade_button(
             if let Some(selected) = ui_state.selected_plant {
                 if let Ok(mut upgrade) = plant_query.get_mut(selected) {
                     let cost = upgrade.get_upgrade_cost();
-                    if cost > 0 && inventory.energy >= cost {
-                        inventory.energy -= cost;
+                    if cost > 0 && base_query.single_mut().is_ok_and(|mut inventory| inventory.remove(ItemId::ENERGY, cost)) {
                         upgrade.apply_upgrade();
                         info!("Plant upgraded to level {:?}", upgrade.level);
                     }
@@ -260,7 +259,7 @@ fn handle_upgrade_button(
 
 fn handle_unlock_button(
     ui_state: Res<PlantUpgradeUIState>,
-    mut inventory: ResMut<Inventory>,
+    mut base_query: Query<&mut Inventory, With<Base>>,
     mut variety_tree: ResMut<PlantVarietyTree>,
     _harvest_stats: Res<PlantHarvestStats>,
     mut unlock_cost_query: Query<&mut Text, With<UnlockCostText>>,
@@ -279,8 +278,7 @@ fn handle_unlock_button(
                              crate::components::plant::PlantType::EnergyFlower] {
                 if !variety_tree.is_unlocked(plant_type) {
                     if let Some(condition) = variety_tree.unlock_conditions.get(&plant_type) {
-                        if inventory.energy >= condition.energy_cost {
-                            inventory.energy -= condition.energy_cost;
+                        if base_query.single_mut().is_ok_and(|mut inventory| inventory.remove(ItemId::ENERGY, condition.energy_cost)) {
                             variety_tree.unlock(plant_type);
                             info!("Unlocked plant variety: {:?}", plant_type);
                             break;
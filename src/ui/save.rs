@@ -0,0 +1,489 @@
+use bevy::prelude::*;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::{ButtonState, InputSystems};
+use crate::states::GameState;
+use crate::components::save::{SaveInfo, SaveManager, SaveType};
+use crate::systems::save_manager::{
+    delete_save, set_save_description, LoadGameEvent, SaveCompletedEvent, SaveGameEvent,
+};
+use crate::systems::save_ui::{
+    format_game_time, format_save_time, get_save_type_color, get_save_type_name, get_selected_save,
+    hide_save_menu, refresh_save_list, select_save, SaveUIState,
+};
+use crate::ui::focus::UiFocus;
+
+/// 存档浏览界面（F6 存档 / F7 读档）
+pub struct SaveBrowserUIPlugin;
+
+impl Plugin for SaveBrowserUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiFocus>()
+            .add_systems(PreUpdate, capture_description_input
+                .after(InputSystems)
+                .run_if(in_state(GameState::InGame)))
+            .add_systems(Update, (
+                handle_save_menu_buttons,
+                refresh_on_save_completed,
+                scroll_save_list,
+                sync_save_panel,
+            ).chain().run_if(in_state(GameState::InGame)))
+            .add_systems(OnExit(GameState::InGame), close_save_panel);
+    }
+}
+
+#[derive(Component)]
+struct SaveBrowserPanel;
+
+#[derive(Component)]
+struct SaveList;
+
+/// 存档界面按钮
+#[derive(Component, Clone)]
+enum SaveMenuButton {
+    Select(String),
+    NewSave,
+    Overwrite,
+    Load,
+    Rename,
+    ConfirmRename,
+    CancelRename,
+    Delete,
+    ConfirmDelete,
+    CancelDelete,
+    Close,
+}
+
+/// 存档界面在 `UiFocus` 中的名称
+const FOCUS_NAME: &str = "save_browser";
+
+const PANEL_WIDTH: f32 = 520.0;
+const ROW_COLOR: Color = Color::srgb(0.18, 0.18, 0.22);
+const SELECTED_ROW_COLOR: Color = Color::srgb(0.25, 0.3, 0.5);
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.5);
+const DANGER_BUTTON_COLOR: Color = Color::srgb(0.6, 0.2, 0.2);
+
+/// 存档菜单状态变化时重建面板
+fn sync_save_panel(
+    mut commands: Commands,
+    ui_state: Res<SaveUIState>,
+    save_manager: Res<SaveManager>,
+    mut focus: ResMut<UiFocus>,
+    existing_panel: Query<Entity, With<SaveBrowserPanel>>,
+) {
+    if !ui_state.is_changed() {
+        return;
+    }
+    // 菜单打开时滚轮用于滚动存档列表
+    focus.set_wheel(FOCUS_NAME, ui_state.show_save_menu || ui_state.show_load_menu);
+
+    for entity in existing_panel.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    if ui_state.show_save_menu || ui_state.show_load_menu {
+        spawn_save_panel(&mut commands, &ui_state, &save_manager);
+    }
+}
+
+fn close_save_panel(
+    mut commands: Commands,
+    mut ui_state: ResMut<SaveUIState>,
+    mut focus: ResMut<UiFocus>,
+    existing_panel: Query<Entity, With<SaveBrowserPanel>>,
+) {
+    hide_save_menu(&mut ui_state);
+    focus.set_wheel(FOCUS_NAME, false);
+    for entity in existing_panel.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_save_panel(commands: &mut Commands, ui_state: &SaveUIState, save_manager: &SaveManager) {
+    let title = if ui_state.show_save_menu { "保存游戏" } else { "加载游戏" };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(80.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-PANEL_WIDTH / 2.0)),
+                width: Val::Px(PANEL_WIDTH),
+                height: Val::Px(560.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.95)),
+            GlobalZIndex(10),
+            SaveBrowserPanel,
+        ))
+        .with_children(|parent| {
+            // 标题栏
+            parent
+                .spawn(Node {
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(title),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                    spawn_button(parent, "关闭", SaveMenuButton::Close, BUTTON_COLOR);
+                });
+
+            // 存档列表
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        flex_grow: 1.0,
+                        row_gap: Val::Px(4.0),
+                        overflow: Overflow::scroll_y(),
+                        ..default()
+                    },
+                    ScrollPosition::default(),
+                    SaveList,
+                ))
+                .with_children(|parent| {
+                    if ui_state.save_list.is_empty() {
+                        parent.spawn((
+                            Text::new("没有存档"),
+                            TextFont {
+                                font_size: 16.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                        ));
+                    }
+
+                    for info in &ui_state.save_list {
+                        let selected = ui_state.selected_save_id.as_ref() == Some(&info.save_id);
+                        spawn_save_entry(parent, info, selected);
+                    }
+                });
+
+            // 分隔线
+            parent.spawn((
+                Node {
+                    height: Val::Px(2.0),
+                    width: Val::Percent(100.0),
+                    flex_shrink: 0.0,
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+            ));
+
+            spawn_action_bar(parent, ui_state, save_manager);
+        });
+}
+
+/// 一条存档记录：类型、名称、等级、游戏时长、位置与保存时间
+fn spawn_save_entry(parent: &mut ChildSpawnerCommands, info: &SaveInfo, selected: bool) {
+    let name = if info.description.is_empty() { info.save_id.as_str() } else { info.description.as_str() };
+
+    parent
+        .spawn((
+            Button,
+            Node {
+                flex_direction: FlexDirection::Column,
+                flex_shrink: 0.0,
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                ..default()
+            },
+            BackgroundColor(if selected { SELECTED_ROW_COLOR } else { ROW_COLOR }),
+            SaveMenuButton::Select(info.save_id.clone()),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(format!("[{}]", get_save_type_name(info.save_type))),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(get_save_type_color(info.save_type)),
+                    ));
+                    parent.spawn((
+                        Text::new(name),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+
+            parent.spawn((
+                Text::new(format!(
+                    "Lv{}  游戏时长 {}  {}  {}",
+                    info.player_level,
+                    format_game_time(info.game_time),
+                    info.location,
+                    format_save_time(info.real_time),
+                )),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+/// 底部操作栏：删除确认与描述编辑会替换常规按钮
+fn spawn_action_bar(parent: &mut ChildSpawnerCommands, ui_state: &SaveUIState, save_manager: &SaveManager) {
+    let selected = get_selected_save(ui_state);
+
+    if let Some(save_id) = &ui_state.pending_delete {
+        parent.spawn((
+            Text::new(format!("确认删除存档 {}？此操作无法撤销", save_id)),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 0.6, 0.6)),
+        ));
+        spawn_button_row(parent, &[
+            ("确认删除", SaveMenuButton::ConfirmDelete, DANGER_BUTTON_COLOR),
+            ("取消", SaveMenuButton::CancelDelete, BUTTON_COLOR),
+        ]);
+        return;
+    }
+
+    if let Some(description) = &ui_state.editing_description {
+        parent.spawn((
+            Text::new(format!("存档名称: {}_", description)),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.7)),
+        ));
+        spawn_button_row(parent, &[
+            ("确定 (Enter)", SaveMenuButton::ConfirmRename, BUTTON_COLOR),
+            ("取消 (Esc)", SaveMenuButton::CancelRename, BUTTON_COLOR),
+        ]);
+        return;
+    }
+
+    let mut buttons = Vec::new();
+    if ui_state.show_save_menu {
+        let manual_saves = ui_state.save_list.iter().filter(|info| info.save_type == SaveType::Manual).count();
+        let label = if manual_saves >= save_manager.settings.max_manual_saves { "新建存档 (覆盖最旧)" } else { "新建存档" };
+        buttons.push((label, SaveMenuButton::NewSave, BUTTON_COLOR));
+
+        // 只能覆盖手动存档，自动/快速/检查点存档由系统轮换
+        if selected.is_some_and(|info| info.save_type == SaveType::Manual) {
+            buttons.push(("覆盖所选", SaveMenuButton::Overwrite, BUTTON_COLOR));
+        }
+    } else if selected.is_some() {
+        buttons.push(("加载所选", SaveMenuButton::Load, BUTTON_COLOR));
+    }
+    if selected.is_some() {
+        buttons.push(("重命名", SaveMenuButton::Rename, BUTTON_COLOR));
+        buttons.push(("删除", SaveMenuButton::Delete, DANGER_BUTTON_COLOR));
+    }
+
+    spawn_button_row(parent, &buttons);
+}
+
+fn spawn_button_row(parent: &mut ChildSpawnerCommands, buttons: &[(&str, SaveMenuButton, Color)]) {
+    parent
+        .spawn(Node {
+            column_gap: Val::Px(8.0),
+            flex_shrink: 0.0,
+            ..default()
+        })
+        .with_children(|parent| {
+            for (label, button, color) in buttons {
+                spawn_button(parent, label, button.clone(), *color);
+            }
+        });
+}
+
+fn spawn_button(parent: &mut ChildSpawnerCommands, label: &str, button: SaveMenuButton, color: Color) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                height: Val::Px(32.0),
+                padding: UiRect::horizontal(Val::Px(12.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(color),
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn handle_save_menu_buttons(
+    mut ui_state: ResMut<SaveUIState>,
+    save_manager: Res<SaveManager>,
+    mut save_events: MessageWriter<SaveGameEvent>,
+    mut load_events: MessageWriter<LoadGameEvent>,
+    interaction_query: Query<(&Interaction, &SaveMenuButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let selected = get_selected_save(&ui_state).cloned();
+        match button {
+            SaveMenuButton::Select(save_id) => select_save(&mut ui_state, save_id.clone()),
+            SaveMenuButton::NewSave => {
+                save_events.write(SaveGameEvent {
+                    save_type: SaveType::Manual,
+                    description: None,
+                    slot: None,
+                });
+            }
+            SaveMenuButton::Overwrite => {
+                if let Some(info) = selected {
+                    save_events.write(SaveGameEvent {
+                        save_type: SaveType::Manual,
+                        description: Some(info.description),
+                        slot: Some(info.save_id),
+                    });
+                }
+            }
+            SaveMenuButton::Load => {
+                if let Some(info) = selected {
+                    load_events.write(LoadGameEvent { save_id: info.save_id });
+                    hide_save_menu(&mut ui_state);
+                }
+            }
+            SaveMenuButton::Rename => {
+                if let Some(info) = selected {
+                    ui_state.editing_description = Some(info.description);
+                }
+            }
+            SaveMenuButton::ConfirmRename => confirm_rename(&mut ui_state, &save_manager),
+            SaveMenuButton::CancelRename => ui_state.editing_description = None,
+            SaveMenuButton::Delete => {
+                ui_state.pending_delete = selected.map(|info| info.save_id);
+            }
+            SaveMenuButton::ConfirmDelete => {
+                if let Some(save_id) = ui_state.pending_delete.take() {
+                    if let Err(e) = delete_save(&save_manager, &save_id) {
+                        error!("删除存档失败: {}", e);
+                    }
+                    refresh_save_list(&mut ui_state, &save_manager);
+                }
+            }
+            SaveMenuButton::CancelDelete => ui_state.pending_delete = None,
+            SaveMenuButton::Close => hide_save_menu(&mut ui_state),
+        }
+    }
+}
+
+/// 保存编辑中的描述
+fn confirm_rename(ui_state: &mut SaveUIState, save_manager: &SaveManager) {
+    let Some(description) = ui_state.editing_description.take() else { return; };
+    let Some(save_id) = ui_state.selected_save_id.clone() else { return; };
+
+    if let Err(e) = set_save_description(save_manager, &save_id, description.trim()) {
+        error!("修改存档描述失败: {}", e);
+    }
+    refresh_save_list(ui_state, save_manager);
+}
+
+/// 编辑描述时接管键盘输入
+///
+/// 在输入系统之后运行并清空按键状态，避免输入的字母触发其他面板的快捷键。
+fn capture_description_input(
+    mut ui_state: ResMut<SaveUIState>,
+    save_manager: Res<SaveManager>,
+    mut keyboard_events: MessageReader<KeyboardInput>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+) {
+    if ui_state.editing_description.is_none() {
+        keyboard_events.clear();
+        return;
+    }
+
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Enter => {
+                confirm_rename(&mut ui_state, &save_manager);
+                break;
+            }
+            Key::Escape => {
+                ui_state.editing_description = None;
+                break;
+            }
+            Key::Backspace => {
+                if let Some(description) = ui_state.editing_description.as_mut() {
+                    description.pop();
+                }
+            }
+            _ => {
+                if let (Some(text), Some(description)) = (&event.text, ui_state.editing_description.as_mut()) {
+                    description.extend(text.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+    }
+
+    keyboard_input.reset_all();
+}
+
+/// 后台存档完成后刷新列表
+fn refresh_on_save_completed(
+    mut ui_state: ResMut<SaveUIState>,
+    save_manager: Res<SaveManager>,
+    mut completed_events: MessageReader<SaveCompletedEvent>,
+) {
+    if completed_events.is_empty() {
+        return;
+    }
+    completed_events.clear();
+
+    if ui_state.show_save_menu || ui_state.show_load_menu {
+        refresh_save_list(&mut ui_state, &save_manager);
+    }
+}
+
+fn scroll_save_list(
+    mut wheel_events: MessageReader<MouseWheel>,
+    mut list_query: Query<&mut ScrollPosition, With<SaveList>>,
+) {
+    for event in wheel_events.read() {
+        let delta = match event.unit {
+            MouseScrollUnit::Line => event.y * 24.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in list_query.iter_mut() {
+            scroll.y = (scroll.y - delta).max(0.0);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use bevy::prelude::*;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use crate::states::GameState;
+use crate::components::player::Player;
+use crate::components::skill::{PlayerSkills, SkillBranch, SkillTree, UnlockSkillEvent};
+use crate::ui::focus::UiFocus;
+
+pub struct SkillTreeUIPlugin;
+
+impl Plugin for SkillTreeUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SkillTreeUIState>()
+            .init_resource::<UiFocus>()
+            .add_systems(Update, (
+                toggle_skill_panel,
+                refresh_skill_panel,
+                scroll_skill_panel,
+                handle_skill_buttons,
+            ).chain().run_if(in_state(GameState::InGame)))
+            .add_systems(OnExit(GameState::InGame), close_skill_panel);
+    }
+}
+
+#[derive(Component)]
+struct SkillPanel;
+
+#[derive(Component)]
+struct SkillButton {
+    skill_id: String,
+}
+
+/// 技能面板在 `UiFocus` 中的名称
+const FOCUS_NAME: &str = "skill_tree";
+
+#[derive(Resource, Default)]
+struct SkillTreeUIState {
+    is_visible: bool,
+}
+
+const UNLOCKED_COLOR: Color = Color::srgb(0.2, 0.45, 0.2);
+const AVAILABLE_COLOR: Color = Color::srgb(0.2, 0.3, 0.5);
+const HOVERED_COLOR: Color = Color::srgb(0.3, 0.4, 0.65);
+const LOCKED_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+
+fn toggle_skill_panel(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut ui_state: ResMut<SkillTreeUIState>,
+    mut focus: ResMut<UiFocus>,
+    mut commands: Commands,
+    existing_panel: Query<Entity, With<SkillPanel>>,
+    tree: Res<SkillTree>,
+    player_query: Query<(&Player, &PlayerSkills)>,
+) {
+    // 按 K 键切换技能面板
+    if !keyboard.just_pressed(KeyCode::KeyK) {
+        return;
+    }
+    ui_state.is_visible = !ui_state.is_visible;
+    // 面板打开时滚轮用于滚动技能列表
+    focus.set_wheel(FOCUS_NAME, ui_state.is_visible);
+
+    for entity in existing_panel.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    if ui_state.is_visible {
+        if let Ok((player, skills)) = player_query.single() {
+            spawn_skill_panel(&mut commands, &tree, skills, player.level);
+        }
+    }
+}
+
+/// 等级、已学技能或技能树变化时重建面板
+fn refresh_skill_panel(
+    ui_state: Res<SkillTreeUIState>,
+    mut commands: Commands,
+    existing_panel: Query<Entity, With<SkillPanel>>,
+    tree: Res<SkillTree>,
+    player_query: Query<(Ref<Player>, Ref<PlayerSkills>)>,
+) {
+    if !ui_state.is_visible {
+        return;
+    }
+    let Ok((player, skills)) = player_query.single() else { return; };
+    if !player.is_changed() && !skills.is_changed() && !tree.is_changed() {
+        return;
+    }
+
+    for entity in existing_panel.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_skill_panel(&mut commands, &tree, &skills, player.level);
+}
+
+fn spawn_skill_panel(commands: &mut Commands, tree: &SkillTree, skills: &PlayerSkills, level: u32) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(100.0),
+                left: Val::Px(10.0),
+                width: Val::Px(460.0),
+                max_height: Val::Percent(80.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            ScrollPosition::default(),
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.9)),
+            SkillPanel,
+        ))
+        .with_children(|parent| {
+            // 标题
+            parent.spawn((
+                Text::new("技能树"),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent.spawn((
+                Text::new(format!("等级 {}  可用技能点: {}", level, skills.available_points(level))),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.7)),
+            ));
+
+            for branch in SkillBranch::ALL {
+                // 分支标题
+                parent.spawn((
+                    Text::new(branch.name()),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.6, 0.8, 1.0)),
+                    Node {
+                        margin: UiRect::top(Val::Px(8.0)),
+                        ..default()
+                    },
+                ));
+
+                for definition in tree.branch(branch) {
+                    let (status, color) = if skills.is_unlocked(&definition.id) {
+                        ("已解锁".to_string(), UNLOCKED_COLOR)
+                    } else {
+                        match skills.can_unlock(definition, level) {
+                            Ok(()) => ("可解锁".to_string(), AVAILABLE_COLOR),
+                            Err(reason) => (reason, LOCKED_COLOR),
+                        }
+                    };
+
+                    parent
+                        .spawn((
+                            Button,
+                            Node {
+                                width: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                padding: UiRect::all(Val::Px(6.0)),
+                                ..default()
+                            },
+                            BackgroundColor(color),
+                            SkillButton {
+                                skill_id: definition.id.clone(),
+                            },
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new(format!(
+                                    "{} (消耗 {} 点, 需要等级 {})",
+                                    definition.name, definition.cost, definition.required_level
+                                )),
+                                TextFont {
+                                    font_size: 16.0,
+                                    ..default()
+                                },
+                                TextColor(Color::WHITE),
+                            ));
+                            parent.spawn((
+                                Text::new(format!("{}  [{}]", definition.describe_effects(), status)),
+                                TextFont {
+                                    font_size: 14.0,
+                                    ..default()
+                                },
+                                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                            ));
+                        });
+                }
+            }
+        });
+}
+
+fn scroll_skill_panel(
+    mut wheel_events: MessageReader<MouseWheel>,
+    mut panel_query: Query<&mut ScrollPosition, With<SkillPanel>>,
+) {
+    for event in wheel_events.read() {
+        let delta = match event.unit {
+            MouseScrollUnit::Line => event.y * 24.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in panel_query.iter_mut() {
+            scroll.y = (scroll.y - delta).max(0.0);
+        }
+    }
+}
+
+fn handle_skill_buttons(
+    ui_state: Res<SkillTreeUIState>,
+    tree: Res<SkillTree>,
+    player_query: Query<(&Player, &PlayerSkills)>,
+    mut unlock_events: MessageWriter<UnlockSkillEvent>,
+    mut interaction_query: Query<
+        (&Interaction, &SkillButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+) {
+    if !ui_state.is_visible {
+        return;
+    }
+    let Ok((player, skills)) = player_query.single() else { return; };
+
+    for (interaction, button, mut color) in interaction_query.iter_mut() {
+        let Some(definition) = tree.get(&button.skill_id) else { continue; };
+        let available = skills.can_unlock(definition, player.level).is_ok();
+
+        match *interaction {
+            Interaction::Pressed => {
+                if available {
+                    unlock_events.write(UnlockSkillEvent {
+                        skill_id: button.skill_id.clone(),
+                    });
+                }
+            }
+            Interaction::Hovered if available => {
+                color.0 = HOVERED_COLOR;
+            }
+            Interaction::Hovered | Interaction::None => {
+                color.0 = if skills.is_unlocked(&definition.id) {
+                    UNLOCKED_COLOR
+                } else if available {
+                    AVAILABLE_COLOR
+                } else {
+                    LOCKED_COLOR
+                };
+            }
+        }
+    }
+}
+
+/// 离开游戏（结算、菜单）时关闭面板
+fn close_skill_panel(
+    mut ui_state: ResMut<SkillTreeUIState>,
+    mut focus: ResMut<UiFocus>,
+    mut commands: Commands,
+    existing_panel: Query<Entity, With<SkillPanel>>,
+) {
+    ui_state.is_visible = false;
+    focus.set_wheel(FOCUS_NAME, false);
+    for entity in existing_panel.iter() {
+        commands.entity(entity).despawn();
+    }
+}